@@ -0,0 +1,74 @@
+use poise::serenity_prelude as serenity;
+use std::io::Write;
+use crate::types::BotState;
+use tracing::warn;
+
+const AUDIT_LOG_PATH: &str = "audit_log.jsonl";
+// Item number recorded for drafts, which have none (issue and PR numbers start at 1)
+pub const DRAFT_ITEM_NUMBER: i64 = 0;
+
+// One successful project edit. Stored as a JSON line in the audit log.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct AuditEntry {
+    pub timestamp: i64, // Unix seconds
+    pub discord_id: u64,
+    pub github_user: Option<String>,
+    pub project_id: String,
+    pub project_title: String,
+    pub item_number: i64, // DRAFT_ITEM_NUMBER for drafts, which have no number
+    pub field: String,
+    pub old_value: Option<String>, // None when the edit path didn't know the previous value
+    pub new_value: String,
+}
+
+impl AuditEntry {
+    pub fn describe(&self) -> String {
+        let who = self.github_user.clone().unwrap_or_else(|| format!("<@{}>", self.discord_id));
+        let item = if self.item_number == DRAFT_ITEM_NUMBER { "a draft".to_string() } else { format!("#{}", self.item_number) };
+        format!(
+            "<t:{}:R> **{}** set **{}** on {}: ` {} ` ➔ ` {} `",
+            self.timestamp, who, self.field, item,
+            self.old_value.as_deref().unwrap_or("?"), self.new_value
+        )
+    }
+}
+
+// Appends the entry to the log file and mirrors it to the audit channel if one is configured.
+// Failures are logged, never returned: an edit that already happened shouldn't be reported as failed.
+pub async fn record_edit(http: &serenity::Http, state: &BotState, entry: AuditEntry) {
+    let line = match serde_json::to_string(&entry) {
+        Ok(l) => l,
+        Err(e) => { warn!("⚠️ Failed to serialize audit entry: {}", e); return; }
+    };
+    let written = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(AUDIT_LOG_PATH)
+        .and_then(|mut f| writeln!(f, "{}", line));
+    if let Err(e) = written {
+        warn!("⚠️ Failed to write audit log: {}", e);
+    }
+
+    if let Some(channel) = state.audit_channel {
+        let embed = serenity::CreateEmbed::new()
+            .title(format!("📝 Edit in {}", entry.project_title))
+            .description(entry.describe())
+            .color(0x5865F2)
+            .timestamp(serenity::Timestamp::now());
+        if let Err(e) = channel.send_message(http, serenity::CreateMessage::new().embed(embed)).await {
+            warn!("⚠️ Failed to post to audit channel: {}", e);
+        }
+    }
+}
+
+// Most recent entries (newest first) for a project
+pub fn recent_edits(project_id: &str, limit: usize) -> Vec<AuditEntry> {
+    let content = std::fs::read_to_string(AUDIT_LOG_PATH).unwrap_or_default();
+    let mut entries: Vec<AuditEntry> = content.lines()
+        .filter_map(|l| serde_json::from_str::<AuditEntry>(l).ok())
+        .filter(|e| e.project_id == project_id)
+        .collect();
+    entries.reverse();
+    entries.truncate(limit);
+    entries
+}
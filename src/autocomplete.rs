@@ -1,4 +1,22 @@
-use crate::types::Context;
+use poise::serenity_prelude as serenity;
+use crate::types::{CachedProject, Context};
+use crate::cache::{repo_issues, repo_labels, repo_branches, repo_discussion_categories};
+use crate::utils::{is_valid_repo_name, truncate_to};
+
+// Value of another (already filled) option of the command being autocompleted.
+// Uses the leaf options poise resolves, so it also works inside subcommands.
+pub fn sibling_option(ctx: Context<'_>, name: &str) -> Option<String> {
+    if let poise::Context::Application(app_ctx) = ctx {
+        for opt in app_ctx.args {
+            if opt.name == name {
+                if let serenity::ResolvedValue::String(val) = opt.value {
+                    return Some(val.to_string());
+                }
+            }
+        }
+    }
+    None
+}
 
 pub async fn repo_autocomplete<'a>(
     ctx: Context<'_>,
@@ -9,12 +27,24 @@ pub async fn repo_autocomplete<'a>(
     
     let partial = partial.to_lowercase();
     repos.iter()
-        .map(|r| r.name.clone())
+        .map(|r| r.key(&repos))
         .filter(move |name| name.to_lowercase().contains(&partial))
         .collect::<Vec<_>>()
         .into_iter()
 }
 
+pub async fn org_autocomplete<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let partial = partial.to_lowercase();
+    ctx.data().github_orgs.iter()
+        .filter(|o| o.to_lowercase().contains(&partial))
+        .cloned()
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
 pub async fn project_autocomplete<'a>(
     ctx: Context<'_>,
     partial: &'a str,
@@ -30,29 +60,43 @@ pub async fn project_autocomplete<'a>(
         .into_iter()
 }
 
+// Status options of the project picked in the "title" option
+pub async fn status_autocomplete<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let title = sibling_option(ctx, "title").unwrap_or_default();
+    let projects = ctx.data().projects.read().await;
+    let partial = partial.to_lowercase();
+    let mut options: Vec<String> = projects.iter()
+        .find(|p| p.title.eq_ignore_ascii_case(&title))
+        .and_then(|p| p.fields.iter().find(|f| f.name.eq_ignore_ascii_case("Status")))
+        .map(|f| f.options.keys().filter(|o| o.to_lowercase().contains(&partial)).cloned().collect())
+        .unwrap_or_default();
+    options.sort();
+    options.into_iter()
+}
+
+// Open items of the project picked in the "title" option; across all projects until one is picked
 pub async fn item_autocomplete<'a>(
     ctx: Context<'_>,
     partial: &'a str,
 ) -> impl Iterator<Item = String> + 'a {
+    let title = sibling_option(ctx, "title");
     let state = ctx.data();
     let projects = state.projects.read().await;
-    
-    // Try to find the 'title' argument value from the interaction context
-    // This is heuristic-based as Poise hides raw interaction details a bit in autocomplete
-    // but often we can just search ALL cached items if partial is distinctive enough
-    // OR we scan through projects to see if one matches the context.
-    
-    // If we assume the user already picked a project (or typed it), we can try to find it.
-    // However, Poise/Discord doesn't send sibling options reliably in autocomplete trigger.
-    // A better UX pattern: Search across ALL projects' items.
+    let selected = title.and_then(|t| projects.iter().find(|p| p.title.eq_ignore_ascii_case(&t)));
+    let scope: Vec<&CachedProject> = match selected {
+        Some(p) => vec![p],
+        None => projects.iter().collect(),
+    };
     
     let partial_lower = partial.to_lowercase();
     let mut suggestions = Vec::new();
     
-    for proj in projects.iter() {
+    for proj in scope {
         for item in &proj.items {
             let label = format!("{} #{}", item.repo_name, item.number);
-            let _val = format!("{} #{}", item.repo_name, item.number); // Value to return
             
             // Match against "123", "repo #123", "title"
             if label.to_lowercase().contains(&partial_lower) || 
@@ -63,14 +107,16 @@ pub async fn item_autocomplete<'a>(
                 let is_closed = item.state.eq_ignore_ascii_case("CLOSED") || item.state.eq_ignore_ascii_case("MERGED");
                 if is_closed { continue; }
 
-                // Format: "Repo #123: Title (Project)"
-                let display = format!("{} #{}: {} ({})", item.repo_name, item.number, 
-                    if item.title.len() > 30 { format!("{}...", &item.title[..30]) } else { item.title.clone() },
-                    if proj.title.len() > 15 { format!("{}...", &proj.title[..15]) } else { proj.title.clone() }
-                );
-                suggestions.push(display); // We return the display as value for now or we need a KV structure
-                                           // Discord autocomplete allows Name/Value pairs. Poise Iterator<String> uses Name=Value.
-                                           // We'll return the string the user should "send", which is "Repo #123"
+                let short_title = if item.title.len() > 30 { format!("{}...", truncate_to(&item.title, 30)) } else { item.title.clone() };
+                // "Repo #123: Title", plus "(Project)" when searching every project
+                let display = if selected.is_some() {
+                    format!("{} #{}: {}", item.repo_name, item.number, short_title)
+                } else {
+                    format!("{} #{}: {} ({})", item.repo_name, item.number, short_title,
+                        if proj.title.len() > 15 { format!("{}...", truncate_to(&proj.title, 15)) } else { proj.title.clone() }
+                    )
+                };
+                suggestions.push(display);
                                            
                 if suggestions.len() >= 25 { break; } 
             }
@@ -101,23 +147,55 @@ pub async fn user_autocomplete<'a>(
         .into_iter()
 }
 
+// Like user_autocomplete, for the last entry of a comma-separated list (earlier entries are kept)
+pub async fn user_list_autocomplete<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let (done, last) = match partial.rsplit_once(',') {
+        Some((done, last)) => (format!("{},", done), last.trim().to_lowercase()),
+        None => (String::new(), partial.trim().to_lowercase()),
+    };
+    let users = ctx.data().users.read().await;
+    users.iter()
+        .filter(|u| u.login.to_lowercase().contains(&last))
+        .map(|u| format!("{}{}", done, u.login))
+        .filter(|s| s.len() <= 100)
+        .take(25)
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+pub async fn team_autocomplete<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let state = ctx.data();
+    let teams = state.teams.read().await;
+
+    let partial = partial.to_lowercase();
+    teams.iter()
+        .filter(|t| t.slug.contains(&partial) || t.name.to_lowercase().contains(&partial))
+        .map(|t| t.slug.clone())
+        .take(25)
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+// Fields of the project picked in the "title" option; the union over all projects until one is picked
 pub async fn field_autocomplete<'a>(
     ctx: Context<'_>,
     partial: &'a str,
 ) -> impl Iterator<Item = String> + 'a {
+    let title = sibling_option(ctx, "title");
     let state = ctx.data();
     let projects = state.projects.read().await;
-    
-    // Heuristic: try to find which project the user selected.
-    // Poise doesn't give us easy access to other args in autocomplete.
-    // We'll return ALL unique fields across all projects or just common ones if we can't tell.
-    // Better: Filter by project if 'title' arg was filled? (Not possible easily)
-    // We'll just list all unique field names found in cache.
+    let selected = title.and_then(|t| projects.iter().find(|p| p.title.eq_ignore_ascii_case(&t)));
     
     let partial = partial.to_lowercase();
-    let mut fields = std::collections::HashSet::new();
+    let mut fields = std::collections::BTreeSet::new();
     
-    for p in projects.iter() {
+    for p in projects.iter().filter(|p| selected.is_none_or(|s| s.id == p.id)) {
         for f in &p.fields {
             fields.insert(f.name.clone());
         }
@@ -125,6 +203,7 @@ pub async fn field_autocomplete<'a>(
     
     fields.into_iter()
         .filter(move |name| name.to_lowercase().contains(&partial))
+        .take(25)
         .collect::<Vec<_>>()
         .into_iter()
 }
@@ -141,17 +220,7 @@ pub async fn value_autocomplete<'a>(
     let state = ctx.data();
     
     // 1. Determine selected field from interaction context (no lock needed yet)
-    let mut selected_field_name = None;
-    if let poise::Context::Application(app_ctx) = ctx {
-        let data = &app_ctx.interaction.data;
-        for opt in &data.options {
-            if opt.name == "field" {
-                if let Some(val) = opt.value.as_str() {
-                    selected_field_name = Some(val.to_string());
-                }
-            }
-        }
-    }
+    let selected_field_name = sibling_option(ctx, "field");
 
     // 2. Fetch options efficiently (minimize lock time)
     let options = {
@@ -168,7 +237,7 @@ pub async fn value_autocomplete<'a>(
                 if let Some(f) = p.fields.iter().find(|f| f.name.to_lowercase() == field_name_lower) {
                     match f.data_type.as_str() {
                         "SINGLE_SELECT" | "ITERATION" | "STATUS" => {
-                            for (opt_name, _) in &f.options {
+                            for opt_name in f.options.keys() {
                                 if seen.insert(opt_name.clone()) {
                                     opts.push(opt_name.clone());
                                 }
@@ -199,4 +268,94 @@ pub async fn value_autocomplete<'a>(
     }
     
     suggestions.into_iter()
-}
\ No newline at end of file
+}
+pub async fn issue_autocomplete<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let selected_repo = sibling_option(ctx, "repo");
+
+    let mut suggestions = Vec::new();
+    if let Some(repo) = selected_repo.filter(|r| is_valid_repo_name(r)) {
+        let partial = partial.to_lowercase();
+        // Value is "#123: Title" so it resolves back to a number without another lookup
+        suggestions = repo_issues(ctx.data(), &repo).await.into_iter()
+            .filter(|i| i.title.to_lowercase().contains(&partial) || i.number.to_string().contains(&partial))
+            .map(|i| format!("#{}: {}", i.number, i.title).chars().take(100).collect::<String>())
+            .take(25)
+            .collect();
+    }
+
+    if suggestions.is_empty() && !partial.is_empty() {
+        suggestions.push(partial.to_string());
+    }
+    suggestions.into_iter()
+}
+
+pub async fn label_autocomplete<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let mut suggestions = Vec::new();
+    if let Some(repo) = sibling_option(ctx, "repo").filter(|r| is_valid_repo_name(r)) {
+        let partial = partial.to_lowercase();
+        suggestions = repo_labels(ctx.data(), &repo).await.into_iter()
+            .filter(|l| l.to_lowercase().contains(&partial))
+            .take(25)
+            .collect();
+    }
+
+    if suggestions.is_empty() && !partial.is_empty() {
+        suggestions.push(partial.to_string());
+    }
+    suggestions.into_iter()
+}
+
+// Branches of the repo picked in the "repo" option (default branch first), e.g. /pr list base
+pub async fn branch_autocomplete<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let mut suggestions = Vec::new();
+    if let Some(repo) = sibling_option(ctx, "repo").filter(|r| is_valid_repo_name(r)) {
+        let partial = partial.to_lowercase();
+        suggestions = repo_branches(ctx.data(), &repo).await.into_iter()
+            .filter(|b| b.to_lowercase().contains(&partial))
+            .take(25)
+            .collect();
+    }
+
+    if suggestions.is_empty() && !partial.is_empty() {
+        suggestions.push(partial.to_string());
+    }
+    suggestions.into_iter()
+}
+
+// The caller's own saved /proj view presets
+pub async fn preset_autocomplete<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let partial = partial.to_lowercase();
+    let presets = ctx.data().presets.read().await;
+    presets.users.get(&ctx.author().id.get())
+        .map(|p| p.keys().filter(|name| name.contains(&partial)).take(25).cloned().collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+}
+
+pub async fn discussion_category_autocomplete<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let mut suggestions = Vec::new();
+    if let Some(repo) = sibling_option(ctx, "repo").filter(|r| is_valid_repo_name(r)) {
+        let partial = partial.to_lowercase();
+        suggestions = repo_discussion_categories(ctx.data(), &repo).await.into_iter()
+            .map(|(name, _)| name)
+            .filter(|name| name.to_lowercase().contains(&partial))
+            .take(25)
+            .collect();
+    }
+    suggestions.into_iter()
+}
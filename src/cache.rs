@@ -1,116 +1,308 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use crate::types::{BotState, CachedRepo, CachedUser, CachedProject, CachedItem, CachedField, Error};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use crate::stats::Api;
+use crate::github::{all_pages, graphql, fetch_project_items};
+use crate::utils::repo_name;
+use crate::types::{BotState, CachedRepo, CachedUser, CachedTeam, CachedProject, CachedItem, CachedField, CachedIssue, Error};
+use tracing::{info, warn};
 
-pub async fn refresh_cache(state: &Arc<BotState>) -> Result<(), Error> {
-    println!("🔄 Refreshing GitHub cache...");
-    let org = &state.github_org;
+// Branches move faster than the rest of the cache
+const BRANCH_TTL: Duration = Duration::from_secs(300);
+const CACHE_PATH: &str = "cache.json";
+
+// What survives a restart, so autocomplete works while the first refresh is still running
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+pub struct CacheSnapshot {
+    pub repos: Vec<CachedRepo>,
+    pub users: Vec<CachedUser>,
+    pub projects: Vec<CachedProject>,
+}
+
+impl CacheSnapshot {
+    pub fn load() -> Self {
+        if let Ok(content) = std::fs::read_to_string(CACHE_PATH) {
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    async fn save(state: &BotState) {
+        let snapshot = CacheSnapshot {
+            repos: state.repos.read().await.clone(),
+            users: state.users.read().await.clone(),
+            projects: state.projects.read().await.clone(),
+        };
+        let content = match serde_json::to_string(&snapshot) {
+            Ok(content) => content,
+            Err(e) => { warn!("⚠️ Failed to serialize cache: {}", e); return; }
+        };
+        // Write then rename, so a crash mid-write can't leave a truncated file that load() would discard
+        let tmp = format!("{}.tmp", CACHE_PATH);
+        if let Err(e) = std::fs::write(&tmp, content).and_then(|_| std::fs::rename(&tmp, CACHE_PATH)) {
+            warn!("⚠️ Failed to write {}: {}", CACHE_PATH, e);
+        }
+    }
+}
+
+// The separately refreshed parts of the cache, as keyed in BotState::last_refreshed
+pub const CACHE_PARTS: [&str; 3] = ["repos", "users", "projects"];
+
+async fn mark_refreshed(state: &BotState, part: &str) {
+    state.last_refreshed.write().await.insert(part.to_string(), chrono::Utc::now());
+}
+
+// When each cache part last finished refreshing, None if it hasn't since startup
+pub async fn refresh_times(state: &BotState) -> Vec<(&'static str, Option<chrono::DateTime<chrono::Utc>>)> {
+    let times = state.last_refreshed.read().await;
+    CACHE_PARTS.iter().map(|part| (*part, times.get(*part).copied())).collect()
+}
+
+// Ok(false) when some org's repos, members or projects couldn't be fetched (their old data is kept)
+#[tracing::instrument(skip_all)]
+pub async fn refresh_cache(state: &Arc<BotState>) -> Result<bool, Error> {
+    info!("🔄 Refreshing GitHub cache...");
+    let repos_complete = refresh_repos(state).await?;
+    let users_complete = refresh_users(state).await?;
+    let projects_complete = refresh_projects(state).await?;
+    // A partial refresh keeps some stale data in memory; don't let it replace a good snapshot
+    let complete = repos_complete && users_complete && projects_complete;
+    if complete {
+        CacheSnapshot::save(state).await;
+    } else {
+        warn!("⚠️ Cache refresh was incomplete, not updating {}", CACHE_PATH);
+    }
+    Ok(complete)
+}
+
+// Repos of every configured org, plus dropping the per-repo lists (issues, labels, ...) so they get refetched.
+// An org whose fetch fails keeps its previous repos. Ok(false) when that happened for any org.
+pub async fn refresh_repos(state: &BotState) -> Result<bool, Error> {
     let octocrab = &state.octocrab;
 
-    // 1. Fetch Repos
     let mut all_repos = Vec::new();
-    match octocrab.orgs(org).list_repos().per_page(100).send().await {
-        Ok(page) => {
-            match octocrab.all_pages(page).await {
-                Ok(repos) => {
-                     all_repos = repos.into_iter().map(|r| CachedRepo {
-                        name: r.name,
-                        full_name: r.full_name.unwrap_or_default(),
-                    }).collect();
+    let mut complete = true;
+    for org in &state.github_orgs {
+        state.api_stats.record("refresh", Api::Rest);
+        let repos = match octocrab.orgs(org).list_repos().per_page(100).send().await {
+            Ok(page) => {
+                match all_pages(state, "refresh", "repo list", page).await {
+                    Ok(repos) => Some(repos),
+                    Err(e) => {
+                        warn!("⚠️ Failed to paginate repos of {}: {}", org, e);
+                        None
+                    }
                 }
-                Err(e) => println!("⚠️ Failed to paginate repos: {}", e),
+            }
+            Err(e) => {
+                warn!("⚠️ Failed to fetch repos of {}: {}", org, e);
+                None
+            }
+        };
+        match repos {
+            Some(repos) => {
+                all_repos.extend(repos.into_iter().map(|r| CachedRepo {
+                    org: org.clone(),
+                    name: r.name,
+                    full_name: r.full_name.unwrap_or_default(),
+                    default_branch: r.default_branch,
+                }));
+                state.repos_loaded.store(true, Ordering::Relaxed);
+            }
+            None => {
+                complete = false;
+                all_repos.extend(state.repos.read().await.iter().filter(|r| &r.org == org).cloned());
             }
         }
-        Err(e) => println!("⚠️ Failed to fetch repos: {}", e),
     }
     *state.repos.write().await = all_repos;
-    println!("✅ Cached {} repos", state.repos.read().await.len());
+    info!("✅ Cached {} repos", state.repos.read().await.len());
+
+    // Issue and label lists are fetched lazily per repo; drop them so they get refetched
+    state.issues.write().await.clear();
+    state.labels.write().await.clear();
+    state.branches.write().await.clear();
+    state.discussion_categories.write().await.clear();
+    mark_refreshed(state, "repos").await;
+    Ok(complete)
+}
+
+// Members, outside collaborators and teams of every configured org. A login in several
+// orgs is cached once, tagged with the first of them. An org whose member list fails keeps its
+// previous users; Ok(false) when that happened for any org. Collaborators often need admin
+// rights, so failing to list them doesn't count.
+pub async fn refresh_users(state: &BotState) -> Result<bool, Error> {
+    let octocrab = &state.octocrab;
 
-    // 2. Fetch Members (Users) & Outside Collaborators
+    // A. Members & Outside Collaborators
     // Using all_pages to ensure we get everyone, and merging members + collaborators
     let mut all_users_map: HashMap<String, CachedUser> = HashMap::new();
-    
-    // A. Members
-    match octocrab.orgs(org).list_members().per_page(100).send().await {
-        Ok(page) => {
-            match octocrab.all_pages(page).await {
-                Ok(users) => {
-                    for u in users {
-                        all_users_map.insert(u.login.clone(), CachedUser {
-                            login: u.login,
-                            avatar_url: u.avatar_url.to_string(),
-                        });
+    let mut all_teams = Vec::new();
+    let mut complete = true;
+
+    for org in &state.github_orgs {
+        // Members
+        let mut members_ok = true;
+        state.api_stats.record("refresh", Api::Rest);
+        match octocrab.orgs(org).list_members().per_page(100).send().await {
+            Ok(page) => {
+                match all_pages(state, "refresh", "member list", page).await {
+                    Ok(users) => {
+                        for u in users {
+                            all_users_map.entry(u.login.clone()).or_insert(CachedUser {
+                                org: org.clone(),
+                                login: u.login,
+                                avatar_url: u.avatar_url.to_string(),
+                            });
+                        }
+                    }
+                    Err(e) => {
+                        warn!("⚠️ Failed to paginate members of {}: {}", org, e);
+                        members_ok = false;
                     }
                 }
-                Err(e) => println!("⚠️ Failed to paginate members: {}", e),
+            }
+            Err(e) => {
+                warn!("⚠️ Failed to fetch members of {} (check read:org scope): {}", org, e);
+                members_ok = false;
+            }
+        }
+        if !members_ok {
+            complete = false;
+            for u in state.users.read().await.iter().filter(|u| &u.org == org) {
+                all_users_map.entry(u.login.clone()).or_insert_with(|| u.clone());
             }
         }
-        Err(e) => println!("⚠️ Failed to fetch members (check read:org scope): {}", e),
-    }
 
-    // B. Outside Collaborators (Manual request as helper might be missing/named differently)
-    // Endpoint: /orgs/{org}/outside_collaborators
-    let route = format!("/orgs/{}/outside_collaborators", org);
-    match octocrab.get::<octocrab::Page<octocrab::models::Author>, _, _>(route, Some(&[("per_page", "100")])).await {
-        Ok(page) => {
-             match octocrab.all_pages(page).await {
-                Ok(users) => {
-                    for u in users {
-                        all_users_map.entry(u.login.clone()).or_insert(CachedUser {
-                            login: u.login,
-                            avatar_url: u.avatar_url.to_string(),
-                        });
+        // Outside Collaborators (Manual request as helper might be missing/named differently)
+        // Endpoint: /orgs/{org}/outside_collaborators
+        let route = format!("/orgs/{}/outside_collaborators", org);
+        state.api_stats.record("refresh", Api::Rest);
+        match octocrab.get::<octocrab::Page<octocrab::models::Author>, _, _>(route, Some(&[("per_page", "100")])).await {
+            Ok(page) => {
+                 match all_pages(state, "refresh", "collaborator list", page).await {
+                    Ok(users) => {
+                        for u in users {
+                            all_users_map.entry(u.login.clone()).or_insert(CachedUser {
+                                org: org.clone(),
+                                login: u.login,
+                                avatar_url: u.avatar_url.to_string(),
+                            });
+                        }
                     }
+                    Err(e) => warn!("⚠️ Failed to paginate collaborators of {}: {}", org, e),
                 }
-                Err(e) => println!("⚠️ Failed to paginate collaborators: {}", e),
             }
+            Err(e) => warn!("⚠️ Failed to fetch collaborators of {} (might need admin:org or just ignored): {}", org, e),
+        }
+
+        // B. Teams (members are fetched when a team is looked at). An org whose fetch fails keeps its previous teams.
+        state.api_stats.record("refresh", Api::Rest);
+        let teams = match octocrab.teams(org).list().per_page(100).send().await {
+            Ok(page) => {
+                match all_pages(state, "refresh", "team list", page).await {
+                    Ok(teams) => Some(teams),
+                    Err(e) => {
+                        warn!("⚠️ Failed to paginate teams of {}: {}", org, e);
+                        None
+                    }
+                }
+            }
+            Err(e) => {
+                warn!("⚠️ Failed to fetch teams of {} (check read:org scope): {}", org, e);
+                None
+            }
+        };
+        match teams {
+            Some(teams) => all_teams.extend(teams.into_iter().map(|t| CachedTeam { org: org.clone(), slug: t.slug, name: t.name })),
+            None => all_teams.extend(state.teams.read().await.iter().filter(|t| &t.org == org).cloned()),
         }
-        Err(e) => println!("⚠️ Failed to fetch collaborators (might need admin:org or just ignored): {}", e),
     }
 
     let all_users: Vec<CachedUser> = all_users_map.into_values().collect();
     *state.users.write().await = all_users;
-    println!("✅ Cached {} users (Members + Collaborators)", state.users.read().await.len());
+    info!("✅ Cached {} users (Members + Collaborators)", state.users.read().await.len());
 
-    // 3. Fetch Projects (GraphQL for V2)
-    // Fetch items inside the project for autocomplete
+    *state.teams.write().await = all_teams;
+    info!("✅ Cached {} teams", state.teams.read().await.len());
+    mark_refreshed(state, "users").await;
+    Ok(complete)
+}
+
+// Projects V2 of every configured org. An org whose fetch fails keeps its previous projects;
+// Ok(false) when that happened for any org.
+pub async fn refresh_projects(state: &BotState) -> Result<bool, Error> {
+    let mut all_projects = Vec::new();
+    let mut any_loaded = false;
+    let mut complete = true;
+    for org in &state.github_orgs {
+        match fetch_org_projects(state, org).await {
+            Some(projects) => {
+                all_projects.extend(projects);
+                any_loaded = true;
+            }
+            None => {
+                complete = false;
+                all_projects.extend(state.projects.read().await.iter().filter(|p| &p.org == org).cloned());
+            }
+        }
+    }
+    *state.projects.write().await = all_projects;
+    if any_loaded {
+        state.projects_loaded.store(true, Ordering::Relaxed);
+    }
+    info!("✅ Cached {} projects (V2)", state.projects.read().await.len());
+    mark_refreshed(state, "projects").await;
+    Ok(complete)
+}
+
+// What the cache keeps of each project item (for autocomplete and node id lookups)
+const CACHE_ITEM_FIELDS: &str = r#"
+    id
+    content {
+        ... on Issue { title number repository { name } state assignees(first: 5) { nodes { login } } }
+        ... on PullRequest { title number repository { name } state assignees(first: 5) { nodes { login } } }
+    }
+"#;
+
+// Projects V2 with their fields and items (GraphQL, the REST API doesn't cover V2).
+// Items are fetched for autocomplete. None when the fetch failed (logged).
+#[tracing::instrument(skip(state))]
+async fn fetch_org_projects(state: &BotState, org: &str) -> Option<Vec<CachedProject>> {
+    // Org goes through a GraphQL variable, never interpolated into the query text
     let query = serde_json::json!({
-        "query": format!(r#"
-            query {{
-                organization(login: "{}") {{
-                    projectsV2(first: 20) {{
-                        nodes {{
+        "query": r#"
+            query($org: String!) {
+                organization(login: $org) {
+                    projectsV2(first: 20) {
+                        nodes {
                             id
                             title
                             url
                             number
-                            fields(first: 20) {{
-                                nodes {{
-                                    ... on ProjectV2FieldCommon {{ id name dataType }}
-                                    ... on ProjectV2SingleSelectField {{
-                                        id name dataType options {{ id name }}
-                                    }}
-                                    ... on ProjectV2IterationField {{
-                                        id name dataType configuration {{ iterations {{ id title }} }}
-                                    }}
-                                }}
-                            }}
-                            items(first: 50) {{
-                                nodes {{
-                                    content {{
-                                        ... on Issue {{ title number repository {{ name }} state }}
-                                        ... on PullRequest {{ title number repository {{ name }} state }}
-                                    }}
-                                }}
-                            }}
-                        }}
-                    }}
-                }}
-            }}
-        "#, org)
+                            shortDescription
+                            fields(first: 20) {
+                                nodes {
+                                    ... on ProjectV2FieldCommon { id name dataType }
+                                    ... on ProjectV2SingleSelectField {
+                                        id name dataType options { id name }
+                                    }
+                                    ... on ProjectV2IterationField {
+                                        id name dataType configuration { iterations { id title } }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        "#,
+        "variables": { "org": org }
     });
 
-    match octocrab.graphql(&query).await {
+    match graphql(state, "refresh", &query).await {
         Ok(resp) => {
              // Parse generic JSON response manually to avoid complex struct definitions
              let json_resp: serde_json::Value = resp;
@@ -159,46 +351,164 @@ pub async fn refresh_cache(state: &Arc<BotState>) -> Result<(), Error> {
                              }
                          }
 
-                         // Extract cached items if available in the same query (we need to update query)
+                         // Items page through their own query so big boards are complete
                          let mut items = Vec::new();
-                         if let Some(nodes) = p.get("items").and_then(|i| i.get("nodes")).and_then(|n| n.as_array()) {
-                             for item in nodes {
-                                 let content = item.get("content");
-                                 if let (Some(i_title), Some(i_num), Some(repo)) = (
-                                     content.and_then(|c| c.get("title")).and_then(|s| s.as_str()),
-                                     content.and_then(|c| c.get("number")).and_then(|n| n.as_i64()),
-                                     content.and_then(|c| c.get("repository")).and_then(|r| r.get("name")).and_then(|s| s.as_str())
-                                 ) {
-                                     let state = content.and_then(|c| c.get("state")).and_then(|s| s.as_str()).unwrap_or("OPEN");
-                                     items.push(CachedItem {
-                                         title: i_title.to_string(),
-                                         number: i_num,
-                                         repo_name: repo.to_string(),
-                                         state: state.to_string(),
-                                     });
+                         match fetch_project_items(state, "refresh", id, CACHE_ITEM_FIELDS).await {
+                             Ok(nodes) => {
+                                 for item in &nodes {
+                                     let content = item.get("content");
+                                     if let (Some(i_title), Some(i_num), Some(repo)) = (
+                                         content.and_then(|c| c.get("title")).and_then(|s| s.as_str()),
+                                         content.and_then(|c| c.get("number")).and_then(|n| n.as_i64()),
+                                         content.and_then(|c| c.get("repository")).and_then(|r| r.get("name")).and_then(|s| s.as_str())
+                                     ) {
+                                         let state = content.and_then(|c| c.get("state")).and_then(|s| s.as_str()).unwrap_or("OPEN");
+                                         items.push(CachedItem {
+                                             id: item.get("id").and_then(|s| s.as_str()).unwrap_or("").to_string(),
+                                             title: i_title.to_string(),
+                                             number: i_num,
+                                             repo_name: repo.to_string(),
+                                             state: state.to_string(),
+                                             assignees: content.and_then(|c| c.get("assignees")).and_then(|a| a.get("nodes")).and_then(|n| n.as_array())
+                                                 .map(|arr| arr.iter().filter_map(|a| a.get("login").and_then(|l| l.as_str()).map(|l| l.to_string())).collect())
+                                                 .unwrap_or_default(),
+                                         });
+                                     }
+                                 }
+                             }
+                             Err(e) => {
+                                 // Keep the last refresh's items rather than emptying autocomplete
+                                 warn!("⚠️ Failed to fetch items of project {}: {}", title, e);
+                                 if let Some(old) = state.projects.read().await.iter().find(|old| old.id == id) {
+                                     items = old.items.clone();
                                  }
                              }
                          }
-                         
+
                          parsed_projects.push(CachedProject {
+                             org: org.to_string(),
                              id: id.to_string(),
                              title: title.to_string(),
                              url: url.to_string(),
                              number,
+                             short_description: p.get("shortDescription").and_then(|s| s.as_str()).filter(|s| !s.is_empty()).map(|s| s.to_string()),
                              items,
                              fields,
                          });
                      }
                  }
                  
-                 *state.projects.write().await = parsed_projects;
-                 println!("✅ Cached {} projects (V2)", state.projects.read().await.len());
+                 return Some(parsed_projects);
              } else {
-                 println!("⚠️ GraphQL response structure mismatch for Projects V2");
+                 warn!("⚠️ GraphQL response structure mismatch for Projects V2 in {}", org);
              }
         }
-        Err(e) => println!("⚠️ Failed to fetch projects via GraphQL: {}", e),
+        Err(e) => warn!("⚠️ Failed to fetch projects of {} via GraphQL: {}", org, e),
     }
 
-    Ok(())
-}
\ No newline at end of file
+    None
+}
+// Open issues of a repo, fetched on first use and kept until the next full refresh
+pub async fn repo_issues(state: &BotState, repo: &str) -> Vec<CachedIssue> {
+    if let Some(issues) = state.issues.read().await.get(repo) {
+        return issues.clone();
+    }
+
+    state.api_stats.record("cache", Api::Rest);
+    let issues: Vec<CachedIssue> = match state.octocrab.issues(state.repo_org(repo).await, repo_name(repo)).list().state(octocrab::params::State::Open).per_page(100).send().await {
+        Ok(page) => page.items.into_iter()
+            .filter(|i| i.pull_request.is_none())
+            .map(|i| CachedIssue { number: i.number, title: i.title })
+            .collect(),
+        Err(e) => {
+            warn!("⚠️ Failed to fetch issues for {}: {}", repo, e);
+            return Vec::new();
+        }
+    };
+
+    state.issues.write().await.insert(repo.to_string(), issues.clone());
+    issues
+}
+
+// Label names of a repo, fetched on first use and kept until the next full refresh
+pub async fn repo_labels(state: &BotState, repo: &str) -> Vec<String> {
+    if let Some(labels) = state.labels.read().await.get(repo) {
+        return labels.clone();
+    }
+
+    state.api_stats.record("cache", Api::Rest);
+    let labels: Vec<String> = match state.octocrab.issues(state.repo_org(repo).await, repo_name(repo)).list_labels_for_repo().per_page(100).send().await {
+        Ok(page) => page.items.into_iter().map(|l| l.name).collect(),
+        Err(e) => {
+            warn!("⚠️ Failed to fetch labels for {}: {}", repo, e);
+            return Vec::new();
+        }
+    };
+
+    state.labels.write().await.insert(repo.to_string(), labels.clone());
+    labels
+}
+
+// Branch names of a repo (default branch first), fetched on first use and refetched once older than BRANCH_TTL
+pub async fn repo_branches(state: &BotState, repo: &str) -> Vec<String> {
+    if let Some((fetched_at, branches)) = state.branches.read().await.get(repo) {
+        if fetched_at.elapsed() < BRANCH_TTL {
+            return branches.clone();
+        }
+    }
+
+    state.api_stats.record("cache", Api::Rest);
+    let mut branches: Vec<String> = match state.octocrab.repos(state.repo_org(repo).await, repo_name(repo)).list_branches().per_page(100).send().await {
+        Ok(page) => page.items.into_iter().map(|b| b.name).collect(),
+        Err(e) => {
+            warn!("⚠️ Failed to fetch branches for {}: {}", repo, e);
+            return Vec::new();
+        }
+    };
+
+    let default_branch = state.repos.read().await.iter().find(|r| r.matches(repo)).and_then(|r| r.default_branch.clone());
+    if let Some(default) = default_branch {
+        if let Some(pos) = branches.iter().position(|b| *b == default) {
+            let b = branches.remove(pos);
+            branches.insert(0, b);
+        }
+    }
+
+    state.branches.write().await.insert(repo.to_string(), (Instant::now(), branches.clone()));
+    branches
+}
+
+// Discussion categories (name, node id) of a repo, fetched on first use and kept until the next full refresh.
+// Empty when Discussions are disabled for the repo.
+pub async fn repo_discussion_categories(state: &BotState, repo: &str) -> Vec<(String, String)> {
+    if let Some(categories) = state.discussion_categories.read().await.get(repo) {
+        return categories.clone();
+    }
+
+    let query = serde_json::json!({
+        "query": r#"
+            query($owner: String!, $name: String!) {
+                repository(owner: $owner, name: $name) {
+                    discussionCategories(first: 25) { nodes { id name } }
+                }
+            }
+        "#,
+        "variables": { "owner": state.repo_org(repo).await, "name": repo_name(repo) }
+    });
+    let categories: Vec<(String, String)> = match graphql(state, "cache", &query).await {
+        Ok(resp) => resp.get("data").and_then(|d| d.get("repository")).and_then(|r| r.get("discussionCategories"))
+            .and_then(|c| c.get("nodes")).and_then(|n| n.as_array())
+            .map(|nodes| nodes.iter().filter_map(|c| Some((
+                c.get("name")?.as_str()?.to_string(),
+                c.get("id")?.as_str()?.to_string(),
+            ))).collect())
+            .unwrap_or_default(),
+        Err(e) => {
+            warn!("⚠️ Failed to fetch discussion categories for {}: {}", repo, e);
+            return Vec::new();
+        }
+    };
+
+    state.discussion_categories.write().await.insert(repo.to_string(), categories.clone());
+    categories
+}
@@ -1,42 +1,96 @@
 use poise::serenity_prelude as serenity;
-use crate::types::{Context, Error};
-use crate::utils::{check_auth, build_item_embed};
+use poise::ChoiceParameter;
+use crate::custom_id::CustomId;
+use crate::types::{BotError, BotState, CachedField, CachedRepo, CachedItem, CachedProject, Context, Error, MergeMethod, RefreshScope, ReviewEvent, StateFilter, UserToken};
+use crate::guild_config::{CommandGroup, DefaultBoard, LabelCategory, WRITE_COMMANDS};
+use crate::utils::{check_auth, connect_button, defer, parse_duration, md_link, validate_field_value, defer_ephemeral, check_repo_name, check_login, is_valid_login, find_project, resolve_issue_number, parse_item_number, item_field_value, body_more_button, build_item_embed, truncate_to, MAX_MODAL_TEXT_LEN, build_issue_embed, build_pr_embed, parse_target_args, state_icon, project_item_line, repo_name, TargetDate};
 use crate::autocomplete::*;
+use crate::render::Rendered;
 use std::time::Duration;
-use serenity::all::{CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption};
-use crate::cache::refresh_cache;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use serenity::all::{CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, CreateModal, CreateInputText};
+use crate::cache::{refresh_cache, refresh_repos, refresh_users, refresh_projects, refresh_times, repo_discussion_categories, repo_labels};
+use crate::github::{all_pages, graphql, graphql_errors, graphql_mutation, search_issues, fetch_project_item, fetch_project_items, find_milestone, create_milestone, set_milestone_due, lock_item, add_project_item, current_field_value, is_not_found, remove_assignee, create_review, set_item_option, add_draft_item};
+use crate::audit::{record_edit, recent_edits, AuditEntry, DRAFT_ITEM_NUMBER};
+use crate::stats::{self, Api};
+use crate::presets::ViewPreset;
+use crate::oauth::{request_device_code, instructions_embed, complete_device_flow, link_token, ConnectOutcome};
+use tracing::{error, warn};
 
 // --- Commands ---
 
 /// Manage Repositories (Issues, PRs)
-#[poise::command(slash_command, subcommands("assign", "target", "list_repos", "list_issues"))]
+#[poise::command(slash_command, subcommands("assign", "unassign", "target", "list_repos", "list_issues", "view_repo_issue", "view_issue", "triage", "close_issue", "reopen_issue", "reopen_issue_legacy", "comment", "create_issue", "label_add", "label_remove", "discussions", "view_discussion"))]
 pub async fn repo(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
-/// Assign an issue to a user
+/// Assign an issue to one or more users
 #[poise::command(slash_command)]
 pub async fn assign(
     ctx: Context<'_>,
     #[description = "Repository name"] 
     #[autocomplete = "repo_autocomplete"]
     repo: String,
-    #[description = "Issue number"] number: u64,
-    #[description = "GitHub Username"] 
-    #[autocomplete = "user_autocomplete"]
+    #[description = "Issue number or title"]
+    #[autocomplete = "issue_autocomplete"]
+    issue: String,
+    #[description = "GitHub username(s), comma-separated"] 
+    #[autocomplete = "user_list_autocomplete"]
     user: String,
+    #[description = "Due date (YYYY-MM-DD or 'today'), set on the server's default board"]
+    due: Option<String>,
 ) -> Result<(), Error> {
     let _ = check_auth(ctx).await?; // Enforce auth
+    check_repo_name(&repo)?;
+    let due = match due.map(|d| validate_field_value("DATE", &d)).transpose() {
+        Ok(d) => d.and_then(|d| chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()).map(TargetDate::On),
+        Err(msg) => {
+            ctx.say(format!("❌ {}", msg)).await?;
+            return Ok(());
+        }
+    };
     let state = ctx.data();
-    let org = &state.github_org;
-    ctx.defer().await?;
+    let org = &state.repo_org(&repo).await;
+    let number = match resolve_issue_number(state, &repo, &issue).await {
+        Some(n) => n,
+        None => {
+            ctx.say(format!("❌ No open issue in **{}** matches `{}`.", repo, issue)).await?;
+            return Ok(());
+        }
+    };
+    let (logins, invalid) = split_assignees(state, &user).await;
+    if logins.is_empty() {
+        ctx.say(format!("❌ No known GitHub users in `{}`.", user)).await?;
+        return Ok(());
+    }
+    defer(ctx).await;
 
-    match state.octocrab.issues(org, &repo).add_assignees(number, &[&user]).await {
+    stats::count(ctx, Api::Rest);
+    let login_refs: Vec<&str> = logins.iter().map(|l| l.as_str()).collect();
+    let client = state.octocrab_for(ctx.author().id.get()).await;
+    match client.issues(org, repo_name(&repo)).add_assignees(number, &login_refs).await {
         Ok(issue) => {
+             let names = logins.iter().map(|l| format!("**{}**", l)).collect::<Vec<_>>().join(", ");
+             let mut description = format!("successfully assigned {} to issue **#{}** in **{}**", names, number, repo);
+             if !invalid.is_empty() {
+                 description.push_str(&format!("\n⚠️ Skipped unknown users: {}", invalid.iter().map(|l| format!("`{}`", l)).collect::<Vec<_>>().join(", ")));
+             }
+             if let Some(date) = due {
+                 let board = state.guild_configs.read().await.default_board(ctx.guild_id().map(|g| g.get()));
+                 let status = match board {
+                     Some(board) => set_board_date(ctx.http(), state, &ctx.command().qualified_name, ctx.author().id.get(), &board, &issue, date).await
+                         .unwrap_or_else(|e| e),
+                     None => "ℹ️ No default board is configured here (`/config default-board`), so the due date was not set.".to_string(),
+                 };
+                 description.push('\n');
+                 description.push_str(&status);
+             }
              let embed = serenity::CreateEmbed::new()
                 .title(format!("assigned issue #{}", number))
                 .url(issue.html_url.to_string())
-                .description(format!("successfully assigned **{}** to issue **#{}** in **{}**", user, number, repo))
+                .description(description)
                 .color(0x57F287) // Green
                 .timestamp(serenity::Timestamp::now());
             
@@ -53,6 +107,228 @@ pub async fn assign(
     Ok(())
 }
 
+/// Remove a user from an issue's assignees
+#[poise::command(slash_command)]
+pub async fn unassign(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Issue number or title"]
+    #[autocomplete = "issue_autocomplete"]
+    issue: String,
+    #[description = "GitHub Username"] 
+    #[autocomplete = "user_autocomplete"]
+    user: String,
+) -> Result<(), Error> {
+    let _ = check_auth(ctx).await?; // Enforce auth
+    check_repo_name(&repo)?;
+    check_login(&user)?;
+    let state = ctx.data();
+    let number = match resolve_issue_number(state, &repo, &issue).await {
+        Some(n) => n,
+        None => {
+            ctx.say(format!("❌ No open issue in **{}** matches `{}`.", repo, issue)).await?;
+            return Ok(());
+        }
+    };
+    defer(ctx).await;
+
+    stats::count(ctx, Api::Rest);
+    let current = match state.octocrab.issues(&state.repo_org(&repo).await, repo_name(&repo)).get(number).await {
+        Ok(issue) => issue,
+        Err(e) => {
+            ctx.say(format!("❌ Failed to fetch issue #{}: {}", number, e)).await?;
+            return Ok(());
+        }
+    };
+    if !current.assignees.iter().any(|a| a.login.eq_ignore_ascii_case(&user)) {
+        let embed = serenity::CreateEmbed::new()
+            .title(format!("{} isn't assigned to #{}", user, number))
+            .url(current.html_url.to_string())
+            .description(format!("**{}** in **{}** was left unchanged", current.title, repo))
+            .color(0x5865F2);
+        ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        return Ok(());
+    }
+
+    stats::count(ctx, Api::Rest);
+    let client = state.octocrab_for(ctx.author().id.get()).await;
+    match remove_assignee(state, &client, &repo, number, &user).await {
+        Ok(issue) => {
+             let embed = serenity::CreateEmbed::new()
+                .title(format!("unassigned issue #{}", number))
+                .url(issue.html_url.to_string())
+                .description(format!("successfully removed **{}** from issue **#{}** in **{}**", user, number, repo))
+                .color(0x57F287) // Green
+                .timestamp(serenity::Timestamp::now());
+             ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        }
+        Err(e) => {
+             let embed = serenity::CreateEmbed::new()
+                .title("unassignment failed")
+                .description(format!("error: {}", e))
+                .color(0xED4245); // Red
+             ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        }
+    }
+    Ok(())
+}
+
+// Splits a comma-separated assignee list into known logins (cache casing, deduplicated)
+// and entries that aren't org members. With an empty users cache any valid login passes.
+async fn split_assignees(state: &BotState, input: &str) -> (Vec<String>, Vec<String>) {
+    let users = state.users.read().await;
+    let mut logins: Vec<String> = Vec::new();
+    let mut invalid = Vec::new();
+    for name in input.split(',').map(|n| n.trim().trim_start_matches('@')).filter(|n| !n.is_empty()) {
+        let known = if users.is_empty() {
+            Some(name.to_string()).filter(|n| is_valid_login(n))
+        } else {
+            users.iter().find(|u| u.login.eq_ignore_ascii_case(name)).map(|u| u.login.clone())
+        };
+        match known {
+            Some(login) if !logins.iter().any(|l| l.eq_ignore_ascii_case(&login)) => logins.push(login),
+            Some(_) => {}
+            None => invalid.push(name.to_string()),
+        }
+    }
+    (logins, invalid)
+}
+
+// Applies confirmed /repo target arguments (see parse_target_args) to an issue:
+// - a milestone name puts the issue on that milestone, creating it if needed; a date with it
+//   becomes the milestone's due date (durations count from its current due date)
+// - a date alone goes into the guild's default board Date field (durations count from its value)
+// Ok/Err hold the message for the user.
+pub async fn apply_target(http: &serenity::Http, state: &BotState, guild_id: Option<u64>, discord_id: u64, repo: &str, number: u64, args: &str) -> Result<String, String> {
+    let parsed = parse_target_args(args)?;
+    let today = chrono::Utc::now().date_naive();
+
+    state.api_stats.record("component", Api::Rest);
+    let issue = state.octocrab.issues(&state.repo_org(repo).await, repo_name(repo)).get(number).await
+        .map_err(|e| format!("Failed to fetch issue #{}: {}", number, e))?;
+
+    let Some(name) = parsed.milestone else {
+        let date = parsed.date.ok_or("Nothing to apply.")?;
+        let board = state.guild_configs.read().await.default_board(guild_id).ok_or(
+            "No default board is configured here (`/config default-board`). Add a milestone name to put the date on a milestone instead."
+        )?;
+        return set_board_date(http, state, "component", discord_id, &board, &issue, date).await;
+    };
+
+    state.api_stats.record("component", Api::Rest);
+    let existing = find_milestone(state, repo, &name).await.map_err(|e| format!("Failed to fetch milestones: {}", e))?;
+    let mut lines = Vec::new();
+    let milestone = match existing {
+        Some(m) => match parsed.date {
+            Some(date) => {
+                let base = m.due_on.map(|d| d.date_naive()).unwrap_or(today);
+                let due = date.resolve(base).ok_or("That date is out of range.")?;
+                state.api_stats.record("component", Api::Rest);
+                let updated = set_milestone_due(state, repo, m.number, due).await
+                    .map_err(|e| format!("Failed to update the due date of **{}**: {}", m.title, e))?;
+                lines.push(format!("📅 Milestone **{}** is now due **{}**.", updated.title, due));
+                updated
+            }
+            None => m,
+        },
+        None => {
+            let due = match parsed.date {
+                Some(date) => Some(date.resolve(today).ok_or("That date is out of range.")?),
+                None => None,
+            };
+            state.api_stats.record("component", Api::Rest);
+            let created = create_milestone(state, repo, &name, due).await
+                .map_err(|e| format!("No milestone named `{}` in **{}**, and creating it failed: {}", name, repo, e))?;
+            lines.push(match due {
+                Some(d) => format!("🆕 Created milestone **{}**, due **{}**.", created.title, d),
+                None => format!("🆕 Created milestone **{}**.", created.title),
+            });
+            created
+        }
+    };
+
+    state.api_stats.record("component", Api::Rest);
+    state.octocrab_for(discord_id).await.issues(&state.repo_org(repo).await, repo_name(repo)).update(number).milestone(milestone.number as u64).send().await
+        .map_err(|e| format!("Failed to set the milestone: {}", e))?;
+    lines.push(format!("🎯 Issue **#{}** in **{}** now targets milestone **{}**.", number, repo, milestone.title));
+    Ok(lines.join("\n"))
+}
+
+// Puts the issue on the board and sets the board's due Date field. Relative dates count from
+// the field's current value, or today when it's empty. Ok/Err hold a status line for the user.
+async fn set_board_date(
+    http: &serenity::Http,
+    state: &BotState,
+    source: &str,
+    discord_id: u64,
+    board: &DefaultBoard,
+    issue: &octocrab::models::issues::Issue,
+    date: TargetDate,
+) -> Result<String, String> {
+    let proj = {
+        let projects = state.projects.read().await;
+        projects.iter().find(|p| p.title.eq_ignore_ascii_case(&board.project)).cloned()
+    };
+    let Some(proj) = proj else {
+        return Err(format!("⚠️ The default board **{}** is not in the cache, due date not set.", board.project));
+    };
+    let Some(field) = proj.fields.iter().find(|f| f.name.eq_ignore_ascii_case(&board.date_field) && f.data_type == "DATE") else {
+        return Err(format!("⚠️ **{}** has no Date field named **{}**, due date not set.", proj.title, board.date_field));
+    };
+
+    let item_id = match add_project_item(state, source, &proj.id, &issue.node_id).await {
+        Ok(Some(id)) => id,
+        Ok(None) => return Err(format!("⚠️ Couldn't add the issue to **{}**, due date not set.", proj.title)),
+        Err(e) => return Err(format!("⚠️ Couldn't add the issue to **{}**: {}", proj.title, e)),
+    };
+
+    let _item_guard = lock_item(state, &item_id).await;
+    // The current value is only needed as the base of "+1w"-style dates
+    let old_value = if date.is_relative() {
+        fetch_project_item(state, source, &proj.id, issue.number as i64).await.ok().flatten()
+            .and_then(|item| item_field_value(&item, &field.name))
+    } else {
+        None
+    };
+    let base = old_value.as_deref()
+        .and_then(|v| chrono::NaiveDate::parse_from_str(v, "%Y-%m-%d").ok())
+        .unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let date = date.resolve(base).ok_or("That date is out of range.")?.format("%Y-%m-%d").to_string();
+
+    let mutation = serde_json::json!({
+        "query": r#"
+            mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $dateVal: Date!) {
+                updateProjectV2ItemFieldValue(input: {
+                    projectId: $projectId
+                    itemId: $itemId
+                    fieldId: $fieldId
+                    value: { date: $dateVal }
+                }) { projectV2Item { id } }
+            }
+        "#,
+        "variables": { "projectId": proj.id, "itemId": item_id, "fieldId": field.id, "dateVal": date }
+    });
+    match graphql_mutation(state, source, &mutation).await {
+        Ok(_) => {
+            record_edit(http, state, AuditEntry {
+                timestamp: chrono::Utc::now().timestamp(),
+                discord_id,
+                github_user: state.user_mapping.read().await.map.get(&discord_id).cloned(),
+                project_id: proj.id.clone(),
+                project_title: proj.title.clone(),
+                item_number: issue.number as i64,
+                field: field.name.clone(),
+                old_value,
+                new_value: date.clone(),
+            }).await;
+            Ok(format!("📅 **{}** set to **{}** on **{}**", field.name, date, proj.title))
+        }
+        Err(e) => Err(format!("⚠️ Added to **{}** but setting **{}** failed: {}", proj.title, field.name, e)),
+    }
+}
+
 /// Target an issue with arguments
 #[poise::command(slash_command)]
 pub async fn target(
@@ -60,15 +336,31 @@ pub async fn target(
     #[description = "Repository name"] 
     #[autocomplete = "repo_autocomplete"]
     repo: String,
-    #[description = "Issue number"] number: u64,
+    #[description = "Issue number or title"]
+    #[autocomplete = "issue_autocomplete"]
+    issue: String,
     #[description = "Arguments (e.g. +1w, v2.0)"] args: String,
 ) -> Result<(), Error> {
     let _ = check_auth(ctx).await?; // Enforce auth
+    check_repo_name(&repo)?;
+    let args = args.trim().to_string();
+    if let Err(msg) = parse_target_args(&args) {
+        ctx.say(format!("❌ {}", msg)).await?;
+        return Ok(());
+    }
     let state = ctx.data();
-    let org = &state.github_org;
+    let org = &state.repo_org(&repo).await;
+    let number = match resolve_issue_number(state, &repo, &issue).await {
+        Some(n) => n,
+        None => {
+            ctx.say(format!("❌ No open issue in **{}** matches `{}`.", repo, issue)).await?;
+            return Ok(());
+        }
+    };
 
     // Fetch issue details first for context
-    let issue_res = state.octocrab.issues(org, &repo).get(number).await;
+    stats::count(ctx, Api::Rest);
+    let issue_res = state.octocrab.issues(org, repo_name(&repo)).get(number).await;
     
     let title = match issue_res {
         Ok(i) => i.title,
@@ -80,11 +372,17 @@ pub async fn target(
         .description(format!("**Repo:** {}\n**Issue:** {}\n**Target Args:** `{}`", repo, title, args))
         .color(0xFEE75C); // Yellow
 
+    // The confirm button carries everything the handler needs to apply the target
+    let confirm_id = CustomId::TargetConfirm { repo: repo.clone(), num: number, args: args.clone() }.to_string();
+    if args.is_empty() || confirm_id.len() > 100 {
+        ctx.say("❌ The target arguments are empty or too long.").await?;
+        return Ok(());
+    }
     let components = vec![serenity::CreateActionRow::Buttons(vec![
-        serenity::CreateButton::new("confirm_target")
+        serenity::CreateButton::new(confirm_id)
             .label("Confirm")
             .style(serenity::ButtonStyle::Success),
-        serenity::CreateButton::new("cancel_target")
+        serenity::CreateButton::new(CustomId::TargetCancel.to_string())
             .label("Cancel")
             .style(serenity::ButtonStyle::Secondary),
     ])];
@@ -95,28 +393,47 @@ pub async fn target(
 
 /// List all repositories in the Organization
 #[poise::command(slash_command, rename = "list")]
-pub async fn list_repos(ctx: Context<'_>) -> Result<(), Error> {
+pub async fn list_repos(
+    ctx: Context<'_>,
+    #[description = "Only this organization (default: all configured)"]
+    #[autocomplete = "org_autocomplete"]
+    org: Option<String>,
+) -> Result<(), Error> {
     let state = ctx.data();
-    let repos = state.repos.read().await;
+    let orgs = match selected_orgs(state, org.as_deref()) {
+        Ok(orgs) => orgs,
+        Err(msg) => {
+            ctx.say(msg).await?;
+            return Ok(());
+        }
+    };
+    let repos: Vec<CachedRepo> = state.repos.read().await.iter()
+        .filter(|r| orgs.iter().any(|o| o.eq_ignore_ascii_case(&r.org)))
+        .cloned()
+        .collect();
     
     if repos.is_empty() {
-        ctx.say("No repositories found in cache. Try refreshing?").await?;
+        if state.repos_loaded.load(Ordering::Relaxed) {
+            ctx.say(format!("No repositories found in {}.", orgs.join(", "))).await?;
+        } else {
+            ctx.say("⚠️ Repositories haven't been loaded from GitHub yet (the last fetch failed). Try /refresh?").await?;
+        }
         return Ok(());
     }
     
     // Chunk repos for embed fields if many
-    let repo_names: Vec<String> = repos.iter().map(|r| format!("• {}", r.name)).collect();
+    let repo_names: Vec<String> = repos.iter().map(|r| format!("• {}", r.display_name())).collect();
     let description = repo_names.join("\n");
 
     // Truncate if too long for one embed (Discord limit 4096 chars)
     let description = if description.len() > 4000 {
-        format!("{}\n...and more", &description[..4000])
+        format!("{}\n...and more", truncate_to(&description, 4000))
     } else {
         description
     };
 
     let embed = serenity::CreateEmbed::new()
-        .title(format!("Repositories in {}", state.github_org))
+        .title(format!("Repositories in {}", orgs.join(", ")))
         .description(description)
         .color(0x5865F2); // Blurple
 
@@ -124,417 +441,2169 @@ pub async fn list_repos(ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
-/// List open issues in a repository
+// The `org` option of the list commands: that org (configured ones only), or all of them
+fn selected_orgs(state: &BotState, org: Option<&str>) -> Result<Vec<String>, String> {
+    match org.map(str::trim).filter(|o| !o.is_empty()) {
+        Some(name) => state.github_orgs.iter()
+            .find(|o| o.eq_ignore_ascii_case(name))
+            .map(|o| vec![o.clone()])
+            .ok_or_else(|| format!("❌ `{}` isn't one of the configured organizations ({}).", name, state.github_orgs.join(", "))),
+        None => Ok(state.github_orgs.clone()),
+    }
+}
+
+// Top-level shortcuts for the most used subcommands (/issues, /board, /item). The grouped
+// versions stay; guild_config::ALIASES maps these names back to them.
+pub fn aliases() -> Vec<poise::Command<Arc<BotState>, Error>> {
+    let shortcut = |mut cmd: poise::Command<Arc<BotState>, Error>, name: &str| {
+        cmd.name = name.to_string();
+        cmd.qualified_name = name.to_string();
+        cmd.identifying_name = format!("{}_alias", cmd.identifying_name);
+        cmd
+    };
+    vec![
+        shortcut(list_issues(), "issues"),
+        shortcut(view_project(), "board"),
+        shortcut(view_item(), "item"),
+    ]
+}
+
+/// List issues in a repository
 #[poise::command(slash_command, rename = "issues")]
 pub async fn list_issues(
     ctx: Context<'_>,
     #[description = "Repository name"] 
     #[autocomplete = "repo_autocomplete"]
     repo: String,
+    #[description = "Only issues assigned to this user"]
+    #[autocomplete = "user_autocomplete"]
+    assignee: Option<String>,
+    #[description = "Only issues with this label"]
+    #[autocomplete = "label_autocomplete"]
+    label: Option<String>,
+    #[description = "Issue state (default open)"]
+    state: Option<StateFilter>,
+    #[description = "Reply with plain markdown instead of an embed"]
+    plain: Option<bool>,
 ) -> Result<(), Error> {
+    check_repo_name(&repo)?;
+    if let Some(a) = &assignee {
+        check_login(a)?;
+    }
+    let issue_state = state.unwrap_or(StateFilter::Open);
     let state = ctx.data();
-    let org = &state.github_org;
-    ctx.defer().await?;
-
-    match state.octocrab.issues(org, &repo).list().state(octocrab::params::State::Open).per_page(10).send().await {
-        Ok(page) => {
-             if page.items.is_empty() {
-                 ctx.say(format!("No open issues in {}/{}", org, repo)).await?;
-                 return Ok(());
-             }
-
-             let mut embed = serenity::CreateEmbed::new()
-                .title(format!("Open Issues in {}/{}", org, repo))
-                .color(0x5865F2);
-
-             for issue in page.items {
-                 embed = embed.field(
-                     format!("#{} {}", issue.number, issue.title), 
-                     format!("By: {} | [Link]({})", issue.user.login, issue.html_url), 
-                     false
-                 );
-             }
-             
-             // Add a refresh button example
-             let components = vec![serenity::CreateActionRow::Buttons(vec![
-                serenity::CreateButton::new(format!("refresh_issues_{}", repo))
-                    .label("Refresh")
-                    .style(serenity::ButtonStyle::Secondary)
-                    .emoji('🔄')
-             ])];
+    defer(ctx).await;
 
-             ctx.send(poise::CreateReply::default().embed(embed).components(components)).await?;
+    match build_issue_list(state, &ctx.command().qualified_name, &repo, assignee.as_deref(), label.as_deref(), issue_state, 1).await {
+        Ok((rendered, components)) => {
+            ctx.send(rendered.reply(plain.unwrap_or(false)).components(components)).await?;
         }
-        Err(e) => {
-            ctx.say(format!("❌ Failed to fetch issues: {}", e)).await?;
+        Err(msg) => {
+            ctx.say(msg).await?;
         }
     }
     Ok(())
 }
 
-/// Manage Organization Projects
-#[poise::command(slash_command, subcommands("list_projects", "view_project", "view_item", "edit_project_item"))]
-pub async fn proj(_ctx: Context<'_>) -> Result<(), Error> {
-    Ok(())
-}
+const ISSUES_PAGE_SIZE: u8 = 10;
+
+// Fetches and renders one page of a /repo issues listing, shared with the 🔄 Refresh and
+// Prev/Next buttons. Err holds the message for the user (fetch failure or no matching issues).
+pub async fn build_issue_list(
+    state: &BotState,
+    source: &str,
+    repo: &str,
+    assignee: Option<&str>,
+    label: Option<&str>,
+    issue_state: StateFilter,
+    page_num: u32,
+) -> Result<(Rendered, Vec<serenity::CreateActionRow>), String> {
+    let org = &state.repo_org(repo).await;
+    let labels: Vec<String> = label.map(|l| l.to_string()).into_iter().collect();
+    state.api_stats.record(source, Api::Rest);
+    let issues_handler = state.octocrab.issues(org, repo_name(repo));
+    let mut request = issues_handler.list().state(issue_state.as_param()).per_page(ISSUES_PAGE_SIZE).page(page_num);
+    if let Some(a) = assignee {
+        request = request.assignee(a);
+    }
+    if !labels.is_empty() {
+        request = request.labels(&labels);
+    }
 
-/// List Projects in the Organization
-#[poise::command(slash_command, rename = "list")]
-pub async fn list_projects(ctx: Context<'_>) -> Result<(), Error> {
-    let state = ctx.data();
-    let projects = state.projects.read().await;
+    let state_word = match issue_state {
+        StateFilter::Open => "Open ",
+        StateFilter::Closed => "Closed ",
+        StateFilter::All => "",
+    };
 
-    if projects.is_empty() {
-        ctx.say("No projects found in cache. Try /refresh?").await?;
-        return Ok(());
+    // Active filters, e.g. " assigned to alice labeled bug"
+    let mut filters = String::new();
+    if let Some(a) = assignee {
+        filters.push_str(&format!(" assigned to {}", a));
+    }
+    if let Some(l) = label {
+        filters.push_str(&format!(" labeled {}", l));
     }
 
-    let mut embed = serenity::CreateEmbed::new()
-        .title(format!("Projects in {}", state.github_org))
-        .color(0xEB459E); // Pinkish
+    let page = request.send().await.map_err(|e| format!("❌ Failed to fetch issues: {}", e))?;
+    if page.items.is_empty() {
+        return Err(if page_num > 1 {
+            format!("No more {}issues in {}/{}{}", state_word.to_lowercase(), org, repo_name(repo), filters)
+        } else {
+            format!("No {}issues in {}/{}{}", state_word.to_lowercase(), org, repo_name(repo), filters)
+        });
+    }
 
-    for proj in projects.iter() {
-        embed = embed.field(
-            &proj.title,
-            format!("[View Board]({})", proj.url),
-            false
+    // GitHub only says how many pages there are (no `last` link on the last page itself)
+    let page_size = ISSUES_PAGE_SIZE as u32;
+    let has_next = page.next.is_some();
+    let footer = match page.number_of_pages() {
+        Some(last) if has_next => format!("Page {}/{} • ~{} issues", page_num, last, last * page_size),
+        _ => format!("Page {}/{} • {} issues", page_num, page_num, (page_num - 1) * page_size + page.items.len() as u32),
+    };
+
+    let mut rendered = Rendered::new(format!("{}Issues in {}/{}{}", state_word, org, repo, filters), 0x5865F2).footer(footer);
+    for issue in page.items {
+        rendered = rendered.field(
+            format!("#{} {}", issue.number, issue.title),
+            format!("By: {} | [Link]({})", issue.user.login, issue.html_url)
         );
     }
 
-    ctx.send(poise::CreateReply::default().embed(embed)).await?;
-    Ok(())
+    // Paging is skipped when the repo and filters don't fit in a custom_id
+    let page_id = |page: u32| Some(CustomId::IssuesPage {
+        repo: repo.to_string(),
+        page,
+        state: issue_state.name().to_string(),
+        assignee: assignee.map(|a| a.to_string()),
+        label: label.map(|l| l.to_string()),
+    }.to_string()).filter(|id| id.len() <= 100);
+    let mut buttons = Vec::new();
+    if let Some(prev) = page_id(page_num - 1).filter(|_| page_num > 1) {
+        buttons.push(serenity::CreateButton::new(prev).label("◀️ Prev").style(serenity::ButtonStyle::Secondary));
+    }
+    // The button re-runs the plain open-issues listing, so filtered listings don't get one
+    let unfiltered = assignee.is_none() && labels.is_empty() && matches!(issue_state, StateFilter::Open);
+    let refresh = CustomId::RefreshIssues { repo: repo.to_string() }.to_string();
+    if unfiltered && refresh.len() <= 100 {
+        buttons.push(serenity::CreateButton::new(refresh)
+            .label("Refresh")
+            .style(serenity::ButtonStyle::Secondary)
+            .emoji('🔄'));
+    }
+    if let Some(next) = page_id(page_num + 1).filter(|_| has_next) {
+        buttons.push(serenity::CreateButton::new(next).label("Next ▶️").style(serenity::ButtonStyle::Secondary));
+    }
+    let components = if buttons.is_empty() { Vec::new() } else { vec![serenity::CreateActionRow::Buttons(buttons)] };
+    Ok((rendered, components))
 }
 
-/// View items in a specific Project
-#[poise::command(slash_command, rename = "view")]
-pub async fn view_project(
+/// List recent discussions in a repository
+#[poise::command(slash_command)]
+pub async fn discussions(
     ctx: Context<'_>,
-    #[description = "Project Title"] 
-    #[autocomplete = "project_autocomplete"]
-    title: String,
-    #[description = "Filter items (active [default], all)"]
-    filter: Option<String>,
-    #[description = "Page number (default 1)"]
-    page: Option<usize>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Only discussions in this category"]
+    #[autocomplete = "discussion_category_autocomplete"]
+    category: Option<String>,
 ) -> Result<(), Error> {
+    check_repo_name(&repo)?;
     let state = ctx.data();
-    let filter = filter.unwrap_or_else(|| "active".to_string()).to_lowercase();
-    let page_num = page.unwrap_or(1);
-    let page_size = 20;
-
-    // Scope the read lock
-    let project_opt = {
-        let projects = state.projects.read().await;
-        projects.iter().find(|p| p.title.eq_ignore_ascii_case(&title)).cloned()
+    let org = &state.repo_org(&repo).await;
+    defer(ctx).await;
+
+    let category_id = match &category {
+        Some(name) => match repo_discussion_categories(state, &repo).await.into_iter().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+            Some((_, id)) => Some(id),
+            None => {
+                ctx.say(format!("❌ No discussion category `{}` in **{}**.", name, repo)).await?;
+                return Ok(());
+            }
+        },
+        None => None,
     };
-    
-    match project_opt {
-        Some(proj) => {
-            ctx.defer().await?;
-            
-            // GraphQL query to fetch project items (fetch 100 to support local paging/filtering)
-            // In a real robust app, we would use cursor-based pagination
-            let query = serde_json::json!({
-                "query": r#"
-                    query($id: ID!) {
-                        node(id: $id) {
-                            ... on ProjectV2 {
-                                items(first: 100) {
-                                    nodes {
-                                        id
-                                        type
-                                        content {
-                                            ... on Issue {
-                                                title
-                                                number
-                                                url
-                                                repository { name }
-                                                state
-                                                body
-                                                assignees(first: 3) { nodes { login } }
-                                                labels(first: 3) { nodes { name } }
-                                            }
-                                            ... on PullRequest {
-                                                title
-                                                number
-                                                url
-                                                repository { name }
-                                                state
-                                                body
-                                                assignees(first: 3) { nodes { login } }
-                                            }
-                                            ... on DraftIssue {
-                                                title
-                                                body
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                "#,
-                "variables": {
-                    "id": proj.id
-                }
-            });
-
-            match state.octocrab.graphql(&query).await {
-                Ok(resp) => {
-                    let json_resp: serde_json::Value = resp;
-                    
-                    let mut all_items = Vec::new();
-                    
-                    if let Some(nodes) = json_resp.get("data")
-                        .and_then(|d| d.get("node"))
-                        .and_then(|d| d.get("items"))
-                        .and_then(|d| d.get("nodes"))
-                        .and_then(|d| d.as_array()) 
-                    {
-                        for item in nodes {
-                            let content = item.get("content");
-                            
-                            if let Some(issue) = content.and_then(|c| c.get("number")) {
-                                let state = content.and_then(|c| c.get("state")).and_then(|s| s.as_str()).unwrap_or("");
-                                
-                                // Filtering
-                                let is_closed = state == "CLOSED" || state == "MERGED";
-                                if filter == "active" && is_closed { continue; }
-
-                                let title = content.and_then(|c| c.get("title")).and_then(|t| t.as_str()).unwrap_or("?");
-                                let repo = content.and_then(|c| c.get("repository")).and_then(|r| r.get("name")).and_then(|n| n.as_str()).unwrap_or("?");
-                                let number = issue.as_i64().unwrap_or(0);
-                                let url = content.and_then(|c| c.get("url")).and_then(|u| u.as_str()).unwrap_or("");
-                                
-                                let icon = match state {
-                                    "OPEN" => "🟢",
-                                    "CLOSED" => "🟣",
-                                    "MERGED" => "🟣",
-                                    _ => "⚪",
-                                };
-                                
-                                all_items.push(format!("{} **{}/[#{}]({})** {}", icon, repo, number, url, title));
-                            } else if let Some(draft_title) = content.and_then(|c| c.get("title")).and_then(|t| t.as_str()) {
-                                all_items.push(format!("📝 **Draft:** {}", draft_title));
-                            }
-                        }
-                    }
 
-                    let mut display_lines = Vec::new();
-                    let mut menu_options = Vec::new();
-
-                    if let Some(nodes) = json_resp.get("data")
-                        .and_then(|d| d.get("node"))
-                        .and_then(|d| d.get("items"))
-                        .and_then(|d| d.get("nodes"))
-                        .and_then(|d| d.as_array()) 
-                    {
-                        for item in nodes {
-                            let content = item.get("content");
-                            if let Some(issue) = content.and_then(|c| c.get("number")) {
-                                let state = content.and_then(|c| c.get("state")).and_then(|s| s.as_str()).unwrap_or("");
-                                
-                                // Filtering
-                                let is_closed = state == "CLOSED" || state == "MERGED";
-                                if filter == "active" && is_closed { continue; }
-
-                                let title = content.and_then(|c| c.get("title")).and_then(|t| t.as_str()).unwrap_or("?");
-                                let repo = content.and_then(|c| c.get("repository")).and_then(|r| r.get("name")).and_then(|n| n.as_str()).unwrap_or("?");
-                                let number = issue.as_i64().unwrap_or(0);
-                                let url = content.and_then(|c| c.get("url")).and_then(|u| u.as_str()).unwrap_or("");
-                                
-                                let icon = match state {
-                                    "OPEN" => "<:issue:1458877117176742065>",
-                                    "CLOSED" => "<:issue_neutral:1458877524015579209>",
-                                    "MERGED" => "<:pr_merged:1458877132414517360>",
-                                    _ => "⚪",
-                                };
-                                
-                                display_lines.push(format!("{} **{}/[#{}]({})** {}", icon, repo, number, url, title));
-                                menu_options.push((number, title.to_string(), repo.to_string()));
-                            } else if let Some(draft_title) = content.and_then(|c| c.get("title")).and_then(|t| t.as_str()) {
-                                display_lines.push(format!("<:issue_draft:1458883679777460434> **Draft:** {}", draft_title));
-                                // Drafts skipped in menu for now as they have no number
-                            }
+    let query = serde_json::json!({
+        "query": r#"
+            query($owner: String!, $name: String!, $category: ID) {
+                repository(owner: $owner, name: $name) {
+                    hasDiscussionsEnabled
+                    discussions(first: 10, categoryId: $category, orderBy: { field: UPDATED_AT, direction: DESC }) {
+                        nodes {
+                            number title url
+                            author { login }
+                            category { name }
+                            comments { totalCount }
+                            answer { id }
                         }
                     }
+                }
+            }
+        "#,
+        "variables": { "owner": org, "name": repo_name(&repo), "category": category_id }
+    });
 
-                    // Recalculate pagination based on filtered list
-                    let total_items = display_lines.len();
-                    // Re-calculate start_idx in case filter changed things
-                    let start_idx = (page_num - 1) * page_size;
-                    
-                    if start_idx >= total_items && total_items > 0 {
-                         ctx.say(format!("Page {} is out of bounds.", page_num)).await?;
-                         return Ok(());
-                    }
-                    if display_lines.is_empty() {
-                         ctx.say(format!("No items found in project {} with filter '{}'.", proj.title, filter)).await?;
-                         return Ok(());
-                    }
-
-                    let end_idx = std::cmp::min(start_idx + page_size, total_items);
-                    let page_display = &display_lines[start_idx..end_idx];
-                    let page_menu_opts = menu_options.iter().skip(start_idx).take(page_size);
-
-                    let embed = serenity::CreateEmbed::new()
-                        .title(format!("Project: {} ({})", proj.title, filter))
-                        .url(&proj.url)
-                        .description(page_display.join("\n"))
-                        .footer(serenity::CreateEmbedFooter::new(format!("Page {}/{} • Total: {}", page_num, (total_items + page_size - 1) / page_size, total_items)))
-                        .color(0xEB459E);
-
-                    let mut components = Vec::new();
-                    
-                    // 1. Select Menu
-                    let mut select_opts_vec = Vec::new();
-                    for (num, title, repo) in page_menu_opts {
-                        // Label max 100 chars
-                        let label = format!("{} #{}: {}", repo, num, title);
-                        let label = if label.len() > 95 { format!("{}...", &label[..95]) } else { label };
-                        select_opts_vec.push(CreateSelectMenuOption::new(label, num.to_string()));
-                    }
-                    
-                    if !select_opts_vec.is_empty() {
-                        let menu_id = format!("proj_select_{}", proj.id); // Use Project ID to context
-                        let menu = CreateSelectMenu::new(menu_id, CreateSelectMenuKind::String { options: select_opts_vec })
-                            .placeholder("🔍 Select an item to view details...");
-                        components.push(serenity::CreateActionRow::SelectMenu(menu));
-                    }
-
-                    // 2. Buttons
-                    let mut buttons = Vec::new();
-                    if page_num > 1 {
-                        buttons.push(serenity::CreateButton::new(format!("proj_page_{}_{}", title, page_num - 1)).label("◀️ Prev").style(serenity::ButtonStyle::Secondary));
-                    }
-                    buttons.push(serenity::CreateButton::new_link(&proj.url).label("Open Board"));
-                    if end_idx < total_items {
-                         buttons.push(serenity::CreateButton::new(format!("proj_page_{}_{}", title, page_num + 1)).label("Next ▶️").style(serenity::ButtonStyle::Secondary));
-                    }
-                    components.push(serenity::CreateActionRow::Buttons(buttons));
+    match graphql(state, stats::source(ctx), &query).await {
+        Ok(resp) => {
+            let repository = resp.get("data").and_then(|d| d.get("repository"));
+            if repository.and_then(|r| r.get("hasDiscussionsEnabled")).and_then(|b| b.as_bool()) == Some(false) {
+                ctx.say(format!("Discussions are not enabled for {}/{}.", org, repo)).await?;
+                return Ok(());
+            }
+            let nodes = repository.and_then(|r| r.get("discussions")).and_then(|d| d.get("nodes")).and_then(|n| n.as_array()).cloned().unwrap_or_default();
+            if nodes.is_empty() {
+                ctx.say(format!("No discussions in {}/{}", org, repo)).await?;
+                return Ok(());
+            }
 
-                    ctx.send(poise::CreateReply::default().embed(embed).components(components)).await?;
-                }
-                Err(e) => {
-                    ctx.say(format!("❌ Failed to fetch project items: {}", e)).await?;
-                }
+            let mut embed = serenity::CreateEmbed::new()
+                .title(format!("Discussions in {}/{}{}", org, repo, category.as_ref().map(|c| format!(" ({})", c)).unwrap_or_default()))
+                .color(0x5865F2);
+            for d in nodes {
+                let number = d.get("number").and_then(|n| n.as_i64()).unwrap_or(0);
+                let title = d.get("title").and_then(|t| t.as_str()).unwrap_or("?");
+                let author = d.get("author").and_then(|a| a.get("login")).and_then(|l| l.as_str()).unwrap_or("ghost");
+                let category_name = d.get("category").and_then(|c| c.get("name")).and_then(|n| n.as_str()).unwrap_or("?");
+                let comments = d.get("comments").and_then(|c| c.get("totalCount")).and_then(|n| n.as_i64()).unwrap_or(0);
+                let answered = if d.get("answer").is_some_and(|a| !a.is_null()) { " | ✅ Answered" } else { "" };
+                let link = md_link("Link", d.get("url").and_then(|u| u.as_str()));
+                embed = embed.field(
+                    format!("#{} {}", number, title),
+                    format!("By: {} | {} | 💬 {}{} | {}", author, category_name, comments, answered, link),
+                    false
+                );
             }
+            ctx.send(poise::CreateReply::default().embed(embed)).await?;
         }
-        None => {
-            ctx.say(format!("Project '{}' not found in cache. Try /refresh?", title)).await?;
+        Err(e) => {
+            ctx.say(format!("❌ Failed to fetch discussions: {}", e)).await?;
         }
     }
     Ok(())
 }
 
-/// View details of a specific item in a project
-#[poise::command(slash_command, rename = "view-item")]
-pub async fn view_item(
+/// Show a single discussion
+#[poise::command(slash_command, rename = "view-discussion")]
+pub async fn view_discussion(
     ctx: Context<'_>,
-    #[description = "Project Title"] 
-    #[autocomplete = "project_autocomplete"]
-    title: String,
-    #[description = "Item ID (e.g. '123' or 'backend-service #123')"]
-    #[autocomplete = "item_autocomplete"]
-    item_query: String,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Discussion number"] number: i64,
 ) -> Result<(), Error> {
-    // Re-using the logic from view_project but finding specific item
-    // In production, you'd want a more direct lookup
+    check_repo_name(&repo)?;
     let state = ctx.data();
-    
-     // Scope the read lock
-    let project_opt = {
-        let projects = state.projects.read().await;
-        projects.iter().find(|p| p.title.eq_ignore_ascii_case(&title)).cloned()
-    };
-    
-    match project_opt {
-        Some(proj) => {
-            ctx.defer().await?;
-             // Fetch items (same query as view)
-             // ... (Optimized query to just get specific item would be better but V2 ID mapping is complex)
-             // We'll fetch items and filter in memory for this MVP
-             let query = serde_json::json!({
-                "query": r#"
-                    query($id: ID!) {
-                        node(id: $id) {
-                            ... on ProjectV2 {
-                                items(first: 100) {
-                                    nodes {
-                                        content {
-                                            ... on Issue {
-                                                title
-                                                number
-                                                body
-                                                url
-                                                repository { name }
-                                                state
-                                                assignees(first: 3) { nodes { login } }
-                                                labels(first: 5) { nodes { name } }
-                                            }
-                                            ... on PullRequest {
-                                                title
-                                                number
-                                                body
-                                                url
-                                                repository { name }
-                                                state
-                                                assignees(first: 3) { nodes { login } }
-                                            }
-                                        }
-                                        fieldValues(first: 20) {
-                                            nodes {
-                                                ... on ProjectV2ItemFieldTextValue { text field { ... on ProjectV2FieldCommon { name } } }
-                                                ... on ProjectV2ItemFieldDateValue { date field { ... on ProjectV2FieldCommon { name } } }
-                                                ... on ProjectV2ItemFieldSingleSelectValue { name field { ... on ProjectV2FieldCommon { name } } }
-                                                ... on ProjectV2ItemFieldNumberValue { number field { ... on ProjectV2FieldCommon { name } } }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
+    defer(ctx).await;
+
+    let query = serde_json::json!({
+        "query": r#"
+            query($owner: String!, $name: String!, $number: Int!) {
+                repository(owner: $owner, name: $name) {
+                    discussion(number: $number) {
+                        number title url body createdAt
+                        author { login }
+                        category { name }
+                        comments { totalCount }
+                        upvoteCount
+                        answer { author { login } url }
                     }
-                "#,
-                "variables": { "id": proj.id }
-            });
-            
-            match state.octocrab.graphql(&query).await {
-                Ok(resp) => {
-                    let json_resp: serde_json::Value = resp;
+                }
+            }
+        "#,
+        "variables": { "owner": state.repo_org(&repo).await, "name": repo_name(&repo), "number": number }
+    });
+
+    let resp = match graphql(state, stats::source(ctx), &query).await {
+        Ok(r) => r,
+        Err(e) => {
+            ctx.say(format!("❌ Failed to fetch discussion: {}", e)).await?;
+            return Ok(());
+        }
+    };
+    let Some(d) = resp.get("data").and_then(|d| d.get("repository")).and_then(|r| r.get("discussion")).filter(|d| !d.is_null()) else {
+        ctx.say(format!("❌ Discussion #{} not found in **{}**.", number, repo)).await?;
+        return Ok(());
+    };
+
+    let title = d.get("title").and_then(|t| t.as_str()).unwrap_or("?");
+    let body = d.get("body").and_then(|b| b.as_str()).unwrap_or("");
+    let author = d.get("author").and_then(|a| a.get("login")).and_then(|l| l.as_str()).unwrap_or("ghost");
+    let category_name = d.get("category").and_then(|c| c.get("name")).and_then(|n| n.as_str()).unwrap_or("?");
+    let comments = d.get("comments").and_then(|c| c.get("totalCount")).and_then(|n| n.as_i64()).unwrap_or(0);
+    let upvotes = d.get("upvoteCount").and_then(|n| n.as_i64()).unwrap_or(0);
+    let answer = d.get("answer").filter(|a| !a.is_null()).map(|a| {
+        let by = a.get("author").and_then(|x| x.get("login")).and_then(|l| l.as_str()).unwrap_or("ghost");
+        md_link(&format!("✅ by {}", by), a.get("url").and_then(|u| u.as_str()))
+    });
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("{} #{} {}", repo, number, title))
+        .description(if body.chars().count() > 1000 { format!("{}...", body.chars().take(1000).collect::<String>()) } else { body.to_string() })
+        .field("Author", author, true)
+        .field("Category", category_name, true)
+        .field("Comments", comments.to_string(), true)
+        .field("Upvotes", upvotes.to_string(), true)
+        .field("Answer", answer.unwrap_or_else(|| "None".to_string()), true)
+        .color(0x5865F2);
+    if let Some(url) = d.get("url").and_then(|u| u.as_str()) {
+        embed = embed.url(url);
+    }
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Show an issue, with labels from the server's taxonomy as fields
+#[poise::command(slash_command, rename = "view-issue")]
+pub async fn view_issue(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Issue number or title"]
+    #[autocomplete = "issue_autocomplete"]
+    issue: String,
+) -> Result<(), Error> {
+    check_repo_name(&repo)?;
+    let state = ctx.data();
+    let number = match resolve_issue_number(state, &repo, &issue).await {
+        Some(n) => n,
+        None => {
+            ctx.say(format!("❌ No open issue in **{}** matches `{}`.", repo, issue)).await?;
+            return Ok(());
+        }
+    };
+    show_issue(ctx, &repo, number).await
+}
+
+/// Show one issue by number (open or closed): state, author, assignees, labels, body and comment count
+#[poise::command(slash_command, rename = "view")]
+pub async fn view_repo_issue(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Issue number"] number: u64,
+) -> Result<(), Error> {
+    check_repo_name(&repo)?;
+    show_issue(ctx, &repo, number).await
+}
+
+// Shared by /repo view and /repo view-issue
+async fn show_issue(ctx: Context<'_>, repo: &str, number: u64) -> Result<(), Error> {
+    let state = ctx.data();
+    defer(ctx).await;
+
+    let taxonomy = state.guild_configs.read().await.taxonomy(ctx.guild_id().map(|g| g.get()));
+    stats::count(ctx, Api::Rest);
+    match state.octocrab.issues(&state.repo_org(repo).await, repo_name(repo)).get(number).await {
+        Ok(issue) => {
+            ctx.send(poise::CreateReply::default().embed(build_issue_embed(repo, &issue, &taxonomy))).await?;
+        }
+        Err(e) => {
+            ctx.say(format!("❌ Failed to fetch issue #{}: {}", number, e)).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Set an issue's taxonomy labels (e.g. Priority) from dropdowns
+#[poise::command(slash_command, guild_only)]
+pub async fn triage(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Issue number or title"]
+    #[autocomplete = "issue_autocomplete"]
+    issue: String,
+) -> Result<(), Error> {
+    let _ = check_auth(ctx).await?; // Enforce auth
+    check_repo_name(&repo)?;
+    let state = ctx.data();
+    let taxonomy = state.guild_configs.read().await.taxonomy(ctx.guild_id().map(|g| g.get()));
+    if taxonomy.is_empty() {
+        ctx.say("No label taxonomy is configured in this server. An admin can add one with `/config taxonomy-set`.").await?;
+        return Ok(());
+    }
+    let number = match resolve_issue_number(state, &repo, &issue).await {
+        Some(n) => n,
+        None => {
+            ctx.say(format!("❌ No open issue in **{}** matches `{}`.", repo, issue)).await?;
+            return Ok(());
+        }
+    };
+    defer(ctx).await;
+
+    stats::count(ctx, Api::Rest);
+    // Label changes are made as the user
+    let client = state.octocrab_for(ctx.author().id.get()).await;
+    let issues_handler = client.issues(&state.repo_org(&repo).await, repo_name(&repo));
+    let mut issue = match issues_handler.get(number).await {
+        Ok(i) => i,
+        Err(e) => {
+            ctx.say(format!("❌ Failed to fetch issue #{}: {}", number, e)).await?;
+            return Ok(());
+        }
+    };
+
+    let prefix = format!("triage_{}_", ctx.id());
+    let reply = ctx.send(poise::CreateReply::default()
+        .embed(build_issue_embed(&repo, &issue, &taxonomy))
+        .components(triage_menus(&prefix, &taxonomy, &issue))
+    ).await?;
+    let message = reply.message().await?.into_owned();
+
+    // One dropdown per category; keep accepting changes until the menus time out
+    while let Some(mci) = message.await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .timeout(Duration::from_secs(120))
+        .await
+    {
+        let category = mci.data.custom_id.strip_prefix(&prefix)
+            .and_then(|i| i.parse::<usize>().ok())
+            .and_then(|i| taxonomy.get(i));
+        let chosen = match &mci.data.kind {
+            serenity::ComponentInteractionDataKind::StringSelect { values } => values.first().cloned(),
+            _ => None,
+        };
+        let (Some(category), Some(chosen)) = (category, chosen) else { continue };
+
+        // Swap the category's label, keep everything else
+        let mut labels: Vec<String> = issue.labels.iter()
+            .map(|l| l.name.clone())
+            .filter(|l| !category.contains(l))
+            .collect();
+        labels.push(chosen.clone());
+
+        stats::count(ctx, Api::Rest);
+        match issues_handler.replace_all_labels(number, &labels).await {
+            Ok(updated) => {
+                issue.labels = updated;
+                mci.create_response(ctx, serenity::CreateInteractionResponse::UpdateMessage(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .embed(build_issue_embed(&repo, &issue, &taxonomy))
+                        .components(triage_menus(&prefix, &taxonomy, &issue))
+                )).await?;
+            }
+            Err(e) => {
+                mci.create_response(ctx, serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .content(format!("❌ Failed to set {} to `{}`: {}", category.name, chosen, e))
+                        .ephemeral(true)
+                )).await?;
+            }
+        }
+    }
+
+    let _ = reply.edit(ctx, poise::CreateReply::default()
+        .embed(build_issue_embed(&repo, &issue, &taxonomy))
+        .components(vec![])
+    ).await;
+    Ok(())
+}
+
+/// Close an issue
+#[poise::command(slash_command, rename = "close")]
+pub async fn close_issue(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Issue number"] number: u64,
+) -> Result<(), Error> {
+    set_issue_state(ctx, repo, number, octocrab::models::IssueState::Closed).await
+}
+
+/// Reopen a closed issue
+#[poise::command(slash_command, rename = "reopen")]
+pub async fn reopen_issue(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Issue number"] number: u64,
+) -> Result<(), Error> {
+    set_issue_state(ctx, repo, number, octocrab::models::IssueState::Open).await
+}
+
+// /repo reopen-issue: the old name of /repo reopen, registered as a renamed copy of it
+pub fn reopen_issue_legacy() -> poise::Command<Arc<BotState>, Error> {
+    let mut cmd = reopen_issue();
+    cmd.name = "reopen-issue".to_string();
+    cmd.identifying_name = format!("{}_legacy", cmd.identifying_name);
+    cmd.description = Some("Reopen a closed issue (old name of /repo reopen)".to_string());
+    cmd
+}
+
+// Shared by /repo close and /repo reopen
+async fn set_issue_state(ctx: Context<'_>, repo: String, number: u64, target: octocrab::models::IssueState) -> Result<(), Error> {
+    let _ = check_auth(ctx).await?; // Enforce auth
+    check_repo_name(&repo)?;
+    let state = ctx.data();
+    defer(ctx).await;
+
+    // (command verb, state name, past tense, outcome)
+    let (verb, done, past, outcome) = match target {
+        octocrab::models::IssueState::Closed => ("close", "closed", "closed", "now closed"),
+        _ => ("reopen", "open", "reopened", "open again"),
+    };
+
+    let issues_handler = state.octocrab.issues(&state.repo_org(&repo).await, repo_name(&repo));
+    stats::count(ctx, Api::Rest);
+    match issues_handler.get(number).await {
+        Ok(issue) if issue.state == target => {
+            let embed = serenity::CreateEmbed::new()
+                .title(format!("issue #{} is already {}", number, done))
+                .url(issue.html_url.to_string())
+                .description(format!("**{}** in **{}** was left unchanged", issue.title, repo))
+                .color(0x5865F2);
+            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            return Ok(());
+        }
+        Ok(_) => {}
+        Err(e) => {
+            ctx.say(format!("❌ Failed to fetch issue #{}: {}", number, e)).await?;
+            return Ok(());
+        }
+    }
+
+    stats::count(ctx, Api::Rest);
+    let client = state.octocrab_for(ctx.author().id.get()).await;
+    match client.issues(&state.repo_org(&repo).await, repo_name(&repo)).update(number).state(target).send().await {
+        Ok(issue) => {
+            // The open-issue list for autocomplete is now stale
+            state.issues.write().await.remove(&repo);
+            let embed = serenity::CreateEmbed::new()
+                .title(format!("{} issue #{}", past, number))
+                .url(issue.html_url.to_string())
+                .description(format!("**{}** in **{}** is {}", issue.title, repo, outcome))
+                .color(0x57F287)
+                .timestamp(serenity::Timestamp::now());
+            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        }
+        Err(e) => {
+            let embed = serenity::CreateEmbed::new()
+                .title(format!("{} failed", verb))
+                .description(format!("error: {}", e))
+                .color(0xED4245);
+            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Comment on an issue or pull request
+#[poise::command(slash_command)]
+pub async fn comment(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Issue or pull request number"] number: u64,
+    #[description = "Comment text (leave empty to write a longer, multi-line comment in a form)"]
+    body: Option<String>,
+) -> Result<(), Error> {
+    let _ = check_auth(ctx).await?; // Enforce auth
+    check_repo_name(&repo)?;
+
+    let Some(body) = body.filter(|b| !b.trim().is_empty()) else {
+        // The modal's paragraph input takes multi-line text, which slash options can't
+        let poise::Context::Application(app_ctx) = ctx else { return Ok(()); };
+        let modal_id = CustomId::CommentModal { repo: repo.clone(), num: number }.to_string();
+        let input = CreateInputText::new(serenity::InputTextStyle::Paragraph, "Comment", "body")
+            .placeholder("Markdown is supported")
+            .max_length(MAX_MODAL_TEXT_LEN);
+        let modal = CreateModal::new(modal_id, format!("Comment on {} #{}", truncate_to(&repo, 30), number))
+            .components(vec![serenity::CreateActionRow::InputText(input)]);
+        app_ctx.interaction.create_response(ctx, serenity::CreateInteractionResponse::Modal(modal)).await?;
+        return Ok(());
+    };
+
+    defer(ctx).await;
+    let embed = post_comment(ctx.data(), &ctx.command().qualified_name, ctx.author().id.get(), &repo, number, &body).await;
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// File a new issue (opens a form for the title and description)
+#[poise::command(slash_command, rename = "create-issue")]
+pub async fn create_issue(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+) -> Result<(), Error> {
+    let _ = check_auth(ctx).await?; // Enforce auth
+    check_repo_name(&repo)?;
+    let poise::Context::Application(app_ctx) = ctx else { return Ok(()); };
+
+    let modal_id = CustomId::CreateIssue { repo: repo.clone() }.to_string();
+    let title = CreateInputText::new(serenity::InputTextStyle::Short, "Title", "title")
+        .max_length(256);
+    let body = CreateInputText::new(serenity::InputTextStyle::Paragraph, "Description", "body")
+        .placeholder("Markdown is supported")
+        .max_length(MAX_MODAL_TEXT_LEN)
+        .required(false);
+    let modal = CreateModal::new(modal_id, format!("New issue in {}", truncate_to(&repo, 30)))
+        .components(vec![serenity::CreateActionRow::InputText(title), serenity::CreateActionRow::InputText(body)]);
+    app_ctx.interaction.create_response(ctx, serenity::CreateInteractionResponse::Modal(modal)).await?;
+    Ok(())
+}
+
+// Files the issue from the /repo create-issue form as the author, assigned to their linked
+// GitHub account, and builds the reply embed
+pub async fn file_issue(state: &BotState, discord_id: u64, repo: &str, title: &str, body: &str, assignee: Option<String>) -> serenity::CreateEmbed {
+    let client = state.octocrab_for(discord_id).await;
+    let issues_handler = client.issues(&state.repo_org(repo).await, repo_name(repo));
+    let mut request = issues_handler.create(title);
+    if !body.trim().is_empty() {
+        request = request.body(body);
+    }
+    if let Some(login) = &assignee {
+        request = request.assignees(vec![login.clone()]);
+    }
+    state.api_stats.record("component", Api::Rest);
+    match request.send().await {
+        Ok(issue) => {
+            // The open-issue list for autocomplete is now stale
+            state.issues.write().await.remove(repo);
+            let mut description = format!("**{}** in **{}**", issue.title, repo);
+            if let Some(login) = assignee {
+                description.push_str(&format!("\nassigned to **{}**", login));
+            }
+            serenity::CreateEmbed::new()
+                .title(format!("created issue #{}", issue.number))
+                .url(issue.html_url.to_string())
+                .description(description)
+                .color(0x57F287)
+                .timestamp(serenity::Timestamp::now())
+        }
+        Err(e) => serenity::CreateEmbed::new()
+            .title("issue creation failed")
+            .description(format!("error: {}", e))
+            .color(0xED4245),
+    }
+}
+
+// Posts the comment and builds the reply embed; shared with the /repo comment modal.
+// GitHub enforces the length limit, its error is shown as is.
+pub async fn post_comment(state: &BotState, source: &str, discord_id: u64, repo: &str, number: u64, body: &str) -> serenity::CreateEmbed {
+    let client = state.octocrab_for(discord_id).await;
+    state.api_stats.record(source, Api::Rest);
+    match client.issues(&state.repo_org(repo).await, repo_name(repo)).create_comment(number, body).await {
+        Ok(comment) => serenity::CreateEmbed::new()
+            .title(format!("commented on #{}", number))
+            .url(comment.html_url.to_string())
+            .description(format!("posted a comment on **#{}** in **{}**", number, repo))
+            .color(0x57F287)
+            .timestamp(serenity::Timestamp::now()),
+        Err(e) => serenity::CreateEmbed::new()
+            .title("comment failed")
+            .description(format!("GitHub didn't accept the comment on **#{}** in **{}**.\nerror: {}", number, repo, e))
+            .color(0xED4245),
+    }
+}
+
+/// Add a label to an issue
+#[poise::command(slash_command, rename = "label-add")]
+pub async fn label_add(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Issue number or title"]
+    #[autocomplete = "issue_autocomplete"]
+    issue: String,
+    #[description = "Label name"]
+    #[autocomplete = "label_autocomplete"]
+    label: String,
+) -> Result<(), Error> {
+    let _ = check_auth(ctx).await?; // Enforce auth
+    check_repo_name(&repo)?;
+    let state = ctx.data();
+    let number = match resolve_issue_number(state, &repo, &issue).await {
+        Some(n) => n,
+        None => {
+            ctx.say(format!("❌ No open issue in **{}** matches `{}`.", repo, issue)).await?;
+            return Ok(());
+        }
+    };
+    defer(ctx).await;
+
+    // GitHub silently creates unknown labels when adding them, so only allow existing ones
+    let labels = repo_labels(state, &repo).await;
+    let Some(label) = labels.iter().find(|l| l.eq_ignore_ascii_case(label.trim())).cloned() else {
+        let embed = serenity::CreateEmbed::new()
+            .title("label add failed")
+            .description(format!("**{}** has no label named `{}`", repo, label))
+            .color(0xED4245);
+        ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        return Ok(());
+    };
+
+    stats::count(ctx, Api::Rest);
+    let client = state.octocrab_for(ctx.author().id.get()).await;
+    match client.issues(&state.repo_org(&repo).await, repo_name(&repo)).add_labels(number, std::slice::from_ref(&label)).await {
+        Ok(_) => {
+            let embed = serenity::CreateEmbed::new()
+                .title(format!("labeled issue #{}", number))
+                .url(format!("https://github.com/{}/{}/issues/{}", state.repo_org(&repo).await, repo_name(&repo), number))
+                .description(format!("added **{}** to issue **#{}** in **{}**", label, number, repo))
+                .color(0x57F287)
+                .timestamp(serenity::Timestamp::now());
+            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        }
+        Err(e) => {
+            let description = if is_not_found(&e) {
+                format!("issue **#{}** was not found in **{}**", number, repo)
+            } else {
+                format!("error: {}", e)
+            };
+            let embed = serenity::CreateEmbed::new()
+                .title("label add failed")
+                .description(description)
+                .color(0xED4245);
+            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove a label from an issue
+#[poise::command(slash_command, rename = "label-remove")]
+pub async fn label_remove(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Issue number or title"]
+    #[autocomplete = "issue_autocomplete"]
+    issue: String,
+    #[description = "Label name"]
+    #[autocomplete = "label_autocomplete"]
+    label: String,
+) -> Result<(), Error> {
+    let _ = check_auth(ctx).await?; // Enforce auth
+    check_repo_name(&repo)?;
+    let state = ctx.data();
+    let number = match resolve_issue_number(state, &repo, &issue).await {
+        Some(n) => n,
+        None => {
+            ctx.say(format!("❌ No open issue in **{}** matches `{}`.", repo, issue)).await?;
+            return Ok(());
+        }
+    };
+    defer(ctx).await;
+
+    let label = label.trim();
+    stats::count(ctx, Api::Rest);
+    let client = state.octocrab_for(ctx.author().id.get()).await;
+    match client.issues(&state.repo_org(&repo).await, repo_name(&repo)).remove_label(number, label).await {
+        Ok(_) => {
+            let embed = serenity::CreateEmbed::new()
+                .title(format!("unlabeled issue #{}", number))
+                .url(format!("https://github.com/{}/{}/issues/{}", state.repo_org(&repo).await, repo_name(&repo), number))
+                .description(format!("removed **{}** from issue **#{}** in **{}**", label, number, repo))
+                .color(0x57F287)
+                .timestamp(serenity::Timestamp::now());
+            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        }
+        // 404: the label doesn't exist or isn't on the issue
+        Err(e) if is_not_found(&e) => {
+            let embed = serenity::CreateEmbed::new()
+                .title(format!("issue #{} doesn't have that label", number))
+                .description(format!("**{}** is not on issue **#{}** in **{}**, nothing was removed", label, number, repo))
+                .color(0x5865F2);
+            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        }
+        Err(e) => {
+            let embed = serenity::CreateEmbed::new()
+                .title("label remove failed")
+                .description(format!("error: {}", e))
+                .color(0xED4245);
+            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        }
+    }
+    Ok(())
+}
+
+// Select menus for /repo triage, current values preselected. Discord allows 5 rows of 25 options.
+fn triage_menus(prefix: &str, taxonomy: &[LabelCategory], issue: &octocrab::models::issues::Issue) -> Vec<serenity::CreateActionRow> {
+    let labels: Vec<String> = issue.labels.iter().map(|l| l.name.clone()).collect();
+    taxonomy.iter().take(5).enumerate().map(|(idx, category)| {
+        let current = category.value_of(&labels);
+        let options: Vec<CreateSelectMenuOption> = category.labels.iter().take(25)
+            .map(|l| CreateSelectMenuOption::new(l, l).default_selection(current.as_deref() == Some(l.as_str())))
+            .collect();
+        serenity::CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(format!("{}{}", prefix, idx), CreateSelectMenuKind::String { options })
+                .placeholder(format!("Set {}", category.name))
+        )
+    }).collect()
+}
+
+/// Pull Request Commands
+#[poise::command(slash_command, subcommands("list_prs", "view_pr", "merge_pr", "review_pr"))]
+pub async fn pr(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// List open pull requests in a repository
+#[poise::command(slash_command, rename = "list")]
+pub async fn list_prs(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Only pull requests into this branch"]
+    #[autocomplete = "branch_autocomplete"]
+    base: Option<String>,
+    #[description = "Reply with plain markdown instead of an embed"]
+    plain: Option<bool>,
+) -> Result<(), Error> {
+    check_repo_name(&repo)?;
+    let state = ctx.data();
+    let org = &state.repo_org(&repo).await;
+    let base = base.map(|b| b.trim().to_string()).filter(|b| !b.is_empty());
+    defer(ctx).await;
+
+    let pulls = state.octocrab.pulls(org, repo_name(&repo));
+    let mut request = pulls.list().state(octocrab::params::State::Open).per_page(10);
+    if let Some(b) = &base {
+        request = request.base(b);
+    }
+    // " into `main`" when filtered by base branch
+    let into = base.as_ref().map(|b| format!(" into `{}`", b)).unwrap_or_default();
+    stats::count(ctx, Api::Rest);
+    match request.send().await {
+        Ok(page) => {
+            if page.items.is_empty() {
+                ctx.say(format!("No open pull requests{} in {}/{}", into, org, repo_name(&repo))).await?;
+                return Ok(());
+            }
+
+            let mut rendered = Rendered::new(format!("Open Pull Requests{} in {}/{}", into, org, repo_name(&repo)), 0x5865F2);
+            for pr in page.items {
+                let author = pr.user.as_ref().map(|u| u.login.as_str()).unwrap_or("?");
+                let draft = if pr.draft.unwrap_or(false) { " (draft)" } else { "" };
+                rendered = rendered.field(
+                    format!("#{} {}{}", pr.number, pr.title.as_deref().unwrap_or(""), draft),
+                    format!("By: {} | `{}` → `{}` | {}", author, pr.head.ref_field, pr.base.ref_field,
+                        md_link("Link", pr.html_url.as_ref().map(|u| u.as_str())))
+                );
+            }
+            ctx.send(rendered.reply(plain.unwrap_or(false))).await?;
+        }
+        Err(e) => {
+            ctx.say(format!("❌ Failed to fetch pull requests: {}", e)).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Show a pull request with its merge and review status
+#[poise::command(slash_command, rename = "view")]
+pub async fn view_pr(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Pull request number"] number: u64,
+) -> Result<(), Error> {
+    check_repo_name(&repo)?;
+    let state = ctx.data();
+    defer(ctx).await;
+
+    let pulls = state.octocrab.pulls(&state.repo_org(&repo).await, repo_name(&repo));
+    stats::count(ctx, Api::Rest);
+    let pr = match pulls.get(number).await {
+        Ok(pr) => pr,
+        Err(e) => {
+            ctx.say(format!("❌ Failed to fetch pull request #{}: {}", number, e)).await?;
+            return Ok(());
+        }
+    };
+    // Reviews are only decoration, show the PR without them if this fails
+    stats::count(ctx, Api::Rest);
+    let reviews = pulls.list_reviews(number).per_page(100).send().await.map(|p| p.items).unwrap_or_default();
+
+    ctx.send(poise::CreateReply::default().embed(build_pr_embed(&repo, &pr, &reviews))).await?;
+    Ok(())
+}
+
+/// Merge a pull request (asks for confirmation first)
+#[poise::command(slash_command, rename = "merge")]
+pub async fn merge_pr(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Pull request number"] number: u64,
+    #[description = "Merge method (default merge)"]
+    method: Option<MergeMethod>,
+) -> Result<(), Error> {
+    let _ = check_auth(ctx).await?; // Enforce auth
+    check_repo_name(&repo)?;
+    let method = method.unwrap_or(MergeMethod::Merge);
+    let state = ctx.data();
+    defer(ctx).await;
+
+    let pulls = state.octocrab.pulls(&state.repo_org(&repo).await, repo_name(&repo));
+    stats::count(ctx, Api::Rest);
+    let pr = match pulls.get(number).await {
+        Ok(pr) => pr,
+        Err(e) => {
+            ctx.say(format!("❌ Failed to fetch pull request #{}: {}", number, e)).await?;
+            return Ok(());
+        }
+    };
+    if pr.merged_at.is_some() {
+        ctx.say(format!("Pull request **#{}** in **{}** is already merged.", number, repo)).await?;
+        return Ok(());
+    }
+
+    // 1. Confirmation
+    let title = pr.title.clone().unwrap_or_default();
+    let embed = serenity::CreateEmbed::new()
+        .title("Confirm Merge")
+        .description(format!(
+            "**Pull request:** {} #{} {}\n**Branches:** `{}` ➔ `{}`\n**Method:** {}",
+            repo, number, title, pr.head.ref_field, pr.base.ref_field, method.name()
+        ))
+        .color(0xFEE75C);
+
+    let ctx_id = ctx.id();
+    let confirm_id = format!("merge_confirm_{}", ctx_id);
+    let cancel_id = format!("merge_cancel_{}", ctx_id);
+    let components = vec![serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(&confirm_id).label("Merge").style(serenity::ButtonStyle::Success),
+        serenity::CreateButton::new(&cancel_id).label("Cancel").style(serenity::ButtonStyle::Danger),
+    ])];
+
+    let reply = ctx.send(poise::CreateReply::default().embed(embed).components(components)).await?;
+    let interaction = reply.message().await?.await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .timeout(Duration::from_secs(60))
+        .await;
+
+    let Some(mci) = interaction else {
+        let _ = reply.edit(ctx, poise::CreateReply::default().content("⏰ Timed out.").components(vec![])).await;
+        return Ok(());
+    };
+    if mci.data.custom_id != confirm_id {
+        mci.create_response(ctx, serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new().content("❌ Cancelled.").components(vec![])
+        )).await?;
+        return Ok(());
+    }
+    if let Err(e) = mci.defer(ctx).await {
+        warn!("⚠️ Failed to defer confirmation: {}", e);
+    }
+
+    // 2. Merge exactly the commit that was confirmed; GitHub refuses if the branch moved since
+    stats::count(ctx, Api::Rest);
+    let client = state.octocrab_for(ctx.author().id.get()).await;
+    let embed = match client.pulls(&state.repo_org(&repo).await, repo_name(&repo)).merge(number).method(method.as_param()).sha(pr.head.sha.clone()).send().await {
+        Ok(result) if result.merged => serenity::CreateEmbed::new()
+            .title(format!("merged pull request #{}", number))
+            .description(format!("**{}** was merged into `{}` in **{}** ({})", title, pr.base.ref_field, repo, method.name()))
+            .color(0x8957E5)
+            .timestamp(serenity::Timestamp::now()),
+        Ok(result) => serenity::CreateEmbed::new()
+            .title("merge failed")
+            .description(result.message.unwrap_or_else(|| "GitHub did not merge the pull request.".to_string()))
+            .color(0xED4245),
+        Err(e) => {
+            // GitHub's own reason, e.g. "Pull Request is not mergeable" or missing required reviews
+            let reason = match &e {
+                octocrab::Error::GitHub { source, .. } => source.message.clone(),
+                other => other.to_string(),
+            };
+            serenity::CreateEmbed::new()
+                .title("merge failed")
+                .description(format!("error: {}", reason))
+                .color(0xED4245)
+        }
+    };
+    let embed = match &pr.html_url {
+        Some(url) => embed.url(url.to_string()),
+        None => embed,
+    };
+    reply.edit(ctx, poise::CreateReply::default().embed(embed).components(vec![])).await?;
+    Ok(())
+}
+
+/// Approve, request changes on, or comment on a pull request
+#[poise::command(slash_command, rename = "review")]
+pub async fn review_pr(
+    ctx: Context<'_>,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Pull request number"] number: u64,
+    #[description = "Review verdict"] event: ReviewEvent,
+    #[description = "Review comment (required unless approving)"] body: Option<String>,
+) -> Result<(), Error> {
+    let gh_user = check_auth(ctx).await?;
+    check_repo_name(&repo)?;
+    let mut body = body.unwrap_or_default();
+    if body.trim().is_empty() && event != ReviewEvent::Approve {
+        ctx.say("❌ Say what should change (or what you're commenting) in `body`.").await?;
+        return Ok(());
+    }
+    let state = ctx.data();
+    defer(ctx).await;
+
+    // Without their own token the review goes out under the bot's, so credit the person who submitted it
+    let client = match state.user_octocrab(ctx.author().id.get()).await {
+        Some(client) => client,
+        None => {
+            let attribution = format!("_Submitted by @{} from Discord_", gh_user);
+            body = if body.trim().is_empty() { attribution } else { format!("{}\n\n{}", body, attribution) };
+            state.octocrab.clone()
+        }
+    };
+
+    stats::count(ctx, Api::Rest);
+    match create_review(state, &client, &repo, number, event.as_api(), &body).await {
+        Ok(review) => {
+            let (verb, color) = match event {
+                ReviewEvent::Approve => ("approved", 0x57F287),
+                ReviewEvent::RequestChanges => ("requested changes on", 0xFEE75C),
+                ReviewEvent::Comment => ("reviewed", 0x5865F2),
+            };
+            let embed = serenity::CreateEmbed::new()
+                .title(format!("{} pull request #{}", verb, number))
+                .url(review.html_url.to_string())
+                .description(format!("submitted a review on **#{}** in **{}**", number, repo))
+                .color(color)
+                .timestamp(serenity::Timestamp::now());
+            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        }
+        Err(e) => {
+            let reason = match &e {
+                octocrab::Error::GitHub { source, .. } => source.message.clone(),
+                other => other.to_string(),
+            };
+            let embed = serenity::CreateEmbed::new()
+                .title("review failed")
+                .description(format!("error: {}", reason))
+                .color(0xED4245);
+            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Manage Organization Projects
+#[poise::command(slash_command, subcommands("list_projects", "view_project", "view_item", "search_items", "edit_project_item", "get_field", "bulk_move", "audit", "preset", "diff", "set_description", "add_item", "remove_item", "create_draft"))]
+pub async fn proj(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// List Projects in the Organization
+#[poise::command(slash_command, rename = "list")]
+pub async fn list_projects(
+    ctx: Context<'_>,
+    #[description = "Only this organization (default: all configured)"]
+    #[autocomplete = "org_autocomplete"]
+    org: Option<String>,
+) -> Result<(), Error> {
+    let state = ctx.data();
+    let orgs = match selected_orgs(state, org.as_deref()) {
+        Ok(orgs) => orgs,
+        Err(msg) => {
+            ctx.say(msg).await?;
+            return Ok(());
+        }
+    };
+    let projects: Vec<CachedProject> = state.projects.read().await.iter()
+        .filter(|p| orgs.iter().any(|o| o.eq_ignore_ascii_case(&p.org)))
+        .cloned()
+        .collect();
+
+    if projects.is_empty() {
+        if state.projects_loaded.load(Ordering::Relaxed) {
+            ctx.say(format!("{} has no projects.", orgs.join(", "))).await?;
+        } else {
+            ctx.say("⚠️ Projects haven't been loaded from GitHub yet (the last fetch failed). Try /refresh?").await?;
+        }
+        return Ok(());
+    }
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("Projects in {}", orgs.join(", ")))
+        .color(0xEB459E); // Pinkish
+
+    for proj in projects.iter() {
+        let value = match &proj.short_description {
+            Some(d) => format!("{}\n[View Board]({})", d, proj.url),
+            None => format!("[View Board]({})", proj.url),
+        };
+        embed = embed.field(&proj.title, value, false);
+    }
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+// Results per /proj search page
+const SEARCH_PAGE_SIZE: usize = 25;
+
+/// Search items across every project board
+#[poise::command(slash_command, rename = "search")]
+pub async fn search_items(
+    ctx: Context<'_>,
+    #[description = "Keywords, a number (123) or repo and number (Repo #123)"]
+    query: String,
+    #[description = "Page number (default 1)"]
+    page: Option<usize>,
+) -> Result<(), Error> {
+    let state = ctx.data();
+    let page_num = page.unwrap_or(1).max(1);
+    let words: Vec<String> = query.split_whitespace().map(|w| w.to_lowercase()).collect();
+    if words.is_empty() {
+        ctx.say("❌ Give me something to search for.").await?;
+        return Ok(());
+    }
+
+    // Every word has to match: numbers ("123", "#123") the item number, anything else the repo or title
+    let matches_item = |item: &CachedItem| {
+        let haystack = format!("{} {}", item.repo_name, item.title).to_lowercase();
+        words.iter().all(|w| match w.trim_start_matches('#').parse::<i64>() {
+            Ok(n) => item.number == n,
+            Err(_) => haystack.contains(w.as_str()),
+        })
+    };
+
+    // (project title, project url, matching items)
+    let mut groups: Vec<(String, String, Vec<CachedItem>)> = Vec::new();
+    for proj in state.projects.read().await.iter() {
+        let hits: Vec<CachedItem> = proj.items.iter().filter(|i| matches_item(i)).cloned().collect();
+        if !hits.is_empty() {
+            groups.push((proj.title.clone(), proj.url.clone(), hits));
+        }
+    }
+
+    if groups.is_empty() {
+        return search_items_on_github(ctx, &query).await;
+    }
+
+    let total: usize = groups.iter().map(|(_, _, hits)| hits.len()).sum();
+    let pages = total.div_ceil(SEARCH_PAGE_SIZE);
+    if page_num > pages {
+        ctx.say(format!("❌ Page {} is out of bounds ({} page(s) of results).", page_num, pages)).await?;
+        return Ok(());
+    }
+
+    // Walk the flattened results and keep this page's slice, still grouped by project
+    let start = (page_num - 1) * SEARCH_PAGE_SIZE;
+    let mut seen = 0;
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("🔍 Items matching \"{}\"", query))
+        .color(0x5865F2)
+        .footer(serenity::CreateEmbedFooter::new(format!("Page {}/{} • {} match(es) from the cache", page_num, pages, total)));
+    for (title, url, hits) in &groups {
+        let mut lines = Vec::new();
+        for item in hits {
+            if seen >= start && seen < start + SEARCH_PAGE_SIZE {
+                let item_url = format!("https://github.com/{}/{}/issues/{}", state.repo_org(&item.repo_name).await, item.repo_name, item.number);
+                lines.push(format!("{} **{}/{}** {}", state_icon(&item.state), item.repo_name, md_link(&format!("#{}", item.number), Some(&item_url)), item.title));
+            }
+            seen += 1;
+        }
+        if lines.is_empty() {
+            continue;
+        }
+        // Whole lines only, so links are never cut; fields hold 1024 chars
+        let mut value = String::new();
+        for line in &lines {
+            if value.len() + line.len() > 950 {
+                value.push_str("…\n");
+                break;
+            }
+            value.push_str(line);
+            value.push('\n');
+        }
+        embed = embed.field(title, format!("{}[View Board]({})", value, url), false);
+    }
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+// /proj search fallback when no cached item matches: a GitHub issue/PR search in the configured orgs
+async fn search_items_on_github(ctx: Context<'_>, query: &str) -> Result<(), Error> {
+    let state = ctx.data();
+    // Qualifiers (org:, repo:, ...) would widen the search past the configured orgs, keep plain words
+    let keywords: Vec<&str> = query.split_whitespace().filter(|w| !w.contains(':')).collect();
+    if keywords.is_empty() {
+        ctx.say(format!("No project items match `{}`.", query)).await?;
+        return Ok(());
+    }
+
+    defer(ctx).await;
+    let search = format!("{} {}", state.org_qualifiers(), keywords.join(" "));
+    let page = match search_issues(state, stats::source(ctx), &search, SEARCH_PAGE_SIZE as u8, 1).await {
+        Ok(page) => page,
+        Err(e) => {
+            ctx.say(format!("No project items match `{}`, and the GitHub search failed: {}", query, e)).await?;
+            return Ok(());
+        }
+    };
+    if page.items.is_empty() {
+        ctx.say(format!("No project items or GitHub issues match `{}`.", query)).await?;
+        return Ok(());
+    }
+
+    let lines: Vec<String> = page.items.iter().map(|i| {
+        let repo = i.repository_url.as_str().split('/').next_back().unwrap_or("?");
+        let state_name = match i.state {
+            octocrab::models::IssueState::Open => "OPEN",
+            _ => "CLOSED",
+        };
+        format!("{} **{}/{}** {}", state_icon(state_name), repo, md_link(&format!("#{}", i.number), Some(i.html_url.as_str())), i.title)
+    }).collect();
+    let total = page.total_count.unwrap_or(page.items.len() as u64);
+    let embed = serenity::CreateEmbed::new()
+        .title(format!("🔍 GitHub results for \"{}\"", query))
+        .description(truncate_to(&lines.join("\n"), 4000))
+        .color(0x5865F2)
+        .footer(serenity::CreateEmbedFooter::new(format!("Not on any cached board • showing {} of {} from GitHub search", page.items.len(), total)));
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// View items in a specific Project
+#[poise::command(slash_command, rename = "view")]
+#[allow(clippy::too_many_arguments)]
+pub async fn view_project(
+    ctx: Context<'_>,
+    #[description = "Project Title"] 
+    #[autocomplete = "project_autocomplete"]
+    title: String,
+    #[description = "Filter items (active [default], all)"]
+    filter: Option<String>,
+    #[description = "Page number (default 1)"]
+    page: Option<usize>,
+    #[description = "Field to show next to each item (e.g. 'Status')"]
+    #[autocomplete = "field_autocomplete"]
+    show_field: Option<String>,
+    #[description = "Group items by this field's value (e.g. 'Status')"]
+    #[autocomplete = "field_autocomplete"]
+    group_by: Option<String>,
+    #[description = "Only show issues/PRs carrying this label"]
+    label: Option<String>,
+    #[description = "Reply with plain markdown instead of an embed"]
+    plain: Option<bool>,
+) -> Result<(), Error> {
+    let view = ViewPreset { project: title, filter, show_field, group_by, label };
+    show_project_view(ctx, &view, page.unwrap_or(1).max(1), plain.unwrap_or(false)).await
+}
+
+// Body of /proj view, shared with /proj preset run
+async fn show_project_view(ctx: Context<'_>, view: &ViewPreset, page_num: usize, plain: bool) -> Result<(), Error> {
+    defer(ctx).await;
+    match build_project_view(ctx.data(), &ctx.command().qualified_name, view, page_num).await {
+        Ok((rendered, components)) => { ctx.send(rendered.reply(plain).components(components)).await?; }
+        Err(msg) => { ctx.say(msg).await?; }
+    }
+    Ok(())
+}
+
+// What /proj view reads of each item. Labels and field values only when the view uses them.
+fn view_item_fields(view: &ViewPreset) -> String {
+    let labels = if view.label.is_some() { "labels(first: 10) { nodes { name } }" } else { "" };
+    let field_values = if view.show_field.is_some() || view.group_by.is_some() { VIEW_FIELD_VALUES } else { "" };
+    format!(r#"
+    id
+    type
+    content {{
+        ... on Issue {{
+            title
+            number
+            url
+            repository {{ name }}
+            state
+            body
+            assignees(first: 3) {{ nodes {{ login }} }}
+            {labels}
+        }}
+        ... on PullRequest {{
+            title
+            number
+            url
+            repository {{ name }}
+            state
+            body
+            assignees(first: 3) {{ nodes {{ login }} }}
+            {labels}
+        }}
+        ... on DraftIssue {{
+            title
+            body
+        }}
+    }}
+    {field_values}
+"#)
+}
+
+const VIEW_FIELD_VALUES: &str = r#"
+    fieldValues(first: 20) {
+        nodes {
+            ... on ProjectV2ItemFieldTextValue { text field { ... on ProjectV2FieldCommon { name } } }
+            ... on ProjectV2ItemFieldDateValue { date field { ... on ProjectV2FieldCommon { name } } }
+            ... on ProjectV2ItemFieldSingleSelectValue { name field { ... on ProjectV2FieldCommon { name } } }
+            ... on ProjectV2ItemFieldNumberValue { number field { ... on ProjectV2FieldCommon { name } } }
+            ... on ProjectV2ItemFieldIterationValue { title field { ... on ProjectV2FieldCommon { name } } }
+        }
+    }
+"#;
+
+// What an item embed (/proj view-item and the board's select menu) reads of each item
+pub const DETAIL_ITEM_FIELDS: &str = r#"
+    content {
+        ... on Issue {
+            title
+            number
+            body
+            url
+            repository { name }
+            state
+            assignees(first: 3) { nodes { login } }
+            labels(first: 5) { nodes { name } }
+            milestone { title dueOn }
+            subIssuesSummary { total completed }
+            subIssues(first: 10) { nodes { number title state } }
+            closedByPullRequestsReferences(first: 5, includeClosedPrs: true) { totalCount nodes { number title url state repository { name } } }
+        }
+        ... on PullRequest {
+            title
+            number
+            body
+            url
+            repository { name }
+            state
+            assignees(first: 3) { nodes { login } }
+            milestone { title dueOn }
+        }
+    }
+    fieldValues(first: 20) {
+        nodes {
+            ... on ProjectV2ItemFieldTextValue { text field { ... on ProjectV2FieldCommon { name } } }
+            ... on ProjectV2ItemFieldDateValue { date field { ... on ProjectV2FieldCommon { name } } }
+            ... on ProjectV2ItemFieldSingleSelectValue { name field { ... on ProjectV2FieldCommon { name } } }
+            ... on ProjectV2ItemFieldNumberValue { number field { ... on ProjectV2FieldCommon { name } } }
+            ... on ProjectV2ItemFieldIterationValue { title startDate duration field { ... on ProjectV2FieldCommon { name } } }
+        }
+    }
+"#;
+
+// custom_id that renders `page` of this exact view (ProjRefresh). None when it can't be encoded:
+// a ':' in a segment before the label, or longer than Discord's 100 chars.
+fn view_refresh_id(proj: &CachedProject, view: &ViewPreset, filter: &str, page: usize) -> Option<String> {
+    let id = CustomId::ProjRefresh {
+        proj_id: proj.id.clone(),
+        page,
+        filter: filter.to_string(),
+        show_field: view.show_field.clone(),
+        group_by: view.group_by.clone(),
+        label: view.label.clone(),
+    }.to_string();
+    let encodable = !filter.contains(':') && [&view.show_field, &view.group_by].iter().all(|f| !f.as_deref().unwrap_or("").contains(':'));
+    (encodable && id.len() <= 100).then_some(id)
+}
+
+// Prev/Next target: the full view when it fits. Only the board's default view falls back to its
+// number; None for any other view, so paging never switches to a different view.
+fn view_page_id(proj: &CachedProject, view: &ViewPreset, filter: &str, page: usize) -> Option<String> {
+    let default_view = filter == "active" && view.show_field.is_none() && view.group_by.is_none() && view.label.is_none();
+    view_refresh_id(proj, view, filter, page)
+        .or_else(|| default_view.then(|| CustomId::ProjPage { number: proj.number, page }.to_string()))
+}
+
+// Fetches the board and renders one page of it. Err holds a message for the user.
+// `source` is what the API calls are counted under in /stats.
+pub async fn build_project_view(state: &BotState, source: &str, view: &ViewPreset, page_num: usize) -> Result<(Rendered, Vec<serenity::CreateActionRow>), String> {
+    let title = &view.project;
+    let filter = view.filter.clone().unwrap_or_else(|| "active".to_string()).to_lowercase();
+    let show_field = &view.show_field;
+    let group_by = &view.group_by;
+    let label = &view.label;
+    let page_size = 20;
+
+    // Scope the read lock
+    let project = find_project(&state.projects.read().await, title);
+    
+    match project {
+        Ok(proj) => {
+            // The whole board is fetched so paging, filtering and grouping happen locally
+            match fetch_project_items(state, source, &proj.id, &view_item_fields(view)).await {
+                Ok(nodes) => {
+                    
+                    // (group value, line, select menu entry); drafts have no menu entry
+                    let mut entries = Vec::new();
+                    let group_key = |item: &serde_json::Value| group_by.as_ref().and_then(|f| item_field_value(item, f));
+                    let has_label = |content: Option<&serde_json::Value>| match &label {
+                        Some(wanted) => content.and_then(|c| c.get("labels")).and_then(|l| l.get("nodes")).and_then(|n| n.as_array())
+                            .is_some_and(|labels| labels.iter().any(|l| l.get("name").and_then(|n| n.as_str()).is_some_and(|n| n.eq_ignore_ascii_case(wanted)))),
+                        None => true,
+                    };
+
+                    // " [Status: In Progress]" when show_field is set
+                    let field_suffix = |item: &serde_json::Value| match &show_field {
+                        Some(f) => format!(" [{}: {}]", f, item_field_value(item, f).unwrap_or_else(|| "-".to_string())),
+                        None => String::new(),
+                    };
+
+                    for item in &nodes {
+                        let Some((line, menu)) = project_item_line(item) else { continue; };
+                        let content = item.get("content");
+                        if menu.is_some() {
+                            // Filtering
+                            let state = content.and_then(|c| c.get("state")).and_then(|s| s.as_str()).unwrap_or("");
+                            let is_closed = state == "CLOSED" || state == "MERGED";
+                            if filter == "active" && is_closed { continue; }
+                            if !has_label(content) { continue; }
+                        } else if label.is_some() {
+                            // Drafts have no labels
+                            continue;
+                        }
+                        entries.push((group_key(item), format!("{}{}", line, field_suffix(item)), menu));
+                    }
+
+                    // Grouping: sort by value, items without one go last under "(No value)"
+                    let no_value_count = entries.iter().filter(|(g, _, _)| g.is_none()).count();
+                    if group_by.is_some() {
+                        entries.sort_by(|a, b| (a.0.is_none(), &a.0).cmp(&(b.0.is_none(), &b.0)));
+                    }
+
+                    // Recalculate pagination based on filtered list
+                    let total_items = entries.len();
+                    // Re-calculate start_idx in case filter changed things
+                    let start_idx = (page_num - 1) * page_size;
+                    
+                    if start_idx >= total_items && total_items > 0 {
+                         return Err(format!("Page {} is out of bounds.", page_num));
+                    }
+                    if entries.is_empty() {
+                         let label_note = label.as_ref().map(|l| format!(" and label '{}'", l)).unwrap_or_default();
+                         return Err(format!("No items found in project {} with filter '{}'{}.", proj.title, filter, label_note));
+                    }
+
+                    let end_idx = std::cmp::min(start_idx + page_size, total_items);
+                    let page_entries = &entries[start_idx..end_idx];
+                    let page_menu_opts = page_entries.iter().filter_map(|(_, _, m)| m.as_ref());
+
+                    // A group header before the first item of each group on the page
+                    let mut page_display = Vec::new();
+                    for (i, (group, line, _)) in page_entries.iter().enumerate() {
+                        if let Some(field) = &group_by {
+                            if i == 0 || page_entries[i - 1].0 != *group {
+                                page_display.push(format!("__**{}: {}**__", field, group.as_deref().unwrap_or("(No value)")));
+                            }
+                        }
+                        page_display.push(line.clone());
+                    }
+
+                    let prev_id = (page_num > 1).then(|| view_page_id(&proj, view, &filter, page_num - 1)).flatten();
+                    let next_id = (end_idx < total_items).then(|| view_page_id(&proj, view, &filter, page_num + 1)).flatten();
+                    let mut footer = format!("Page {}/{} • Total: {}", page_num, total_items.div_ceil(page_size), total_items);
+                    // No Prev/Next for views too long for a button; say how to get to the other pages
+                    if total_items > page_size && prev_id.is_none() && next_id.is_none() {
+                        footer.push_str(" • Use the page option for other pages");
+                    }
+                    if let Some(field) = &group_by {
+                        footer.push_str(&format!(" • No {}: {}", field, no_value_count));
+                    }
+                    if let Some(l) = &label {
+                        footer.push_str(&format!(" • Label: {}", l));
+                    }
+
+                    let rendered = Rendered::new(format!("Project: {} ({})", proj.title, filter), 0xEB459E)
+                        .url(&proj.url)
+                        .description(page_display.join("\n"))
+                        .footer(footer);
+
+                    let mut components = Vec::new();
+                    
+                    // 1. Select Menu
+                    let mut select_opts_vec = Vec::new();
+                    for (num, title, repo) in page_menu_opts {
+                        // Label max 100 chars
+                        let label = format!("{} #{}: {}", repo, num, title);
+                        let label = if label.len() > 95 { format!("{}...", truncate_to(&label, 95)) } else { label };
+                        select_opts_vec.push(CreateSelectMenuOption::new(label, num.to_string()));
+                    }
+                    
+                    if !select_opts_vec.is_empty() {
+                        let menu_id = CustomId::ProjSelect { proj_id: proj.id.clone() }.to_string();
+                        let menu = CreateSelectMenu::new(menu_id, CreateSelectMenuKind::String { options: select_opts_vec })
+                            .placeholder("🔍 Select an item to view details...");
+                        components.push(serenity::CreateActionRow::SelectMenu(menu));
+                    }
+
+                    // 2. Buttons; Prev/Next keep the filter, label, show_field and group_by like Refresh does
+                    let mut buttons = Vec::new();
+                    if let Some(id) = prev_id {
+                        buttons.push(serenity::CreateButton::new(id).label("◀️ Prev").style(serenity::ButtonStyle::Secondary));
+                    }
+                    buttons.push(serenity::CreateButton::new_link(&proj.url).label("Open Board"));
+                    if let Some(id) = next_id {
+                         buttons.push(serenity::CreateButton::new(id).label("Next ▶️").style(serenity::ButtonStyle::Secondary));
+                    }
+                    // Re-renders this exact page and view in place; skipped when the view doesn't fit in a custom_id
+                    if let Some(refresh_id) = view_refresh_id(&proj, view, &filter, page_num) {
+                        buttons.push(serenity::CreateButton::new(refresh_id).label("Refresh").emoji('🔄').style(serenity::ButtonStyle::Secondary));
+                    }
+                    components.push(serenity::CreateActionRow::Buttons(buttons));
+
+                    Ok((rendered, components))
+                }
+                Err(e) => Err(format!("❌ Failed to fetch project items: {}", e)),
+            }
+        }
+        Err(msg) => Err(msg),
+    }
+}
+
+/// Change a project's short description (and optionally its README)
+#[poise::command(slash_command, rename = "set-description")]
+pub async fn set_description(
+    ctx: Context<'_>,
+    #[description = "Project Title"]
+    #[autocomplete = "project_autocomplete"]
+    title: String,
+    #[description = "New short description (empty clears it)"]
+    text: String,
+    #[description = "New README (markdown); left unchanged when omitted"]
+    readme: Option<String>,
+) -> Result<(), Error> {
+    let _ = check_auth(ctx).await?;
+    let state = ctx.data();
+    let proj = find_project(&state.projects.read().await, &title).map_err(BotError::NotFound)?;
+    defer(ctx).await;
+
+    // Omitted inputs are left alone by updateProjectV2, so the README only changes when given
+    let mut input = serde_json::json!({ "projectId": proj.id, "shortDescription": text.trim() });
+    if let Some(r) = &readme {
+        input["readme"] = serde_json::json!(r);
+    }
+    let mutation = serde_json::json!({
+        "query": r#"
+            mutation($input: UpdateProjectV2Input!) {
+                updateProjectV2(input: $input) { projectV2 { title shortDescription } }
+            }
+        "#,
+        "variables": { "input": input }
+    });
+    let resp = match graphql_mutation(state, stats::source(ctx), &mutation).await {
+        Ok(r) => r,
+        Err(e) => {
+            ctx.say(format!("❌ Failed to update **{}**: {}", proj.title, e)).await?;
+            return Ok(());
+        }
+    };
+    let Some(updated) = resp.get("data").and_then(|d| d.get("updateProjectV2")).and_then(|u| u.get("projectV2")) else {
+        let msg = graphql_errors(&resp).unwrap_or_else(|| "unknown error".to_string());
+        ctx.say(format!("❌ Failed to update **{}**: {}", proj.title, msg)).await?;
+        return Ok(());
+    };
+
+    // Keep the cache in line with what GitHub now has
+    let new_title = updated.get("title").and_then(|t| t.as_str()).unwrap_or(&proj.title).to_string();
+    let new_description = updated.get("shortDescription").and_then(|d| d.as_str()).filter(|d| !d.is_empty()).map(|d| d.to_string());
+    if let Some(p) = state.projects.write().await.iter_mut().find(|p| p.id == proj.id) {
+        p.title = new_title.clone();
+        p.short_description = new_description.clone();
+    }
+
+    let mut description = format!("**Description:** {}", new_description.as_deref().unwrap_or("*(none)*"));
+    if readme.is_some() {
+        description.push_str("\n**README** updated.");
+    }
+    let embed = serenity::CreateEmbed::new()
+        .title(format!("📝 Updated {}", new_title))
+        .url(&proj.url)
+        .description(description)
+        .color(0x57F287);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Add an existing issue or pull request to a project
+#[poise::command(slash_command, rename = "add-item")]
+pub async fn add_item(
+    ctx: Context<'_>,
+    #[description = "Project Title"]
+    #[autocomplete = "project_autocomplete"]
+    title: String,
+    #[description = "Repository name"] 
+    #[autocomplete = "repo_autocomplete"]
+    repo: String,
+    #[description = "Issue or pull request number (or an open issue's title)"]
+    #[autocomplete = "issue_autocomplete"]
+    issue: String,
+    #[description = "Initial Status on the board"]
+    #[autocomplete = "status_autocomplete"]
+    status: Option<String>,
+) -> Result<(), Error> {
+    let _ = check_auth(ctx).await?;
+    check_repo_name(&repo)?;
+    let state = ctx.data();
+    let proj = find_project(&state.projects.read().await, &title).map_err(BotError::NotFound)?;
+    let number = match resolve_issue_number(state, &repo, &issue).await {
+        Some(n) => n,
+        None => {
+            ctx.say(format!("❌ No open issue in **{}** matches `{}`.", repo, issue)).await?;
+            return Ok(());
+        }
+    };
+    // Resolve the Status option up front so a typo doesn't leave a half-done add
+    let status = match status.as_deref().map(|s| status_option(&proj, s)).transpose() {
+        Ok(s) => s,
+        Err(msg) => {
+            ctx.say(format!("❌ {}", msg)).await?;
+            return Ok(());
+        }
+    };
+    if proj.items.iter().any(|i| i.number == number as i64 && i.repo_name == repo_name(&repo)) {
+        ctx.say(format!("ℹ️ **{}** #{} is already in **{}**.", repo, number, proj.title)).await?;
+        return Ok(());
+    }
+    defer(ctx).await;
+
+    // 1. Node id of the issue/PR
+    let query = serde_json::json!({
+        "query": r#"
+            query($owner: String!, $repo: String!, $number: Int!) {
+                repository(owner: $owner, name: $repo) {
+                    issueOrPullRequest(number: $number) {
+                        ... on Issue { id title state assignees(first: 5) { nodes { login } } }
+                        ... on PullRequest { id title state assignees(first: 5) { nodes { login } } }
+                    }
+                }
+            }
+        "#,
+        "variables": { "owner": state.repo_org(&repo).await, "repo": repo_name(&repo), "number": number }
+    });
+    let content = match graphql(state, stats::source(ctx), &query).await {
+        Ok(resp) => resp.get("data").and_then(|d| d.get("repository")).and_then(|r| r.get("issueOrPullRequest")).cloned()
+            .filter(|c| c.get("id").is_some()),
+        Err(e) => {
+            ctx.say(format!("❌ Failed to look up #{}: {}", number, e)).await?;
+            return Ok(());
+        }
+    };
+    let Some(content) = content else {
+        ctx.say(format!("❌ No issue or pull request #{} in **{}**.", number, repo)).await?;
+        return Ok(());
+    };
+    let content_id = content.get("id").and_then(|i| i.as_str()).unwrap_or_default();
+    let item_title = content.get("title").and_then(|t| t.as_str()).unwrap_or("?").to_string();
+
+    // 2. Add it (GitHub hands back the existing item if someone added it meanwhile)
+    let item_id = match add_project_item(state, stats::source(ctx), &proj.id, content_id).await {
+        Ok(Some(id)) => id,
+        Ok(None) => {
+            ctx.say(format!("❌ GitHub didn't add #{} to **{}**.", number, proj.title)).await?;
+            return Ok(());
+        }
+        Err(e) => {
+            ctx.say(format!("❌ Failed to add #{} to **{}**: {}", number, proj.title, e)).await?;
+            return Ok(());
+        }
+    };
+
+    // Autocomplete and /proj view pick it up without a refresh
+    if let Some(p) = state.projects.write().await.iter_mut().find(|p| p.id == proj.id) {
+        if !p.items.iter().any(|i| i.number == number as i64 && i.repo_name == repo_name(&repo)) {
+            p.items.push(CachedItem {
+                id: item_id.clone(),
+                title: item_title.clone(),
+                number: number as i64,
+                repo_name: repo_name(&repo).to_string(),
+                state: content.get("state").and_then(|s| s.as_str()).unwrap_or("OPEN").to_string(),
+                assignees: content.get("assignees").and_then(|a| a.get("nodes")).and_then(|n| n.as_array())
+                    .map(|arr| arr.iter().filter_map(|a| a.get("login").and_then(|l| l.as_str()).map(|l| l.to_string())).collect())
+                    .unwrap_or_default(),
+            });
+        }
+    }
+
+    // 3. Initial Status; the item stays on the board even if this part fails
+    let mut description = format!("Added **{}** #{} {} to **{}**", repo, number, item_title, proj.title);
+    let mut color = 0x57F287;
+    if let Some((field, option_name, option_id)) = status {
+        let result = set_initial_status(ctx.http(), state, &ctx.command().qualified_name, ctx.author().id.get(), &proj, &item_id, number as i64, &field, &option_name, &option_id).await;
+        description.push('\n');
+        description.push_str(&result.unwrap_or_else(|line| { color = 0xFEE75C; line }));
+    }
+
+    let embed = serenity::CreateEmbed::new()
+        .title(format!("➕ Added to {}", proj.title))
+        .url(&proj.url)
+        .description(description)
+        .color(color);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Remove an item from a project (asks for confirmation first)
+#[poise::command(slash_command, rename = "remove-item")]
+pub async fn remove_item(
+    ctx: Context<'_>,
+    #[description = "Project Title"]
+    #[autocomplete = "project_autocomplete"]
+    title: String,
+    #[description = "Item ID (e.g. '123' or 'Repo #123')"]
+    #[autocomplete = "item_autocomplete"]
+    item_query: String,
+) -> Result<(), Error> {
+    let _ = check_auth(ctx).await?;
+    let state = ctx.data();
+    let proj = find_project(&state.projects.read().await, &title).map_err(BotError::NotFound)?;
+    defer(ctx).await;
+
+    // 1. Item node id
+    let target_num = parse_item_number(&item_query);
+    let item = fetch_project_item(state, stats::source(ctx), &proj.id, target_num).await?;
+    let Some(item) = item else {
+        ctx.say(format!("❌ Item #{} not found in project.", target_num)).await?;
+        return Ok(());
+    };
+    let item_node_id = item.get("id").and_then(|s| s.as_str()).unwrap_or("").to_string();
+    let content = item.get("content");
+    let item_title = content.and_then(|c| c.get("title")).and_then(|s| s.as_str()).unwrap_or("").to_string();
+    let item_repo = content.and_then(|c| c.get("repository")).and_then(|r| r.get("name")).and_then(|n| n.as_str()).unwrap_or("").to_string();
+
+    // 2. Confirmation
+    let embed = serenity::CreateEmbed::new()
+        .title("Confirm Removal")
+        .description(format!(
+            "**Project:** {}\n**Item:** {} #{} {}\nThe item and its field values are removed from the board; the issue itself is kept.",
+            proj.title, item_repo, target_num, item_title
+        ))
+        .color(0xFEE75C);
+
+    let ctx_id = ctx.id();
+    let confirm_id = format!("remove_confirm_{}", ctx_id);
+    let cancel_id = format!("remove_cancel_{}", ctx_id);
+    let components = vec![serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(&confirm_id).label("Remove").style(serenity::ButtonStyle::Danger),
+        serenity::CreateButton::new(&cancel_id).label("Cancel").style(serenity::ButtonStyle::Secondary),
+    ])];
+
+    let reply = ctx.send(poise::CreateReply::default().embed(embed).components(components)).await?;
+    let interaction = reply.message().await?.await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .timeout(Duration::from_secs(60))
+        .await;
+
+    let Some(mci) = interaction else {
+        let _ = reply.edit(ctx, poise::CreateReply::default().content("⏰ Timed out.").components(vec![])).await;
+        return Ok(());
+    };
+    if mci.data.custom_id != confirm_id {
+        mci.create_response(ctx, serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new().content("❌ Cancelled.").components(vec![])
+        )).await?;
+        return Ok(());
+    }
+    if let Err(e) = mci.defer(ctx).await {
+        warn!("⚠️ Failed to defer confirmation: {}", e);
+    }
+
+    // 3. Delete
+    let mutation = serde_json::json!({
+        "query": r#"
+            mutation($projectId: ID!, $itemId: ID!) {
+                deleteProjectV2Item(input: { projectId: $projectId, itemId: $itemId }) { deletedItemId }
+            }
+        "#,
+        "variables": { "projectId": proj.id, "itemId": item_node_id }
+    });
+    let _item_guard = lock_item(state, &item_node_id).await;
+    // Not retried: a resend after the delete went through fails as if nothing was removed
+    let result = match graphql(state, stats::source(ctx), &mutation).await {
+        Ok(resp) if resp.get("data").and_then(|d| d.get("deleteProjectV2Item")).is_some_and(|d| !d.is_null()) => Ok(()),
+        Ok(resp) => Err(graphql_errors(&resp).unwrap_or_else(|| "unknown error".to_string())),
+        Err(e) => Err(e.to_string()),
+    };
+
+    let embed = match result {
+        Ok(()) => {
+            if let Some(p) = state.projects.write().await.iter_mut().find(|p| p.id == proj.id) {
+                p.items.retain(|i| !(i.number == target_num && i.repo_name == item_repo));
+            }
+            serenity::CreateEmbed::new()
+                .title(format!("➖ Removed from {}", proj.title))
+                .url(&proj.url)
+                .description(format!("**{}** #{} {} is no longer on the board", item_repo, target_num, item_title))
+                .color(0x57F287)
+        }
+        Err(e) => serenity::CreateEmbed::new()
+            .title("removal failed")
+            .description(format!("error: {}", e))
+            .color(0xED4245),
+    };
+    reply.edit(ctx, poise::CreateReply::default().embed(embed).components(vec![])).await?;
+    Ok(())
+}
+
+/// Create a draft issue on a project (opens a form for the title and body)
+#[poise::command(slash_command, rename = "create-draft")]
+pub async fn create_draft(
+    ctx: Context<'_>,
+    #[description = "Project Title"]
+    #[autocomplete = "project_autocomplete"]
+    title: String,
+    #[description = "Initial Status on the board"]
+    #[autocomplete = "status_autocomplete"]
+    status: Option<String>,
+) -> Result<(), Error> {
+    let _ = check_auth(ctx).await?;
+    let state = ctx.data();
+    let proj = find_project(&state.projects.read().await, &title).map_err(BotError::NotFound)?;
+    // The Status option rides along in the modal's custom_id
+    let status = match status.as_deref().map(|s| status_option(&proj, s)).transpose() {
+        Ok(s) => s,
+        Err(msg) => {
+            ctx.say(format!("❌ {}", msg)).await?;
+            return Ok(());
+        }
+    };
+    let poise::Context::Application(app_ctx) = ctx else { return Ok(()); };
+
+    let modal_id = CustomId::CreateDraft { proj_id: proj.id.clone(), status: status.map(|(_, _, id)| id) }.to_string();
+    let title_input = CreateInputText::new(serenity::InputTextStyle::Short, "Title", "title")
+        .max_length(256);
+    let body_input = CreateInputText::new(serenity::InputTextStyle::Paragraph, "Body", "body")
+        .placeholder("Markdown is supported")
+        .max_length(MAX_MODAL_TEXT_LEN)
+        .required(false);
+    let modal = CreateModal::new(modal_id, format!("New draft in {}", truncate_to(&proj.title, 30)))
+        .components(vec![serenity::CreateActionRow::InputText(title_input), serenity::CreateActionRow::InputText(body_input)]);
+    app_ctx.interaction.create_response(ctx, serenity::CreateInteractionResponse::Modal(modal)).await?;
+    Ok(())
+}
+
+// Creates the draft from the /proj create-draft form and sets its initial Status, if any.
+// Drafts have no number, so they stay out of the item cache.
+pub async fn file_draft(http: &serenity::Http, state: &BotState, discord_id: u64, proj_id: &str, status_option_id: Option<&str>, title: &str, body: &str) -> serenity::CreateEmbed {
+    let Some(proj) = state.projects.read().await.iter().find(|p| p.id == proj_id).cloned() else {
+        return serenity::CreateEmbed::new()
+            .title("draft creation failed")
+            .description("This board is no longer in the cache. Try /refresh?")
+            .color(0xED4245);
+    };
+
+    let item_id = match add_draft_item(state, "component", &proj.id, title, body).await {
+        Ok(id) => id,
+        Err(e) => {
+            return serenity::CreateEmbed::new()
+                .title("draft creation failed")
+                .description(format!("error: {}", e))
+                .color(0xED4245);
+        }
+    };
+
+    let mut description = format!("Created draft **{}** on **{}**", title, proj.title);
+    let mut color = 0x57F287;
+    let status = status_option_id.and_then(|id| {
+        let field = proj.fields.iter().find(|f| f.name.eq_ignore_ascii_case("Status"))?;
+        let name = field.options.iter().find(|(_, v)| v.as_str() == id)?.0.clone();
+        Some((field.clone(), name, id.to_string()))
+    });
+    if let Some((field, option_name, option_id)) = status {
+        let result = set_initial_status(http, state, "component", discord_id, &proj, &item_id, DRAFT_ITEM_NUMBER, &field, &option_name, &option_id).await;
+        description.push('\n');
+        description.push_str(&result.unwrap_or_else(|line| { color = 0xFEE75C; line }));
+    }
+
+    serenity::CreateEmbed::new()
+        .title(format!("📝 Draft added to {}", proj.title))
+        .url(&proj.url)
+        .description(description)
+        .color(color)
+}
+
+// Sets the Status of an item that was just added to a board, under the item lock like any other
+// edit, and records it in the audit log. Returns the line for the reply; Err when the item stays
+// on the board without a Status. `source` is what the call is counted under in /stats.
+#[allow(clippy::too_many_arguments)]
+async fn set_initial_status(
+    http: &serenity::Http,
+    state: &BotState,
+    source: &str,
+    discord_id: u64,
+    proj: &CachedProject,
+    item_id: &str,
+    item_number: i64,
+    field: &CachedField,
+    option_name: &str,
+    option_id: &str,
+) -> Result<String, String> {
+    let _item_guard = lock_item(state, item_id).await;
+    let old_value = current_field_value(state, source, item_id, &field.name).await;
+    match set_item_option(state, source, &proj.id, item_id, &field.id, option_id).await {
+        Ok(()) => {
+            record_edit(http, state, AuditEntry {
+                timestamp: chrono::Utc::now().timestamp(),
+                discord_id,
+                github_user: state.user_mapping.read().await.map.get(&discord_id).cloned(),
+                project_id: proj.id.clone(),
+                project_title: proj.title.clone(),
+                item_number,
+                field: field.name.clone(),
+                old_value,
+                new_value: option_name.to_string(),
+            }).await;
+            Ok(format!("**{}** set to **{}**", field.name, option_name))
+        }
+        Err(e) => Err(format!("⚠️ Setting **{}** to **{}** failed, the item has no {}: {}", field.name, option_name, field.name, e)),
+    }
+}
+
+// The project's Status field and the option named `name` (case-insensitive)
+fn status_option(proj: &CachedProject, name: &str) -> Result<(CachedField, String, String), String> {
+    let field = proj.fields.iter()
+        .find(|f| f.name.eq_ignore_ascii_case("Status") && !f.options.is_empty())
+        .ok_or_else(|| format!("**{}** has no Status field.", proj.title))?;
+    let (option_name, option_id) = field.options.iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| {
+            let mut names: Vec<&str> = field.options.keys().map(|k| k.as_str()).collect();
+            names.sort();
+            format!("**{}** is not a Status in **{}**. Options: {}", name.trim(), proj.title, names.join(", "))
+        })?;
+    Ok((field.clone(), option_name.clone(), option_id.clone()))
+}
+
+/// Compare the cached items of two projects
+#[poise::command(slash_command)]
+pub async fn diff(
+    ctx: Context<'_>,
+    #[description = "First project"]
+    #[autocomplete = "project_autocomplete"]
+    a: String,
+    #[description = "Second project"]
+    #[autocomplete = "project_autocomplete"]
+    b: String,
+) -> Result<(), Error> {
+    let state = ctx.data();
+    let (proj_a, proj_b) = {
+        let projects = state.projects.read().await;
+        (find_project(&projects, &a), find_project(&projects, &b))
+    };
+    let (proj_a, proj_b) = match (proj_a, proj_b) {
+        (Ok(pa), Ok(pb)) => (pa, pb),
+        (Err(msg), _) | (_, Err(msg)) => return Err(BotError::NotFound(msg).into()),
+    };
+
+    // Items are the same issue/PR when repo and number match
+    let only_in = |left: &CachedProject, right: &CachedProject| -> Vec<String> {
+        left.items.iter()
+            .filter(|i| !right.items.iter().any(|j| j.number == i.number && j.repo_name == i.repo_name))
+            .map(|i| format!("• [{}#{}](https://github.com/{}/{}/issues/{}) {}",
+                i.repo_name, i.number, if left.org.is_empty() { state.default_org() } else { &left.org }, i.repo_name, i.number, i.title.chars().take(40).collect::<String>()))
+            .collect()
+    };
+    // Embed field values max out at 1024 chars
+    let render = |lines: &[String]| -> String {
+        if lines.is_empty() { return "None".to_string(); }
+        let mut out = String::new();
+        for (shown, line) in lines.iter().enumerate() {
+            if out.len() + line.len() + 20 > 1024 {
+                out.push_str(&format!("…and {} more", lines.len() - shown));
+                break;
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+        out
+    };
+
+    let missing_in_b = only_in(&proj_a, &proj_b);
+    let missing_in_a = only_in(&proj_b, &proj_a);
+    let embed = serenity::CreateEmbed::new()
+        .title(format!("Diff: {} ↔ {}", proj_a.title, proj_b.title))
+        .field(format!("Only in {} ({})", proj_a.title, missing_in_b.len()), render(&missing_in_b), false)
+        .field(format!("Only in {} ({})", proj_b.title, missing_in_a.len()), render(&missing_in_a), false)
+        .footer(serenity::CreateEmbedFooter::new("Based on cached items; /refresh for the latest"))
+        .color(0xEB459E);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Saved /proj view filters
+#[poise::command(slash_command, subcommands("preset_save", "preset_run", "preset_list", "preset_delete"))]
+pub async fn preset(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Save /proj view arguments under a name
+#[poise::command(slash_command, rename = "save", ephemeral)]
+pub async fn preset_save(
+    ctx: Context<'_>,
+    #[description = "Preset name"] name: String,
+    #[description = "Project Title"] 
+    #[autocomplete = "project_autocomplete"]
+    title: String,
+    #[description = "Filter items (active [default], all)"]
+    filter: Option<String>,
+    #[description = "Field to show next to each item (e.g. 'Status')"]
+    #[autocomplete = "field_autocomplete"]
+    show_field: Option<String>,
+    #[description = "Group items by this field's value (e.g. 'Status')"]
+    #[autocomplete = "field_autocomplete"]
+    group_by: Option<String>,
+    #[description = "Only show issues/PRs carrying this label"]
+    label: Option<String>,
+) -> Result<(), Error> {
+    let view = ViewPreset { project: title, filter, show_field, group_by, label };
+    let description = view.describe();
+    let saved = {
+        let mut presets = ctx.data().presets.write().await;
+        presets.users.entry(ctx.author().id.get()).or_default().insert(name.to_lowercase(), view);
+        presets.save(&ctx.data().presets_path)
+    };
+    ctx.say(format!("💾 Saved preset **{}**: {}{}", name, description, save_warning(saved, "presets"))).await?;
+    Ok(())
+}
+
+/// Run a saved /proj view preset
+#[poise::command(slash_command, rename = "run")]
+pub async fn preset_run(
+    ctx: Context<'_>,
+    #[description = "Preset name"]
+    #[autocomplete = "preset_autocomplete"]
+    name: String,
+    #[description = "Page number (default 1)"]
+    page: Option<usize>,
+) -> Result<(), Error> {
+    let view = ctx.data().presets.read().await.users.get(&ctx.author().id.get())
+        .and_then(|p| p.get(&name.to_lowercase()))
+        .cloned();
+    match view {
+        Some(view) => show_project_view(ctx, &view, page.unwrap_or(1).max(1), false).await,
+        None => {
+            ctx.say(format!("❌ You have no preset named **{}**. See `/proj preset list`.", name)).await?;
+            Ok(())
+        }
+    }
+}
+
+/// List your saved /proj view presets
+#[poise::command(slash_command, rename = "list", ephemeral)]
+pub async fn preset_list(ctx: Context<'_>) -> Result<(), Error> {
+    let mut lines: Vec<String> = ctx.data().presets.read().await.users.get(&ctx.author().id.get())
+        .map(|p| p.iter().map(|(name, view)| format!("• **{}**: {}", name, view.describe())).collect())
+        .unwrap_or_default();
+    if lines.is_empty() {
+        ctx.say("You have no presets yet. Save one with `/proj preset save`.").await?;
+        return Ok(());
+    }
+    lines.sort();
+
+    let embed = serenity::CreateEmbed::new()
+        .title("Your presets")
+        .description(lines.join("\n"))
+        .color(0xEB459E);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Delete a saved /proj view preset
+#[poise::command(slash_command, rename = "delete", ephemeral)]
+pub async fn preset_delete(
+    ctx: Context<'_>,
+    #[description = "Preset name"]
+    #[autocomplete = "preset_autocomplete"]
+    name: String,
+) -> Result<(), Error> {
+    let (removed, saved) = {
+        let mut presets = ctx.data().presets.write().await;
+        let removed = presets.users.get_mut(&ctx.author().id.get())
+            .and_then(|p| p.remove(&name.to_lowercase()))
+            .is_some();
+        let saved = if removed { presets.save(&ctx.data().presets_path) } else { Ok(()) };
+        (removed, saved)
+    };
+    if removed {
+        ctx.say(format!("🗑️ Deleted preset **{}**.{}", name, save_warning(saved, "presets"))).await?;
+    } else {
+        ctx.say(format!("❌ You have no preset named **{}**.", name)).await?;
+    }
+    Ok(())
+}
+
+/// View details of a specific item in a project
+#[poise::command(slash_command, rename = "view-item")]
+pub async fn view_item(
+    ctx: Context<'_>,
+    #[description = "Project Title"] 
+    #[autocomplete = "project_autocomplete"]
+    title: String,
+    #[description = "Item ID (e.g. '123' or 'backend-service #123')"]
+    #[autocomplete = "item_autocomplete"]
+    item_query: String,
+    #[description = "Attach the raw GraphQL item JSON (bot owners only)"]
+    raw: Option<bool>,
+) -> Result<(), Error> {
+    // Re-using the logic from view_project but finding specific item
+    // In production, you'd want a more direct lookup
+    let state = ctx.data();
+    let raw = raw.unwrap_or(false);
+    if raw && !ctx.framework().options().owners.contains(&ctx.author().id) {
+        ctx.send(poise::CreateReply::default().content("🚫 Only bot owners can attach the raw item JSON.").ephemeral(true)).await?;
+        return Ok(());
+    }
+    
+     // Scope the read lock
+    let project = find_project(&state.projects.read().await, &title);
+    
+    match project {
+        Ok(proj) => {
+            defer(ctx).await;
+            match fetch_project_items(state, stats::source(ctx), &proj.id, DETAIL_ITEM_FIELDS).await {
+                Ok(nodes) => {
                     
-                    // Improved parsing for "Repo #123" or "123"
-                    let target_num = if let Some(idx) = item_query.find('#') {
-                        item_query[idx+1..].chars().take_while(|c| c.is_ascii_digit()).collect::<String>()
-                    } else {
-                        item_query.chars().take_while(|c| c.is_ascii_digit()).collect::<String>()
-                    };
-                    let target_num: i64 = target_num.parse().unwrap_or(0);
+                    let target_num = parse_item_number(&item_query);
                     
-                    if let Some(nodes) = json_resp.get("data")
-                        .and_then(|d| d.get("node"))
-                        .and_then(|d| d.get("items"))
-                        .and_then(|d| d.get("nodes"))
-                        .and_then(|d| d.as_array()) 
-                    {
-                        for item in nodes {
-                            if let Some(embed) = build_item_embed(item, target_num) {
-                                let components = vec![serenity::CreateActionRow::Buttons(vec![
-                                    serenity::CreateButton::new(format!("edit:item:{}:{}", proj.id, target_num))
-                                        .label("✏️ Edit Item")
-                                        .style(serenity::ButtonStyle::Secondary)
-                                ])];
-                                ctx.send(poise::CreateReply::default().embed(embed).components(components)).await?;
-                                return Ok(());
+                    let (field_emoji, due_soon_days) = {
+                        let configs = state.guild_configs.read().await;
+                        let guild_id = ctx.guild_id().map(|g| g.get());
+                        (configs.field_emoji(guild_id), configs.due_soon_days(guild_id))
+                    };
+                    let users = state.users.read().await.clone();
+                    for item in &nodes {
+                        if let Some(embed) = build_item_embed(item, target_num, &field_emoji, &users, due_soon_days) {
+                            let mut buttons = vec![
+                                serenity::CreateButton::new(CustomId::EditItem { proj_id: proj.id.clone(), num: target_num }.to_string())
+                                    .label("✏️ Edit Item")
+                                    .style(serenity::ButtonStyle::Secondary)
+                            ];
+                            buttons.extend(body_more_button(item));
+                            let components = vec![serenity::CreateActionRow::Buttons(buttons)];
+                            let mut reply = poise::CreateReply::default().embed(embed).components(components);
+                            // The exact node build_item_embed rendered, for chasing schema/rendering mismatches
+                            if raw {
+                                let pretty = serde_json::to_string_pretty(item)?;
+                                reply = reply.attachment(serenity::CreateAttachment::bytes(pretty.into_bytes(), format!("item-{}.json", target_num)));
                             }
+                            ctx.send(reply).await?;
+                            return Ok(());
                         }
-                        ctx.say(format!("❌ Item #{} not found in project items (checked top 100).", target_num)).await?;
                     }
+                    ctx.say(format!("❌ Item #{} not found in project {}.", target_num, proj.title)).await?;
                 },
                 Err(e) => { ctx.say(format!("Error fetching items: {}", e)).await?; }
             }
         },
-        None => { ctx.say("Project not found.").await?; }
+        Err(msg) => { ctx.say(msg).await?; }
     }
 
     Ok(())
@@ -555,257 +2624,576 @@ pub async fn edit_project_item(
     #[description = "New Value (Select Option or Text)"]
     #[autocomplete = "value_autocomplete"]
     value: String,
+    #[description = "Ask for confirmation before applying (default true)"]
+    confirm: Option<bool>,
 ) -> Result<(), Error> {
-    let _ = check_auth(ctx).await?;
+    let gh_user = check_auth(ctx).await?;
     let state = ctx.data();
     
     // 1. Identify Project & Field from Cache
     let (proj, target_field, option_id) = {
-        let projects = state.projects.read().await;
-        if let Some(p) = projects.iter().find(|p| p.title.eq_ignore_ascii_case(&title)) {
-            // Clone the project to extend lifetime
-            let p_clone = p.clone();
-            
-            if let Some(f) = p.fields.iter().find(|f| f.name.eq_ignore_ascii_case(&field)) {
-                // Check if value is an Option ID mapping
-                let opt_id = f.options.iter()
-                    .find(|(k, _)| k.eq_ignore_ascii_case(&value))
-                    .map(|(_, v)| v.clone());
-                (p_clone, f.clone(), opt_id)
-            } else {
-                ctx.say(format!("Field '{}' not found in project '{}'.", field, title)).await?;
+        let p = find_project(&state.projects.read().await, &title).map_err(BotError::NotFound)?;
+        if let Some(f) = p.fields.iter().find(|f| f.name.eq_ignore_ascii_case(&field)).cloned() {
+            // Check if value is an Option ID mapping
+            let opt_id = f.options.iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(&value))
+                .map(|(_, v)| v.clone());
+            (p, f, opt_id)
+        } else {
+            ctx.say(format!("Field '{}' not found in project '{}'.", field, p.title)).await?;
+            return Ok(());
+        }
+    };
+
+    // Free-form values must fit the field type before anything is sent to GitHub
+    let value = if option_id.is_some() {
+        value
+    } else {
+        match validate_field_value(&target_field.data_type, &value) {
+            Ok(v) => v,
+            Err(msg) => {
+                ctx.say(format!("❌ {}", msg)).await?;
                 return Ok(());
             }
-        } else {
-             ctx.say(format!("Project '{}' not found.", title)).await?;
-             return Ok(());
         }
     };
     
-    ctx.defer().await?;
+    defer(ctx).await;
 
     // 2. Parse Item Number
-    let target_num = if let Some(idx) = item_query.find('#') {
-        item_query[idx+1..].chars().take_while(|c| c.is_ascii_digit()).collect::<String>()
-    } else {
-        item_query.chars().take_while(|c| c.is_ascii_digit()).collect::<String>()
-    };
-    let target_num: i64 = target_num.parse().unwrap_or(0);
+    let target_num = parse_item_number(&item_query);
     
     // 3. Fetch Item Node ID and Current Value
-    let query = serde_json::json!({
-        "query": r#"
-            query($id: ID!) {
-                node(id: $id) {
-                    ... on ProjectV2 {
-                        items(first: 100) {
-                            nodes {
-                                id
-                                content {
-                                    ... on Issue { number title }
-                                    ... on PullRequest { number title }
-                                }
-                                fieldValues(first: 20) {
-                                    nodes {
-                                        ... on ProjectV2ItemFieldTextValue { text field { ... on ProjectV2FieldCommon { name } } }
-                                        ... on ProjectV2ItemFieldDateValue { date field { ... on ProjectV2FieldCommon { name } } }
-                                        ... on ProjectV2ItemFieldSingleSelectValue { name field { ... on ProjectV2FieldCommon { name } } }
-                                        ... on ProjectV2ItemFieldNumberValue { number field { ... on ProjectV2FieldCommon { name } } }
-                                        ... on ProjectV2ItemFieldIterationValue { title field { ... on ProjectV2FieldCommon { name } } }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        "#,
-        "variables": { "id": proj.id }
-    });
+    let item = fetch_project_item(state, stats::source(ctx), &proj.id, target_num).await?;
+    let item_node_id = item.as_ref().and_then(|i| i.get("id")).and_then(|s| s.as_str()).unwrap_or("").to_string();
+    let item_title = item.as_ref().and_then(|i| i.get("content")).and_then(|c| c.get("title")).and_then(|s| s.as_str()).unwrap_or("").to_string();
+    let current_val = item.as_ref().and_then(|i| item_field_value(i, &field)).unwrap_or_else(|| "Empty".to_string());
+
+    if item_node_id.is_empty() {
+        ctx.say(format!("❌ Item #{} not found in project.", target_num)).await?;
+        return Ok(());
+    }
 
-    let resp = state.octocrab.graphql(&query).await?;
-    let json_resp: serde_json::Value = resp;
+    // 4. Construct Mutation based on Type
+    // If option_id exists, it's a Single Select or Iteration
+    // If not, use known data type from cache to decide mutation input
     
-    let mut item_node_id = String::new();
-    let mut item_title = String::new();
-    let mut current_val = "Empty".to_string();
-
-    if let Some(items) = json_resp.get("data").and_then(|d| d.get("node")).and_then(|n| n.get("items")).and_then(|i| i.get("nodes")).and_then(|n| n.as_array()) {
-        for item in items {
-            let content = item.get("content");
-            if let Some(num) = content.and_then(|c| c.get("number")).and_then(|n| n.as_i64()) {
-                if num == target_num {
-                    item_node_id = item.get("id").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                    item_title = content.and_then(|c| c.get("title")).and_then(|s| s.as_str()).unwrap_or("").to_string();
-                    
-                    // Find current value for this field
-                     if let Some(fvs) = item.get("fieldValues").and_then(|f| f.get("nodes")).and_then(|n| n.as_array()) {
-                        for fv in fvs {
-                            let fname = fv.get("field").and_then(|f| f.get("name")).and_then(|n| n.as_str()).unwrap_or("");
-                            if fname.eq_ignore_ascii_case(&field) {
-                                // Extract value
-                                if let Some(t) = fv.get("text").and_then(|s| s.as_str()) { current_val = t.to_string(); }
-                                else if let Some(n) = fv.get("name").and_then(|s| s.as_str()) { current_val = n.to_string(); }
-                                else if let Some(d) = fv.get("date").and_then(|s| s.as_str()) { current_val = d.to_string(); }
-                                else if let Some(n) = fv.get("number").and_then(|f| f.as_f64()) { current_val = n.to_string(); }
-                                else if let Some(t) = fv.get("title").and_then(|s| s.as_str()) { current_val = t.to_string(); }
-                            }
+    let mutation = if let Some(opt_id) = option_id {
+         serde_json::json!({
+            "query": r#"
+                mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $optionId: String!) {
+                    updateProjectV2ItemFieldValue(input: {
+                        projectId: $projectId
+                        itemId: $itemId
+                        fieldId: $fieldId
+                        value: { singleSelectOptionId: $optionId } 
+                    }) { projectV2Item { id } }
+                }
+            "#,
+            "variables": {
+                "projectId": proj.id,
+                "itemId": item_node_id,
+                "fieldId": target_field.id,
+                "optionId": opt_id
+            }
+        })
+    } else {
+         match target_field.data_type.as_str() {
+            "NUMBER" => {
+                let num_val = value.parse::<f64>().unwrap_or(0.0);
+                serde_json::json!({
+                    "query": r#"
+                        mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $numVal: Float!) {
+                            updateProjectV2ItemFieldValue(input: {
+                                projectId: $projectId
+                                itemId: $itemId
+                                fieldId: $fieldId
+                                value: { number: $numVal }
+                            }) { projectV2Item { id } }
                         }
-                    }
-                    break;
+                    "#,
+                    "variables": { "projectId": proj.id, "itemId": item_node_id, "fieldId": target_field.id, "numVal": num_val }
+                })
+            },
+            "DATE" => {
+                // Date must be ISO-8601 string, handle "Today" helper
+                let date_val = if value.eq_ignore_ascii_case("Today") {
+                    chrono::Utc::now().format("%Y-%m-%d").to_string()
+                } else {
+                    value.clone()
+                };
+                
+                serde_json::json!({
+                    "query": r#"
+                        mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $dateVal: Date!) {
+                            updateProjectV2ItemFieldValue(input: {
+                                projectId: $projectId
+                                itemId: $itemId
+                                fieldId: $fieldId
+                                value: { date: $dateVal }
+                            }) { projectV2Item { id } }
+                        }
+                    "#,
+                    "variables": { "projectId": proj.id, "itemId": item_node_id, "fieldId": target_field.id, "dateVal": date_val }
+                })
+            },
+            _ => {
+                // TEXT and fallbacks
+                 serde_json::json!({
+                    "query": r#"
+                        mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $textVal: String!) {
+                            updateProjectV2ItemFieldValue(input: {
+                                projectId: $projectId
+                                itemId: $itemId
+                                fieldId: $fieldId
+                                value: { text: $textVal }
+                            }) { projectV2Item { id } }
+                        }
+                    "#,
+                    "variables": { "projectId": proj.id, "itemId": item_node_id, "fieldId": target_field.id, "textVal": value }
+                })
+            }
+         }
+    };
+
+    // 5. Confirmation, unless the caller skipped it
+    let mci = if confirm.unwrap_or(true) {
+        let embed = serenity::CreateEmbed::new()
+            .title("Confirm Edit")
+            .description(format!(
+                "**Project:** {}\n**Item:** #{} {}\n**Field:** {} ({})\n**Change:** ` {} ` ➔ ` {} `", 
+                proj.title, target_num, item_title, target_field.name, target_field.data_type, current_val, value
+            ))
+            .color(0xFEE75C);
+
+        let ctx_id = ctx.id();
+        let confirm_id = format!("edit_confirm_{}", ctx_id);
+        let cancel_id = format!("edit_cancel_{}", ctx_id);
+
+        let components = vec![serenity::CreateActionRow::Buttons(vec![
+            serenity::CreateButton::new(&confirm_id).label("Confirm").style(serenity::ButtonStyle::Success),
+            serenity::CreateButton::new(&cancel_id).label("Cancel").style(serenity::ButtonStyle::Danger),
+        ])];
+
+        let reply = ctx.send(poise::CreateReply::default().embed(embed).components(components)).await?;
+        let interaction = reply.message().await?.await_component_interaction(ctx)
+            .author_id(ctx.author().id)
+            .timeout(Duration::from_secs(60))
+            .await;
+
+        let Some(mci) = interaction else {
+            let _ = reply.edit(ctx, poise::CreateReply::default().content("⏰ Timed out.").components(vec![])).await;
+            return Ok(());
+        };
+        if mci.data.custom_id != confirm_id {
+            mci.create_response(ctx, serenity::CreateInteractionResponse::UpdateMessage(
+                serenity::CreateInteractionResponseMessage::new().content("❌ Cancelled.").components(vec![])
+            )).await?;
+            return Ok(());
+        }
+        if let Err(e) = mci.defer(ctx).await {
+            warn!("⚠️ Failed to defer confirmation: {}", e);
+        }
+        Some(mci)
+    } else {
+        None
+    };
+
+    // Serialize with other writers to this item, then re-read the value: someone
+    // may have changed it while the confirmation was open
+    let _item_guard = lock_item(state, &item_node_id).await;
+    let latest_val = match fetch_project_item(state, stats::source(ctx), &proj.id, target_num).await {
+        Ok(Some(item)) => item_field_value(&item, &target_field.name).unwrap_or_else(|| "Empty".to_string()),
+        _ => current_val.clone(),
+    };
+
+    let result_embed = match graphql_mutation(state, stats::source(ctx), &mutation).await {
+        Ok(_) => {
+            record_edit(ctx.http(), state, AuditEntry {
+                timestamp: chrono::Utc::now().timestamp(),
+                discord_id: ctx.author().id.get(),
+                github_user: Some(gh_user.clone()),
+                project_id: proj.id.clone(),
+                project_title: proj.title.clone(),
+                item_number: target_num,
+                field: target_field.name.clone(),
+                old_value: Some(latest_val.clone()),
+                new_value: value.clone(),
+            }).await;
+
+            let mut description = format!("Updated **{}** to **{}**.", target_field.name, value);
+            if latest_val != current_val {
+                description.push_str(&format!("\n⚠️ It had been changed to **{}** by someone else in the meantime.", latest_val));
+            }
+            serenity::CreateEmbed::new()
+                .title("✅ Edit Successful")
+                .description(description)
+                .color(0x57F287)
+        },
+        Err(e) => {
+            // If it failed, maybe we used wrong value type. Report error.
+            serenity::CreateEmbed::new()
+                .title("❌ Edit Failed")
+                .description(format!("Error: {}\n*Check if value type (Text/Number/Date) matches field.*", e))
+                .color(0xED4245)
+        }
+    };
+
+    match mci {
+        Some(mci) => { mci.edit_response(ctx, serenity::EditInteractionResponse::new().embed(result_embed).components(vec![])).await?; }
+        None => { ctx.send(poise::CreateReply::default().embed(result_embed)).await?; }
+    }
+    Ok(())
+}
+
+/// Show the current value of one field of a project item
+#[poise::command(slash_command, rename = "get")]
+pub async fn get_field(
+    ctx: Context<'_>,
+    #[description = "Project Title"] 
+    #[autocomplete = "project_autocomplete"]
+    title: String,
+    #[description = "Item ID (e.g. '123' or 'Repo #123')"]
+    #[autocomplete = "item_autocomplete"]
+    item_query: String,
+    #[description = "Field Name (e.g. 'Status', 'Priority', 'Date')"]
+    #[autocomplete = "field_autocomplete"]
+    field: String,
+) -> Result<(), Error> {
+    let state = ctx.data();
+    let project = find_project(&state.projects.read().await, &title);
+
+    match project {
+        Ok(proj) => {
+            let field_name = match proj.fields.iter().find(|f| f.name.eq_ignore_ascii_case(&field)) {
+                Some(f) => f.name.clone(),
+                None => {
+                    ctx.say(format!("Field '{}' not found in project '{}'.", field, proj.title)).await?;
+                    return Ok(());
+                }
+            };
+            defer(ctx).await;
+
+            let target_num = parse_item_number(&item_query);
+            match fetch_project_item(state, stats::source(ctx), &proj.id, target_num).await? {
+                Some(item) => {
+                    let value = item_field_value(&item, &field_name).unwrap_or_else(|| "Empty".to_string());
+                    ctx.say(format!("**{}** of #{}: `{}`", field_name, target_num, value)).await?;
                 }
+                None => {
+                    ctx.say(format!("❌ Item #{} not found in project.", target_num)).await?;
+                }
+            }
+        }
+        Err(msg) => {
+            ctx.say(msg).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Set a Single Select field on every item that currently has a given value
+#[poise::command(slash_command, rename = "bulk-move")]
+pub async fn bulk_move(
+    ctx: Context<'_>,
+    #[description = "Project Title"] 
+    #[autocomplete = "project_autocomplete"]
+    title: String,
+    #[description = "Single Select field (e.g. 'Status')"]
+    #[autocomplete = "field_autocomplete"]
+    field: String,
+    #[description = "Move items currently set to this value"]
+    #[autocomplete = "value_autocomplete"]
+    from: String,
+    #[description = "New value"]
+    #[autocomplete = "value_autocomplete"]
+    to: String,
+) -> Result<(), Error> {
+    let gh_user = check_auth(ctx).await?;
+    let state = ctx.data();
+
+    // 1. Resolve project, field and target option from cache
+    let (proj, target_field, option_id) = {
+        let p = find_project(&state.projects.read().await, &title).map_err(BotError::NotFound)?;
+        let Some(f) = p.fields.iter().find(|f| f.name.eq_ignore_ascii_case(&field)).cloned() else {
+            ctx.say(format!("Field '{}' not found in project '{}'.", field, title)).await?;
+            return Ok(());
+        };
+        match f.options.iter().find(|(k, _)| k.eq_ignore_ascii_case(&to)).map(|(_, v)| v.clone()) {
+            Some(opt_id) => (p, f, opt_id),
+            None => {
+                ctx.say(format!("'{}' is not an option of **{}**. Bulk moves only work on Single Select fields.", to, f.name)).await?;
+                return Ok(());
+            }
+        }
+    };
+
+    defer(ctx).await;
+
+    // 2. Find matching items
+    let item_fields = r#"
+        id
+        content {
+            ... on Issue { number }
+            ... on PullRequest { number }
+        }
+        fieldValues(first: 20) {
+            nodes {
+                ... on ProjectV2ItemFieldSingleSelectValue { name field { ... on ProjectV2FieldCommon { name } } }
+                ... on ProjectV2ItemFieldIterationValue { title field { ... on ProjectV2FieldCommon { name } } }
             }
         }
+    "#;
+    let items = fetch_project_items(state, stats::source(ctx), &proj.id, item_fields).await?;
+
+    // (item node id, issue number)
+    let mut matches: Vec<(String, i64)> = Vec::new();
+    for item in &items {
+        let current = item_field_value(item, &target_field.name);
+        if current.map(|v| v.eq_ignore_ascii_case(&from)).unwrap_or(false) {
+            let id = item.get("id").and_then(|s| s.as_str()).unwrap_or("").to_string();
+            let num = item.get("content").and_then(|c| c.get("number")).and_then(|n| n.as_i64()).unwrap_or(0);
+            matches.push((id, num));
+        }
     }
 
-    if item_node_id.is_empty() {
-        ctx.say(format!("❌ Item #{} not found in project.", target_num)).await?;
+    if matches.is_empty() {
+        ctx.say(format!("No items in **{}** have **{}** set to `{}`.", proj.title, target_field.name, from)).await?;
         return Ok(());
     }
 
-    // 4. Confirmation
+    // 3. Confirmation
     let embed = serenity::CreateEmbed::new()
-        .title("Confirm Edit")
+        .title("Confirm Bulk Move")
         .description(format!(
-            "**Project:** {}\n**Item:** #{} {}\n**Field:** {} ({})\n**Change:** ` {} ` ➔ ` {} `", 
-            proj.title, target_num, item_title, target_field.name, target_field.data_type, current_val, value
+            "**Project:** {}\n**Field:** {}\n**Change:** ` {} ` ➔ ` {} `\n**Affected items:** {}",
+            proj.title, target_field.name, from, to, matches.len()
         ))
         .color(0xFEE75C);
 
     let ctx_id = ctx.id();
-    let confirm_id = format!("edit_confirm_{}", ctx_id);
-    let cancel_id = format!("edit_cancel_{}", ctx_id);
-
+    let confirm_id = format!("bulk_confirm_{}", ctx_id);
+    let cancel_id = format!("bulk_cancel_{}", ctx_id);
     let components = vec![serenity::CreateActionRow::Buttons(vec![
-        serenity::CreateButton::new(&confirm_id).label("Confirm").style(serenity::ButtonStyle::Success),
+        serenity::CreateButton::new(&confirm_id).label(format!("Move {} items", matches.len())).style(serenity::ButtonStyle::Success),
         serenity::CreateButton::new(&cancel_id).label("Cancel").style(serenity::ButtonStyle::Danger),
     ])];
 
     let reply = ctx.send(poise::CreateReply::default().embed(embed).components(components)).await?;
-    
-    // 5. Interaction Loop
     let interaction = reply.message().await?.await_component_interaction(ctx)
         .author_id(ctx.author().id)
         .timeout(Duration::from_secs(60))
         .await;
 
-    if let Some(mci) = interaction {
-        if mci.data.custom_id == confirm_id {
-            mci.defer(ctx).await?;
-            
-            // Construct Mutation based on Type
-            // If option_id exists, it's a Single Select or Iteration
-            // If not, use known data type from cache to decide mutation input
-            
-            let mutation = if let Some(opt_id) = option_id {
-                 serde_json::json!({
-                    "query": r#"
-                        mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $optionId: String!) {
-                            updateProjectV2ItemFieldValue(input: {
-                                projectId: $projectId
-                                itemId: $itemId
-                                fieldId: $fieldId
-                                value: { singleSelectOptionId: $optionId } 
-                            }) { projectV2Item { id } }
-                        }
-                    "#,
-                    "variables": {
-                        "projectId": proj.id,
-                        "itemId": item_node_id,
-                        "fieldId": target_field.id,
-                        "optionId": opt_id
-                    }
-                })
-            } else {
-                 match target_field.data_type.as_str() {
-                    "NUMBER" => {
-                        let num_val = value.parse::<f64>().unwrap_or(0.0);
-                        serde_json::json!({
-                            "query": r#"
-                                mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $numVal: Float!) {
-                                    updateProjectV2ItemFieldValue(input: {
-                                        projectId: $projectId
-                                        itemId: $itemId
-                                        fieldId: $fieldId
-                                        value: { number: $numVal }
-                                    }) { projectV2Item { id } }
-                                }
-                            "#,
-                            "variables": { "projectId": proj.id, "itemId": item_node_id, "fieldId": target_field.id, "numVal": num_val }
-                        })
-                    },
-                    "DATE" => {
-                        // Date must be ISO-8601 string, handle "Today" helper
-                        let date_val = if value.eq_ignore_ascii_case("Today") {
-                            chrono::Utc::now().format("%Y-%m-%d").to_string()
-                        } else {
-                            value.clone()
-                        };
-                        
-                        serde_json::json!({
-                            "query": r#"
-                                mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $dateVal: Date!) {
-                                    updateProjectV2ItemFieldValue(input: {
-                                        projectId: $projectId
-                                        itemId: $itemId
-                                        fieldId: $fieldId
-                                        value: { date: $dateVal }
-                                    }) { projectV2Item { id } }
-                                }
-                            "#,
-                            "variables": { "projectId": proj.id, "itemId": item_node_id, "fieldId": target_field.id, "dateVal": date_val }
-                        })
-                    },
-                    _ => {
-                        // TEXT and fallbacks
-                         serde_json::json!({
-                            "query": r#"
-                                mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $textVal: String!) {
-                                    updateProjectV2ItemFieldValue(input: {
-                                        projectId: $projectId
-                                        itemId: $itemId
-                                        fieldId: $fieldId
-                                        value: { text: $textVal }
-                                    }) { projectV2Item { id } }
-                                }
-                            "#,
-                            "variables": { "projectId": proj.id, "itemId": item_node_id, "fieldId": target_field.id, "textVal": value }
-                        })
-                    }
-                 }
-            };
+    let Some(mci) = interaction else {
+        let _ = reply.edit(ctx, poise::CreateReply::default().content("⏰ Timed out.").components(vec![])).await;
+        return Ok(());
+    };
+    if mci.data.custom_id != confirm_id {
+        mci.create_response(ctx, serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new().content("❌ Cancelled.").components(vec![])
+        )).await?;
+        return Ok(());
+    }
+    if let Err(e) = mci.defer(ctx).await {
+        warn!("⚠️ Failed to defer confirmation: {}", e);
+    }
 
-            match state.octocrab.graphql::<serde_json::Value>(&mutation).await {
-                Ok(_) => {
-                    let success_embed = serenity::CreateEmbed::new()
-                        .title("✅ Edit Successful")
-                        .description(format!("Updated **{}** to **{}**.", target_field.name, value))
-                        .color(0x57F287);
-                    mci.edit_response(ctx, serenity::EditInteractionResponse::new().embed(success_embed).components(vec![])).await?;
-                },
-                Err(e) => {
-                     // If it failed, maybe we used wrong value type. Report error.
-                    let fail_embed = serenity::CreateEmbed::new()
-                        .title("❌ Edit Failed")
-                        .description(format!("Error: {}\n*Check if value type (Text/Number/Date) matches field.*", e))
-                        .color(0xED4245);
-                    mci.edit_response(ctx, serenity::EditInteractionResponse::new().embed(fail_embed).components(vec![])).await?;
+    // 4. Run mutations, at most 4 in flight
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(4));
+    let mut tasks = tokio::task::JoinSet::new();
+    // Task id -> item number, so a task that panicked still shows up as failed
+    let mut task_items = std::collections::HashMap::new();
+    for (item_id, num) in matches {
+        let state = state.clone();
+        let semaphore = semaphore.clone();
+        let source = ctx.command().qualified_name.clone();
+        let mutation = serde_json::json!({
+            "query": r#"
+                mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $optionId: String!) {
+                    updateProjectV2ItemFieldValue(input: {
+                        projectId: $projectId, itemId: $itemId, fieldId: $fieldId, value: { singleSelectOptionId: $optionId }
+                    }) { projectV2Item { id } }
                 }
+            "#,
+            "variables": { "projectId": proj.id, "itemId": item_id, "fieldId": target_field.id, "optionId": option_id }
+        });
+        let handle = tasks.spawn(async move {
+            let _permit = semaphore.acquire().await;
+            let _item_guard = lock_item(&state, &item_id).await;
+            (num, graphql_mutation(&state, &source, &mutation).await.is_ok())
+        });
+        task_items.insert(handle.id(), num);
+    }
+
+    let mut moved = Vec::new();
+    let mut failed = Vec::new();
+    while let Some(res) = tasks.join_next().await {
+        match res {
+            Ok((num, true)) => moved.push(num),
+            Ok((num, false)) => failed.push(num),
+            Err(e) => {
+                let num = task_items.get(&e.id()).copied().unwrap_or_default();
+                error!("❌ Bulk move of #{} in {} did not finish: {}", num, proj.title, e);
+                failed.push(num);
             }
-        } else {
-             mci.create_response(ctx, serenity::CreateInteractionResponse::UpdateMessage(
-                serenity::CreateInteractionResponseMessage::new().content("❌ Cancelled.").components(vec![])
-            )).await?;
         }
-    } else {
-         let _ = reply.edit(ctx, poise::CreateReply::default().content("⏰ Timed out.").components(vec![])).await;
     }
+
+    for num in &moved {
+        record_edit(ctx.http(), state, AuditEntry {
+            timestamp: chrono::Utc::now().timestamp(),
+            discord_id: ctx.author().id.get(),
+            github_user: Some(gh_user.clone()),
+            project_id: proj.id.clone(),
+            project_title: proj.title.clone(),
+            item_number: *num,
+            field: target_field.name.clone(),
+            old_value: Some(from.clone()),
+            new_value: to.clone(),
+        }).await;
+    }
+
+    let mut summary = format!("Moved **{}** item(s) from ` {} ` to ` {} `.", moved.len(), from, to);
+    if !failed.is_empty() {
+        failed.sort();
+        let list = failed.iter().map(|n| format!("#{}", n)).collect::<Vec<_>>().join(", ");
+        summary.push_str(&format!("\n❌ Failed: {}", list));
+    }
+    let summary_embed = serenity::CreateEmbed::new()
+        .title(if failed.is_empty() { "✅ Bulk Move Complete" } else { "⚠️ Bulk Move Partially Failed" })
+        .description(summary)
+        .color(if failed.is_empty() { 0x57F287 } else { 0xFEE75C });
+    mci.edit_response(ctx, serenity::EditInteractionResponse::new().embed(summary_embed).components(vec![])).await?;
+    Ok(())
+}
+
+/// Show recent edits made to a Project through the bot
+#[poise::command(slash_command)]
+pub async fn audit(
+    ctx: Context<'_>,
+    #[description = "Project Title"] 
+    #[autocomplete = "project_autocomplete"]
+    title: String,
+) -> Result<(), Error> {
+    let state = ctx.data();
+    let project = find_project(&state.projects.read().await, &title);
+
+    match project {
+        Ok(proj) => {
+            let entries = recent_edits(&proj.id, 15);
+            let description = if entries.is_empty() {
+                "No edits recorded yet.".to_string()
+            } else {
+                entries.iter().map(|e| e.describe()).collect::<Vec<_>>().join("\n")
+            };
+
+            let embed = serenity::CreateEmbed::new()
+                .title(format!("Audit Log: {}", proj.title))
+                .url(&proj.url)
+                .description(description)
+                .color(0xEB459E);
+
+            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        }
+        Err(msg) => {
+            ctx.say(msg).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Manage User Identity
+#[poise::command(slash_command, subcommands("connect", "view", "disconnect", "snooze", "admin_map"))]
+pub async fn user(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Stop the bot from mentioning you for a while
+#[poise::command(slash_command, ephemeral)]
+pub async fn snooze(
+    ctx: Context<'_>,
+    #[description = "How long (e.g. 30m, 4h, 2d) or 'off'"] duration: String,
+) -> Result<(), Error> {
+    let discord_id = ctx.author().id.get();
+    let state = ctx.data();
+
+    if duration.trim().eq_ignore_ascii_case("off") {
+        let saved = {
+            let mut snoozes = state.snoozes.write().await;
+            if snoozes.until.remove(&discord_id).is_some() { snoozes.save(&state.snoozes_path) } else { Ok(()) }
+        };
+        let mut message = "🔔 Snooze cleared, mentions are back on.".to_string();
+        if let Err(e) = saved {
+            warn!("⚠️ Failed to save snoozes after clearing {}: {}", discord_id, e);
+            message.push_str("\n⚠️ This couldn't be saved; the old snooze comes back if the bot restarts.");
+        }
+        ctx.say(message).await?;
+        return Ok(());
+    }
+
+    let Some(secs) = parse_duration(&duration) else {
+        ctx.say(format!("❌ Couldn't read `{}`. Use something like `30m`, `4h`, `2d`, or `off`.", duration)).await?;
+        return Ok(());
+    };
+    let until = chrono::Utc::now().timestamp() + secs;
+    let saved = {
+        let mut snoozes = state.snoozes.write().await;
+        snoozes.until.insert(discord_id, until);
+        snoozes.save(&state.snoozes_path)
+    };
+    let mut message = format!("🔕 Snoozed until <t:{}:f>. Use `/user snooze off` to undo.", until);
+    if let Err(e) = saved {
+        warn!("⚠️ Failed to save snoozes after snoozing {}: {}", discord_id, e);
+        message.push_str("\n⚠️ The snooze couldn't be saved and will be lost when the bot restarts.");
+    }
+    ctx.say(message).await?;
     Ok(())
 }
 
-/// Manage User Identity
-#[poise::command(slash_command, subcommands("connect", "view", "disconnect"))]
-pub async fn user(_ctx: Context<'_>) -> Result<(), Error> {
+/// Link someone else's Discord account to a GitHub login (bot owners only)
+#[poise::command(slash_command, owners_only, ephemeral, rename = "admin-map")]
+pub async fn admin_map(
+    ctx: Context<'_>,
+    #[description = "Discord user to link"] user: serenity::User,
+    #[description = "GitHub username"]
+    #[autocomplete = "user_autocomplete"]
+    github: String,
+) -> Result<(), Error> {
+    check_login(&github)?;
+    let state = ctx.data();
+    defer_ephemeral(ctx).await;
+
+    // Make sure the account exists, and store the login with GitHub's casing
+    stats::count(ctx, Api::Rest);
+    let login = match state.octocrab.users(&github).profile().await {
+        Ok(profile) => profile.login,
+        Err(e) => {
+            ctx.say(format!("❌ Couldn't find GitHub user **{}**: {}", github, e)).await?;
+            return Ok(());
+        }
+    };
+
+    let discord_id = user.id.get();
+    let (previous, saved) = {
+        let mut mapping = state.user_mapping.write().await;
+        let previous = mapping.map.insert(discord_id, login.clone());
+        // A stored token belongs to the old account; without one, writes fall back to the bot's token
+        if previous.as_deref().is_some_and(|p| !p.eq_ignore_ascii_case(&login)) {
+            mapping.tokens.remove(&discord_id);
+        }
+        (previous, mapping.save(&state.user_mapping_path))
+    };
+
+    let mut message = format!("✅ Linked <@{}> to GitHub user **{}**.", discord_id, login);
+    if let Some(prev) = previous {
+        message.push_str(&format!("\nℹ️ Replaced the previous link to **{}**.", prev));
+    }
+    if let Err(e) = saved {
+        warn!("⚠️ Failed to save user mapping after linking {} to {}: {}", discord_id, login, e);
+        message.push_str("\n⚠️ The link couldn't be saved and will be lost when the bot restarts.");
+    }
+    ctx.say(message).await?;
     Ok(())
 }
 
@@ -815,18 +3203,23 @@ pub async fn disconnect(ctx: Context<'_>) -> Result<(), Error> {
     let discord_id = ctx.author().id.get();
     let state = ctx.data();
 
-    let removed = {
+    let (removed, saved) = {
         let mut mapping = state.user_mapping.write().await;
         let res = mapping.map.remove(&discord_id);
-        if res.is_some() {
-            mapping.save();
-        }
-        res
+        mapping.tokens.remove(&discord_id);
+        let saved = if res.is_some() { mapping.save(&state.user_mapping_path) } else { Ok(()) };
+        (res, saved)
     };
 
     match removed {
         Some(gh_user) => {
-            ctx.say(format!("✅ Successfully disconnected from GitHub user **{}**.", gh_user)).await?;
+            match saved {
+                Ok(()) => ctx.say(format!("✅ Successfully disconnected from GitHub user **{}**.", gh_user)).await?,
+                Err(e) => {
+                    warn!("⚠️ Failed to save user mapping after disconnecting {}: {}", discord_id, e);
+                    ctx.say(format!("⚠️ Disconnected from **{}** for now, but the change couldn't be saved and the link will come back when the bot restarts. Please tell the bot owner.", gh_user)).await?
+                }
+            };
         }
         None => {
             ctx.say("ℹ️ You are not currently connected to any GitHub account.").await?;
@@ -835,11 +3228,13 @@ pub async fn disconnect(ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
-/// Connect your Discord account to GitHub using OAuth Device Flow
+/// Connect your Discord account to GitHub using OAuth Device Flow or a personal access token
 #[poise::command(slash_command, ephemeral)]
-pub async fn connect(ctx: Context<'_>) -> Result<(), Error> {
+pub async fn connect(
+    ctx: Context<'_>,
+    #[description = "GitHub personal access token, instead of logging in through the browser"] token: Option<String>,
+) -> Result<(), Error> {
     let state = ctx.data();
-    let client_id = &state.github_client_id;
     let discord_id = ctx.author().id.get();
     
     // Check if already connected
@@ -851,96 +3246,34 @@ pub async fn connect(ctx: Context<'_>) -> Result<(), Error> {
         }
     }
     
-    ctx.defer_ephemeral().await?;
-
-    // 1. Request Device Code
-    let params = [("client_id", client_id.as_str()), ("scope", "read:user")];
-    let res = state.http_client.post("https://github.com/login/device/code")
-        .header("Accept", "application/json")
-        .form(&params)
-        .send()
-        .await?;
-    
-    if !res.status().is_success() {
-        ctx.say(format!("❌ Failed to initiate device flow: {}", res.status())).await?;
+    defer_ephemeral(ctx).await;
+
+    // A pasted token skips the device flow; it is stored and used exactly like an OAuth one
+    if let Some(token) = token.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+        let token = UserToken { access_token: token, refresh_token: None, expires_at: None };
+        let outcome = link_token(state, discord_id, token).await?;
+        let message = match outcome {
+            ConnectOutcome::Linked(_) | ConnectOutcome::LinkedUnsaved(_) => format!(
+                "{}\n⚠️ The bot now acts on GitHub as you with this token (assigning, closing, commenting, merging, ...) within its scopes. Run `/user disconnect` and revoke the token on GitHub to stop that.",
+                outcome.message()
+            ),
+            _ => outcome.message(),
+        };
+        ctx.say(message).await?;
         return Ok(());
     }
 
-    let body: serde_json::Value = res.json().await?;
-    let device_code = body["device_code"].as_str().ok_or("missing device_code")?.to_string();
-    let user_code = body["user_code"].as_str().ok_or("missing user_code")?.to_string();
-    let verification_uri = body["verification_uri"].as_str().ok_or("missing verification_uri")?.to_string();
-    let interval = body["interval"].as_u64().unwrap_or(5);
-
-    // 2. Instruct User
-    let embed = serenity::CreateEmbed::new()
-        .title("🔗 Connect to GitHub")
-        .description(format!("To link your account, please complete the device flow:\n\n1. Click **[Login to GitHub]({})**\n2. Enter code: `{}`", verification_uri, user_code))
-        .footer(serenity::CreateEmbedFooter::new("I will automatically check when you are done..."))
-        .color(0x5865F2);
-
-    let reply_handle = ctx.send(poise::CreateReply::default().embed(embed)).await?;
-
-    // 3. Poll for Token
-    let start_time = std::time::Instant::now();
-    
-    let access_token = loop {
-        if start_time.elapsed().as_secs() > 900 { // 15 min timeout
-            reply_handle.edit(ctx, poise::CreateReply::default().content("⏰ **Timeout**: Connection cancelled.")).await?;
-            return Ok(());
-        }
-        
-        tokio::time::sleep(Duration::from_secs(interval + 1)).await;
-        
-        let params = [
-            ("client_id", client_id.as_str()), 
-            ("device_code", device_code.as_str()), 
-            ("grant_type", "urn:ietf:params:oauth:grant-type:device_code")
-        ];
-        
-        let res = state.http_client.post("https://github.com/login/oauth/access_token")
-            .header("Accept", "application/json")
-            .form(&params)
-            .send()
-            .await?;
-            
-        if let Ok(body) = res.json::<serde_json::Value>().await {
-            if let Some(token) = body["access_token"].as_str() {
-                break token.to_string();
-            }
-            if let Some(err) = body["error"].as_str() {
-                if err == "access_denied" {
-                     reply_handle.edit(ctx, poise::CreateReply::default().content("❌ **Denied**: Access denied by user.")).await?;
-                     return Ok(());
-                }
-                // "authorization_pending" is expected, continue loop
-            }
+    let code = match request_device_code(state).await {
+        Ok(c) => c,
+        Err(e) => {
+            ctx.say(format!("❌ {}", e)).await?;
+            return Ok(());
         }
     };
+    let reply_handle = ctx.send(poise::CreateReply::default().embed(instructions_embed(&code))).await?;
 
-    // 4. Fetch User Identity with Token
-    let user_res = state.http_client.get("https://api.github.com/user")
-        .header("Authorization", format!("Bearer {}", access_token))
-        .header("User-Agent", "usthingy-bot")
-        .send()
-        .await?;
-
-    if !user_res.status().is_success() {
-         reply_handle.edit(ctx, poise::CreateReply::default().content("❌ **Error**: Failed to fetch user info after auth.")).await?;
-         return Ok(());
-    }
-
-    let user_body: serde_json::Value = user_res.json().await?;
-    let github_login = user_body["login"].as_str().ok_or("missing login")?.to_string();
-
-    // 5. Save Mapping
-    {
-        let mut mapping = state.user_mapping.write().await;
-        mapping.map.insert(discord_id, github_login.clone());
-        mapping.save();
-    }
-
-    reply_handle.edit(ctx, poise::CreateReply::default().content(format!("✅ **Success!** Linked to GitHub account **{}**.", github_login))).await?;
+    let outcome = complete_device_flow(state, discord_id, &code).await?;
+    reply_handle.edit(ctx, poise::CreateReply::default().content(outcome.message())).await?;
     Ok(())
 }
 
@@ -951,10 +3284,17 @@ pub async fn view(
     #[description = "GitHub Username (defaults to you if connected)"] 
     #[autocomplete = "user_autocomplete"]
     user: Option<String>,
+    #[description = "Team slug: summarize the workload of the whole team instead"]
+    #[autocomplete = "team_autocomplete"]
+    team: Option<String>,
+    #[description = "Issue/PR state (default open)"]
+    state: Option<StateFilter>,
+    #[description = "Reply with plain markdown instead of an embed"]
+    plain: Option<bool>,
 ) -> Result<(), Error> {
-    let state = ctx.data();
-    let org = &state.github_org;
-    
+    if let Some(slug) = team {
+        return view_team(ctx, &slug, state.unwrap_or(StateFilter::Open), plain.unwrap_or(false)).await;
+    }
     // Determine target user
     let target_user = if let Some(u) = user {
         u
@@ -962,56 +3302,717 @@ pub async fn view(
         // Default to self
         check_auth(ctx).await?
     };
+    // The login is interpolated into search qualifiers below
+    check_login(&target_user)?;
     
-    ctx.defer().await?;
+    defer(ctx).await;
+
+    let (rendered, components) = build_user_workload(ctx.data(), &ctx.command().qualified_name, &target_user, state.unwrap_or(StateFilter::Open), 1).await;
+    ctx.send(rendered.reply(plain.unwrap_or(false)).components(components)).await?;
+
+    Ok(())
+}
+
+// Items per section on one /user view page
+const WORKLOAD_PAGE_SIZE: u8 = 5;
+
+// Assigned issues, open PRs and review requests of one login (the /user view and /me embed),
+// shared with the Prev/Next buttons. The three sections page together; `source` is what the
+// searches are counted under in /stats.
+pub async fn build_user_workload(state: &BotState, source: &str, login: &str, issue_state: StateFilter, page: u32) -> (Rendered, Vec<serenity::CreateActionRow>) {
+    let orgs = state.org_qualifiers();
+    let is_state = issue_state.search_qualifier();
 
     // 1. Assigned Issues
-    let issues_query = format!("org:{} assignee:{} is:issue is:open", org, target_user);
-    // 2. Open PRs
-    let prs_query = format!("org:{} author:{} is:pr is:open", org, target_user);
+    let issues_query = format!("{} assignee:{} is:issue{}", orgs, login, is_state);
+    // 2. Authored PRs
+    let prs_query = format!("{} author:{} is:pr{}", orgs, login, is_state);
     // 3. Review Requests
-    let reviews_query = format!("org:{} review-requested:{} is:pr is:open", org, target_user);
-
+    let reviews_query = format!("{} review-requested:{} is:pr{}", orgs, login, is_state);
     let (issues_res, prs_res, reviews_res) = tokio::join!(
-        state.octocrab.search().issues_and_pull_requests(&issues_query).per_page(5).send(),
-        state.octocrab.search().issues_and_pull_requests(&prs_query).per_page(5).send(),
-        state.octocrab.search().issues_and_pull_requests(&reviews_query).per_page(5).send()
+        search_issues(state, source, &issues_query, WORKLOAD_PAGE_SIZE, page),
+        search_issues(state, source, &prs_query, WORKLOAD_PAGE_SIZE, page),
+        search_issues(state, source, &reviews_query, WORKLOAD_PAGE_SIZE, page)
     );
 
-    let mut embed = serenity::CreateEmbed::new()
-        .title(format!("User: {}", target_user))
-        .url(format!("https://github.com/{}", target_user))
-        .color(0x5865F2);
+    let mut rendered = Rendered::new(format!("User: {}", login), 0x5865F2)
+        .url(format!("https://github.com/{}", login));
+    let avatar = state.users.read().await.iter()
+        .find(|u| u.login.eq_ignore_ascii_case(login))
+        .map(|u| u.avatar_url.clone());
+    if let Some(avatar) = avatar {
+        rendered = rendered.thumbnail(avatar);
+    }
 
     // Helper to format list
     fn format_list(items: Vec<octocrab::models::issues::Issue>) -> String {
         if items.is_empty() { return "None".to_string(); }
         items.into_iter().map(|i| {
-            let repo = i.repository_url.as_str().split('/').last().unwrap_or("?");
+            let repo = i.repository_url.as_str().split('/').next_back().unwrap_or("?");
             format!("• **{}/#{}** [{}]({})", repo, i.number, i.title, i.html_url)
         }).collect::<Vec<_>>().join("\n")
     }
 
-    if let Ok(page) = issues_res {
-        embed = embed.field("🛠️ Assigned Issues", format_list(page.items), false);
+    // Totals come from the search's total_count, not the items listed; N/A when the search failed
+    let total = |res: &Result<octocrab::Page<octocrab::models::issues::Issue>, octocrab::Error>| match res {
+        Ok(page) => Some(page.total_count.unwrap_or(page.items.len() as u64)),
+        Err(_) => None,
+    };
+    let totals = [total(&issues_res), total(&prs_res), total(&reviews_res)];
+    let shown = |t: Option<u64>| t.map(|n| n.to_string()).unwrap_or_else(|| "N/A".to_string());
+    let adjective = issue_state.adjective();
+    rendered = rendered.description(format!(
+        "📊 **{}** {}issues assigned • **{}** {}PRs • **{}** review requests",
+        shown(totals[0]), adjective, shown(totals[1]), adjective, shown(totals[2])
+    ));
+    let names = match issue_state {
+        StateFilter::Open => ["🛠️ Assigned Issues", "🚀 Open PRs", "👀 Review Requests"],
+        StateFilter::Closed => ["🛠️ Closed Assigned Issues", "🚀 Closed PRs", "👀 Closed Review Requests"],
+        StateFilter::All => ["🛠️ Assigned Issues (all)", "🚀 PRs (all)", "👀 Review Requests (all)"],
+    };
+
+    let first = (page as u64 - 1) * WORKLOAD_PAGE_SIZE as u64;
+    for ((name, res), count) in names.into_iter().zip([issues_res, prs_res, reviews_res]).zip(totals) {
+        // "(6–10 of 12)" whenever a section doesn't fit on one page
+        let name = match count {
+            Some(n) if n > WORKLOAD_PAGE_SIZE as u64 && first < n => format!("{} ({}–{} of {})", name, first + 1, (first + WORKLOAD_PAGE_SIZE as u64).min(n), n),
+            _ => name.to_string(),
+        };
+        let value = match res {
+            Ok(page) if page.items.is_empty() && first > 0 => "No more".to_string(),
+            Ok(page) => format_list(page.items),
+            Err(e) => format!("⚠️ Search failed: {}", e),
+        };
+        rendered = rendered.field(name, value);
+    }
+
+    // One pager for all three sections, Next while any of them has more
+    let mut buttons = Vec::new();
+    if page > 1 {
+        buttons.push(serenity::CreateButton::new(CustomId::UserPage { login: login.to_string(), page: page - 1, state: issue_state }.to_string()).label("◀️ Prev").style(serenity::ButtonStyle::Secondary));
+    }
+    if totals.iter().flatten().any(|&n| n > first + WORKLOAD_PAGE_SIZE as u64) {
+        buttons.push(serenity::CreateButton::new(CustomId::UserPage { login: login.to_string(), page: page + 1, state: issue_state }.to_string()).label("Next ▶️").style(serenity::ButtonStyle::Secondary));
+    }
+    let components = if buttons.is_empty() { Vec::new() } else { vec![serenity::CreateActionRow::Buttons(buttons)] };
+
+    (rendered, components)
+}
+
+// Members looked up per /user view team=...; each costs three search calls
+const MAX_TEAM_FANOUT: usize = 8;
+
+// Open issue/PR/review counts per team member
+async fn view_team(ctx: Context<'_>, slug: &str, issue_state: StateFilter, plain: bool) -> Result<(), Error> {
+    let state = ctx.data();
+    let team = state.teams.read().await.iter().find(|t| t.slug.eq_ignore_ascii_case(slug)).cloned();
+    let Some(team) = team else {
+        ctx.say(format!("❌ Team `{}` not found in {}. Try /refresh?", slug, state.github_orgs.join(", "))).await?;
+        return Ok(());
+    };
+    let org = team.org.clone();
+    defer(ctx).await;
+
+    stats::count(ctx, Api::Rest);
+    let first_page = state.octocrab.teams(&org).members(&team.slug).per_page(100).send().await;
+    // Every member, so the "first N of M" footer is right for teams over 100
+    let members = match first_page {
+        Ok(page) => all_pages(state, stats::source(ctx), "team member list", page).await,
+        Err(e) => Err(e),
+    };
+    let members: Vec<String> = match members {
+        Ok(members) => members.into_iter().map(|m| m.login).collect(),
+        Err(e) => {
+            ctx.say(format!("❌ Failed to fetch members of {}: {}", team.name, e)).await?;
+            return Ok(());
+        }
+    };
+    if members.is_empty() {
+        ctx.say(format!("Team **{}** has no members.", team.name)).await?;
+        return Ok(());
+    }
+
+    // Only the counts are needed, so one result per search is enough. A failed search is None
+    // (shown as N/A) rather than 0, so the totals never quietly under-count.
+    let shown_members = &members[..members.len().min(MAX_TEAM_FANOUT)];
+    let mut tasks = tokio::task::JoinSet::new();
+    for (idx, login) in shown_members.iter().enumerate() {
+        let state = ctx.data().clone();
+        let source = ctx.command().qualified_name.clone();
+        let (org, login) = (org.clone(), login.clone());
+        tasks.spawn(async move {
+            // Searches queue on the shared request limit, so large teams don't burst
+            let count = |q: String| {
+                let state = state.clone();
+                let source = source.clone();
+                async move {
+                    search_issues(&state, &source, &q, 1, 1).await.ok().and_then(|p| p.total_count)
+                }
+            };
+            let is_state = issue_state.search_qualifier();
+            let (issues, prs, reviews) = tokio::join!(
+                count(format!("org:{} assignee:{} is:issue{}", org, login, is_state)),
+                count(format!("org:{} author:{} is:pr{}", org, login, is_state)),
+                count(format!("org:{} review-requested:{} is:pr{}", org, login, is_state))
+            );
+            (idx, login, issues, prs, reviews)
+        });
+    }
+
+    // A member whose task panicked keeps its row, with every count unknown
+    let mut rows: Vec<_> = shown_members.iter().enumerate()
+        .map(|(idx, login)| (idx, login.clone(), None, None, None))
+        .collect();
+    while let Some(res) = tasks.join_next().await {
+        match res {
+            Ok((idx, login, issues, prs, reviews)) => rows[idx] = (idx, login, issues, prs, reviews),
+            Err(e) => warn!("⚠️ Workload of a member of {} did not finish: {}", team.name, e),
+        }
+    }
+
+    // A total is only known when every member's count is
+    let shown = |n: Option<u64>| n.map(|n| n.to_string()).unwrap_or_else(|| "N/A".to_string());
+    let total_issues = shown(rows.iter().map(|r| r.2).sum());
+    let total_prs = shown(rows.iter().map(|r| r.3).sum());
+    let total_reviews = shown(rows.iter().map(|r| r.4).sum());
+    let lines: Vec<String> = rows.iter()
+        .map(|(_, login, issues, prs, reviews)| format!("• **{}**: 🛠️ {} | 🚀 {} | 👀 {}", login, shown(*issues), shown(*prs), shown(*reviews)))
+        .collect();
+
+    let adjective = issue_state.adjective();
+    let mut rendered = Rendered::new(format!("Team: {}", team.name), 0x5865F2)
+        .url(format!("https://github.com/orgs/{}/teams/{}", org, team.slug))
+        .description(format!("🛠️ {} assigned {}issues • 🚀 {} {}PRs • 👀 {} review requests", total_issues, adjective, total_prs, adjective, total_reviews))
+        .field("Members", lines.join("\n"));
+    if members.len() > MAX_TEAM_FANOUT {
+        rendered = rendered.footer(format!(
+            "Showing the first {} of {} members; use /user view <username> for the others",
+            MAX_TEAM_FANOUT, members.len()
+        ));
     }
-    if let Ok(page) = prs_res {
-        embed = embed.field("🚀 Open PRs", format_list(page.items), false);
+    ctx.send(rendered.reply(plain)).await?;
+    Ok(())
+}
+
+/// Everything waiting on you: issues, PRs, review requests, and board items assigned to you
+#[poise::command(slash_command)]
+pub async fn me(
+    ctx: Context<'_>,
+    #[description = "Reply with plain markdown instead of an embed"]
+    plain: Option<bool>,
+) -> Result<(), Error> {
+    let login = check_auth(ctx).await?;
+    check_login(&login)?;
+    defer(ctx).await;
+
+    // No pager here: the section headers show when there is more, /user view pages through it
+    let (mut rendered, _) = build_user_workload(ctx.data(), &ctx.command().qualified_name, &login, StateFilter::Open, 1).await;
+    rendered.title = format!("Your work, {}", login);
+
+    // Open board items assigned to you, from the cache (no extra API calls)
+    let board_lines: Vec<String> = {
+        let projects = ctx.data().projects.read().await;
+        projects.iter()
+            .flat_map(|p| p.items.iter().map(move |i| (p, i)))
+            .filter(|(_, i)| i.state == "OPEN" && i.assignees.iter().any(|a| a.eq_ignore_ascii_case(&login)))
+            .map(|(p, i)| format!("• **{}**: {}#{} {}", p.title, i.repo_name, i.number, i.title))
+            .collect()
+    };
+    let mut board_text = board_lines.iter().take(MAX_ME_BOARD_ITEMS).cloned().collect::<Vec<_>>().join("\n");
+    if board_lines.len() > MAX_ME_BOARD_ITEMS {
+        board_text.push_str(&format!("\n…and {} more", board_lines.len() - MAX_ME_BOARD_ITEMS));
     }
-    if let Ok(page) = reviews_res {
-        embed = embed.field("👀 Review Requests", format_list(page.items), false);
+    rendered = rendered.field("📋 On Boards", if board_text.is_empty() { "None".to_string() } else { board_text });
+
+    ctx.send(rendered.reply(plain.unwrap_or(false))).await?;
+    Ok(())
+}
+
+// Board items listed by /me before collapsing into "…and N more"
+const MAX_ME_BOARD_ITEMS: usize = 10;
+
+/// Show which GitHub account you are linked to
+#[poise::command(slash_command, ephemeral)]
+pub async fn whoami(ctx: Context<'_>) -> Result<(), Error> {
+    let state = ctx.data();
+    let linked = state.user_mapping.read().await.map.get(&ctx.author().id.get()).cloned();
+
+    // Write commands this server has turned off, and the /config groups doing it
+    let (blocked, groups) = match ctx.guild_id() {
+        Some(guild_id) => {
+            let configs = state.guild_configs.read().await;
+            let mut groups: Vec<String> = Vec::new();
+            let mut blocked = 0;
+            for name in WRITE_COMMANDS {
+                if let Some(group) = configs.blocking_group(guild_id.get(), name) {
+                    blocked += 1;
+                    if !groups.contains(&group) { groups.push(group); }
+                }
+            }
+            (blocked, groups)
+        }
+        None => (0, Vec::new()),
+    };
+    let groups = groups.iter().map(|g| format!("`{}`", g)).collect::<Vec<_>>().join(", ");
+    let write_access = if blocked == 0 {
+        "✅ Allowed".to_string()
+    } else if blocked == WRITE_COMMANDS.len() {
+        format!("⛔ Disabled in this server ({})", groups)
+    } else {
+        format!("⚠️ Some disabled in this server ({})", groups)
+    };
+
+    let connected = linked.is_some();
+    let embed = match linked {
+        Some(gh_user) => serenity::CreateEmbed::new()
+            .title(format!("You are {}", gh_user))
+            .url(format!("https://github.com/{}", gh_user))
+            .field("Connection", "✅ Connected", true)
+            .field("Write commands", write_access, true)
+            .color(if blocked == 0 { 0x57F287 } else { 0xFEE75C }),
+        None => serenity::CreateEmbed::new()
+            .title("Not connected")
+            .description("Your Discord account is not linked to GitHub. Click the button or run `/user connect` to link it.")
+            .field("Connection", "❌ Not connected", true)
+            .field("Write commands", "⛔ Require a linked account", true)
+            .color(0xED4245),
+    };
+
+    let mut reply = poise::CreateReply::default().embed(embed);
+    if !connected {
+        reply = reply.components(vec![serenity::CreateActionRow::Buttons(vec![connect_button()])]);
     }
+    ctx.send(reply).await?;
+    Ok(())
+}
+
+/// Configure the bot for this server
+#[poise::command(slash_command, guild_only, default_member_permissions = "MANAGE_GUILD", subcommands("config_show", "config_enable", "config_disable", "taxonomy_set", "taxonomy_remove", "field_emoji", "default_board", "due_soon"))]
+pub async fn config(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Show which command groups are disabled here
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "show", ephemeral)]
+pub async fn config_show(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("guild only")?.get();
+    let (disabled, taxonomy, field_emoji, board): (Vec<String>, Vec<LabelCategory>, Vec<String>, Option<DefaultBoard>) = {
+        let configs = ctx.data().guild_configs.read().await;
+        match configs.guilds.get(&guild_id) {
+            Some(c) => (
+                c.disabled_groups.iter().cloned().collect(),
+                c.taxonomy.clone(),
+                c.field_emoji.iter().map(|(f, e)| format!("{} {}", e, f)).collect(),
+                c.default_board.clone(),
+            ),
+            None => (Vec::new(), Vec::new(), Vec::new(), None),
+        }
+    };
 
+    let description = if disabled.is_empty() {
+        "All command groups are enabled.".to_string()
+    } else {
+        format!("Disabled groups: {}", disabled.iter().map(|g| format!("`{}`", g)).collect::<Vec<_>>().join(", "))
+    };
+    let mut embed = serenity::CreateEmbed::new()
+        .title("Server Configuration")
+        .description(description)
+        .color(0x5865F2);
+    for category in &taxonomy {
+        embed = embed.field(format!("🏷️ {}", category.name), category.labels.join(", "), false);
+    }
+    if !field_emoji.is_empty() {
+        embed = embed.field("Field emoji", field_emoji.join("\n"), false);
+    }
+    if let Some(b) = board {
+        embed = embed.field("Default board", format!("{} (due date in **{}**)", b.project, b.date_field), false);
+    }
+    let due_soon_days = ctx.data().guild_configs.read().await.due_soon_days(Some(guild_id));
+    embed = embed.field("Due soon", format!("within {} day(s)", due_soon_days), false);
     ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+// Appended to a reply when a settings change couldn't be written to disk
+fn save_warning(saved: std::io::Result<()>, what: &str) -> &'static str {
+    match saved {
+        Ok(()) => "",
+        Err(e) => {
+            warn!("⚠️ Failed to save {}: {}", what, e);
+            "\n⚠️ This change couldn't be saved and will be lost when the bot restarts."
+        }
+    }
+}
+
+/// Enable a command group in this server
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "enable", ephemeral)]
+pub async fn config_enable(
+    ctx: Context<'_>,
+    #[description = "Command group"] group: CommandGroup,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("guild only")?.get();
+    let saved = {
+        let mut configs = ctx.data().guild_configs.write().await;
+        configs.guilds.entry(guild_id).or_default().disabled_groups.remove(group.key());
+        configs.save(&ctx.data().guild_config_path)
+    };
+    ctx.say(format!("✅ Enabled `{}` commands in this server.{}", group.key(), save_warning(saved, "server config"))).await?;
+    Ok(())
+}
+
+/// Disable a command group in this server
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "disable", ephemeral)]
+pub async fn config_disable(
+    ctx: Context<'_>,
+    #[description = "Command group ('write' = every command that changes GitHub)"] group: CommandGroup,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("guild only")?.get();
+    let saved = {
+        let mut configs = ctx.data().guild_configs.write().await;
+        configs.guilds.entry(guild_id).or_default().disabled_groups.insert(group.key().to_string());
+        configs.save(&ctx.data().guild_config_path)
+    };
+    ctx.say(format!("🚫 Disabled `{}` commands in this server.{}", group.key(), save_warning(saved, "server config"))).await?;
+    Ok(())
+}
+
+/// Define a label category (e.g. Priority = P0, P1, P2) for /repo view-issue and /repo triage
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "taxonomy-set", ephemeral)]
+pub async fn taxonomy_set(
+    ctx: Context<'_>,
+    #[description = "Category name (e.g. Priority)"] name: String,
+    #[description = "Comma-separated labels, in order (e.g. P0, P1, P2)"] labels: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("guild only")?.get();
+    let name = name.trim().to_string();
+    let labels: Vec<String> = labels.split(',').map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect();
+    if name.is_empty() || labels.is_empty() {
+        ctx.say("❌ Give a category name and at least one label.").await?;
+        return Ok(());
+    }
+
+    let saved = {
+        let mut configs = ctx.data().guild_configs.write().await;
+        let taxonomy = &mut configs.guilds.entry(guild_id).or_default().taxonomy;
+        taxonomy.retain(|c| !c.name.eq_ignore_ascii_case(&name));
+        taxonomy.push(LabelCategory { name: name.clone(), labels: labels.clone() });
+        configs.save(&ctx.data().guild_config_path)
+    };
+    ctx.say(format!("🏷️ **{}** now maps to: {}{}", name, labels.join(", "), save_warning(saved, "server config"))).await?;
+    Ok(())
+}
+
+/// Remove a label category
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "taxonomy-remove", ephemeral)]
+pub async fn taxonomy_remove(
+    ctx: Context<'_>,
+    #[description = "Category name"] name: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("guild only")?.get();
+    let (removed, saved) = {
+        let mut configs = ctx.data().guild_configs.write().await;
+        let taxonomy = &mut configs.guilds.entry(guild_id).or_default().taxonomy;
+        let before = taxonomy.len();
+        taxonomy.retain(|c| !c.name.eq_ignore_ascii_case(&name));
+        let removed = taxonomy.len() != before;
+        (removed, configs.save(&ctx.data().guild_config_path))
+    };
+    if removed {
+        ctx.say(format!("🗑️ Removed category **{}**.{}", name, save_warning(saved, "server config"))).await?;
+    } else {
+        ctx.say(format!("No category named **{}**.", name)).await?;
+    }
+    Ok(())
+}
+
+/// Show an emoji before a project field in item details (leave emoji empty to clear)
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "field-emoji", ephemeral)]
+pub async fn field_emoji(
+    ctx: Context<'_>,
+    #[description = "Field name (e.g. Priority)"] field: String,
+    #[description = "Emoji (e.g. 🔥)"] emoji: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("guild only")?.get();
+    let emoji = emoji.map(|e| e.trim().to_string()).filter(|e| !e.is_empty());
+    let saved = {
+        let mut configs = ctx.data().guild_configs.write().await;
+        let map = &mut configs.guilds.entry(guild_id).or_default().field_emoji;
+        match &emoji {
+            Some(e) => { map.insert(field.to_lowercase(), e.clone()); }
+            None => { map.remove(&field.to_lowercase()); }
+        }
+        configs.save(&ctx.data().guild_config_path)
+    };
+    match emoji {
+        Some(e) => ctx.say(format!("✅ **{}** is now shown as {} {}.{}", field, e, field, save_warning(saved, "server config"))).await?,
+        None => ctx.say(format!("✅ Cleared the emoji for **{}**.{}", field, save_warning(saved, "server config"))).await?,
+    };
+    Ok(())
+}
+
+/// Board and Date field used by `/repo assign due:...` (leave project empty to clear)
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "default-board", ephemeral)]
+pub async fn default_board(
+    ctx: Context<'_>,
+    #[description = "Project Title"]
+    #[autocomplete = "project_autocomplete"]
+    project: Option<String>,
+    #[description = "Date field to store the due date in (e.g. Due)"]
+    #[autocomplete = "field_autocomplete"]
+    date_field: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("guild only")?.get();
+    let board = match (project, date_field) {
+        (None, _) => None,
+        (Some(_), None) => {
+            ctx.say("❌ Also give the Date field to set, e.g. `Due`.").await?;
+            return Ok(());
+        }
+        (Some(project), Some(date_field)) => {
+            let proj = find_project(&ctx.data().projects.read().await, &project).map_err(BotError::NotFound)?;
+            let Some(field) = proj.fields.iter().find(|f| f.name.eq_ignore_ascii_case(&date_field)) else {
+                ctx.say(format!("Field '{}' not found in project '{}'.", date_field, proj.title)).await?;
+                return Ok(());
+            };
+            if field.data_type != "DATE" {
+                ctx.say(format!("❌ **{}** is not a Date field.", field.name)).await?;
+                return Ok(());
+            }
+            Some(DefaultBoard { project: proj.title.clone(), date_field: field.name.clone() })
+        }
+    };
+
+    let saved = {
+        let mut configs = ctx.data().guild_configs.write().await;
+        configs.guilds.entry(guild_id).or_default().default_board = board.clone();
+        configs.save(&ctx.data().guild_config_path)
+    };
+    match board {
+        Some(b) => ctx.say(format!("📅 `/repo assign due:...` now sets **{}** on **{}**.{}", b.date_field, b.project, save_warning(saved, "server config"))).await?,
+        None => ctx.say(format!("✅ Cleared the default board; `/repo assign` won't set due dates.{}", save_warning(saved, "server config"))).await?,
+    };
+    Ok(())
+}
 
+/// How many days ahead a Date field counts as "due soon" in item details
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "due-soon", ephemeral)]
+pub async fn due_soon(
+    ctx: Context<'_>,
+    #[description = "Days (0 = only flag overdue items)"]
+    #[max = 365]
+    days: u32,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("guild only")?.get();
+    let saved = {
+        let mut configs = ctx.data().guild_configs.write().await;
+        configs.guilds.entry(guild_id).or_default().due_soon_days = Some(days);
+        configs.save(&ctx.data().guild_config_path)
+    };
+    ctx.say(format!("📅 Open items due within **{}** day(s) are now marked 🟡; overdue ones are always 🔴.{}", days, save_warning(saved, "server config"))).await?;
     Ok(())
 }
 
 /// Manually trigger cache refresh
 #[poise::command(slash_command, owners_only)]
-pub async fn refresh(ctx: Context<'_>) -> Result<(), Error> {
-    ctx.say("🔄 Refreshing cache...").await?;
-    refresh_cache(&ctx.data()).await?;
-    ctx.say("✅ Cache refreshed!").await?;
+pub async fn refresh(
+    ctx: Context<'_>,
+    #[description = "Part of the cache to reload (default all)"]
+    scope: Option<RefreshScope>,
+) -> Result<(), Error> {
+    let scope = scope.unwrap_or(RefreshScope::All);
+    ctx.say(format!("🔄 Refreshing cache ({})...", scope.name())).await?;
+    let state = ctx.data();
+    let complete = match scope {
+        RefreshScope::All => refresh_cache(state).await?,
+        RefreshScope::Repos => refresh_repos(state).await?,
+        RefreshScope::Users => refresh_users(state).await?,
+        RefreshScope::Projects => refresh_projects(state).await?,
+    };
+    let headline = if complete {
+        "✅ Cache refreshed!"
+    } else {
+        "⚠️ Cache partly refreshed: some orgs couldn't be fetched and kept their previous data (see the logs)."
+    };
+    ctx.say(format!("{}\n{}", headline, refresh_summary(state).await)).await?;
+    Ok(())
+}
+
+// One line per cache part with when it last finished refreshing
+async fn refresh_summary(state: &BotState) -> String {
+    refresh_times(state).await.into_iter()
+        .map(|(part, at)| match at {
+            Some(at) => format!("• **{}**: <t:{}:R>", part, at.timestamp()),
+            None => format!("• **{}**: not since startup", part),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Show the bot's orgs, cache, GitHub rate limit and your connection
+#[poise::command(slash_command, ephemeral)]
+pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
+    let state = ctx.data();
+    let orgs = state.github_orgs.iter().map(|o| format!("`{}`", o)).collect::<Vec<_>>().join(", ");
+    let cache = format!(
+        "{} repos • {} users • {} projects",
+        state.repos.read().await.len(), state.users.read().await.len(), state.projects.read().await.len()
+    );
+
+    // The rate limit endpoint itself doesn't count against the limit
+    let rate_limit = match state.octocrab.ratelimit().get().await {
+        Ok(limits) => {
+            let line = |name: &str, rate: &octocrab::models::Rate| {
+                format!("• **{}**: {}/{} (resets <t:{}:R>)", name, rate.remaining, rate.limit, rate.reset)
+            };
+            let mut lines = vec![line("REST", &limits.resources.core)];
+            if let Some(graphql) = &limits.resources.graphql {
+                lines.push(line("GraphQL", graphql));
+            }
+            lines.join("\n")
+        }
+        Err(e) => format!("❌ Couldn't fetch: {}", e),
+    };
+
+    let connection = match state.user_mapping.read().await.map.get(&ctx.author().id.get()) {
+        Some(login) => format!("✅ Connected as [{}](https://github.com/{})", login, login),
+        None => "❌ Not connected, run `/user connect`".to_string(),
+    };
+
+    let embed = serenity::CreateEmbed::new()
+        .title("🩺 Bot Status")
+        .field("🏢 Orgs", orgs, false)
+        .field("📦 Cached", cache, false)
+        .field("🔄 Last refreshed", refresh_summary(state).await, false)
+        .field("⏱️ GitHub rate limit", rate_limit, false)
+        .field("🔗 Your account", connection, false)
+        .color(0x5865F2);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Run a read-only GraphQL query against GitHub and return the JSON
+#[poise::command(slash_command, owners_only, ephemeral, rename = "eval-graphql")]
+pub async fn eval_graphql(
+    ctx: Context<'_>,
+    #[description = "GraphQL query (no mutations)"] query: String,
+) -> Result<(), Error> {
+    if query.to_lowercase().contains("mutation") {
+        ctx.say("❌ Mutations are not allowed here.").await?;
+        return Ok(());
+    }
+    defer_ephemeral(ctx).await;
+
+    let body = serde_json::json!({ "query": query });
+    match graphql(ctx.data(), stats::source(ctx), &body).await {
+        Ok(resp) => {
+            let pretty = serde_json::to_string_pretty(&resp)?;
+            ctx.send(poise::CreateReply::default()
+                .content("✅ Query result:")
+                .attachment(serenity::CreateAttachment::bytes(pretty.into_bytes(), "result.json"))
+            ).await?;
+        }
+        Err(e) => {
+            ctx.say(format!("❌ Query failed: {}", e)).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Show GitHub API calls per command since startup
+#[poise::command(slash_command, owners_only, ephemeral)]
+pub async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+    let rows = ctx.data().api_stats.snapshot();
+    if rows.is_empty() {
+        ctx.say("No GitHub API calls recorded yet.").await?;
+        return Ok(());
+    }
+
+    let total: u64 = rows.iter().map(|(_, _, n)| n).sum();
+    let lines: Vec<String> = rows.iter().take(25)
+        .map(|(source, api, n)| format!("`{:>5}` {} ({})", n, source, api.as_str()))
+        .collect();
+    let embed = serenity::CreateEmbed::new()
+        .title("📊 GitHub API usage")
+        .description(lines.join("\n"))
+        .footer(serenity::CreateEmbedFooter::new(format!("{} calls since startup", total)))
+        .color(0x5865F2);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(title: &str) -> CachedProject {
+        CachedProject {
+            org: "acme".into(),
+            id: "PVT_kwDOA1b2".into(),
+            title: title.into(),
+            url: "https://github.com/orgs/acme/projects/4".into(),
+            number: 4,
+            short_description: None,
+            items: Vec::new(),
+            fields: Vec::new(),
+        }
+    }
+
+    // Regression: titles with '_' used to be split apart when parsing proj_page_{title}_{page}
+    #[test]
+    fn page_buttons_survive_underscore_titles() {
+        let proj = project("my_cool_project");
+        let view = ViewPreset { project: proj.title.clone(), label: Some("bug".into()), group_by: Some("Status".into()), ..Default::default() };
+        let id = view_page_id(&proj, &view, "active", 2).expect("view fits in a custom_id");
+        assert!(!id.contains("my_cool_project"));
+        assert_eq!(CustomId::parse(&id), Some(CustomId::ProjRefresh {
+            proj_id: "PVT_kwDOA1b2".into(),
+            page: 2,
+            filter: "active".into(),
+            show_field: None,
+            group_by: Some("Status".into()),
+            label: Some("bug".into()),
+        }));
+    }
+
+    #[test]
+    fn default_view_falls_back_to_the_project_number() {
+        // A project id too long for ProjRefresh, so only the board number is left
+        let proj = CachedProject { id: format!("PVT_{}", "k".repeat(90)), ..project("my_cool_project") };
+        let other = CachedProject { id: "PVT_kwDOZz99".into(), number: 9, ..project("my_cool_project_v2") };
+        let projects = [other, proj.clone()];
+        let view = ViewPreset { project: proj.title.clone(), ..Default::default() };
+        assert_eq!(view_refresh_id(&proj, &view, "active", 3), None);
+
+        let id = view_page_id(&proj, &view, "active", 3).expect("default view always pages");
+        let Some(CustomId::ProjPage { number, page }) = CustomId::parse(&id) else { panic!("{} is not a proj_page id", id) };
+        assert_eq!(page, 3);
+        // The handler looks the board up by number, never by (a prefix of) the title
+        assert_eq!(projects.iter().find(|p| p.number == number).map(|p| p.title.as_str()), Some("my_cool_project"));
+    }
+
+    // Regression: a filtered view that didn't fit used to page through the unfiltered board
+    #[test]
+    fn other_views_drop_the_pager_when_too_long() {
+        let proj = project("my_cool_project");
+        let view = ViewPreset { project: proj.title.clone(), show_field: Some("Sprint: current".into()), ..Default::default() };
+        assert_eq!(view_refresh_id(&proj, &view, "active", 3), None);
+        assert_eq!(view_page_id(&proj, &view, "active", 3), None);
+
+        let view = ViewPreset { project: proj.title.clone(), label: Some("x".repeat(90)), ..Default::default() };
+        assert_eq!(view_refresh_id(&proj, &view, "active", 1), None);
+        assert_eq!(view_page_id(&proj, &view, "active", 1), None);
+        // A short filtered view still pages
+        assert!(view_page_id(&proj, &ViewPreset { project: proj.title.clone(), ..Default::default() }, "done", 1).is_some());
+    }
+
+    #[test]
+    fn view_query_only_reads_what_the_view_shows() {
+        let plain = view_item_fields(&ViewPreset::default());
+        assert!(!plain.contains("fieldValues") && !plain.contains("labels"), "{}", plain);
+
+        let grouped = view_item_fields(&ViewPreset { group_by: Some("Status".into()), ..Default::default() });
+        assert!(grouped.contains("fieldValues") && !grouped.contains("labels"), "{}", grouped);
+        let labelled = view_item_fields(&ViewPreset { label: Some("bug".into()), ..Default::default() });
+        assert!(!labelled.contains("fieldValues") && labelled.contains("labels"), "{}", labelled);
+    }
+}
@@ -0,0 +1,297 @@
+use std::fmt;
+use poise::ChoiceParameter;
+use crate::types::StateFilter;
+
+// Every persistent component custom_id the bot emits, so the format and the parser live side by side.
+// Discord limits custom_ids to 100 chars; project/field ids are short node ids.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CustomId {
+    // proj_page:{project number}:{page}
+    ProjPage { number: i64, page: usize },
+    // proj:refresh:{proj_id}:{page}:{filter}:{show_field}:{group_by}:{label} (empty = unset)
+    ProjRefresh { proj_id: String, page: usize, filter: String, show_field: Option<String>, group_by: Option<String>, label: Option<String> },
+    // issues_page:{repo}:{page}, or issues_page:{repo}:{page}:{state}:{assignee}:{label} when filtered
+    IssuesPage { repo: String, page: u32, state: String, assignee: Option<String>, label: Option<String> },
+    // refresh_issues_{repo}
+    RefreshIssues { repo: String },
+    // proj_select_{proj_id}
+    ProjSelect { proj_id: String },
+    // edit:item:{proj_id}:{num}
+    EditItem { proj_id: String, num: i64 },
+    // field:sel:{proj_id}:{num}
+    FieldSelect { proj_id: String, num: i64 },
+    // val:sel:{proj_id}:{num}:{field_id}
+    ValueSelect { proj_id: String, num: i64, field_id: String },
+    // val:modal:{proj_id}:{num}:{field_id}
+    ValueModal { proj_id: String, num: i64, field_id: String },
+    // body:more:{repo}:{num}
+    BodyMore { repo: String, num: u64 },
+    // create_draft:{proj_id} or create_draft:{proj_id}:{status option id}
+    CreateDraft { proj_id: String, status: Option<String> },
+    // create_issue:{repo}
+    CreateIssue { repo: String },
+    // comment:modal:{repo}:{num}
+    CommentModal { repo: String, num: u64 },
+    // target:confirm:{repo}:{num}:{args} (args last, may contain ':')
+    TargetConfirm { repo: String, num: u64, args: String },
+    // target:cancel
+    TargetCancel,
+    // user_page:{login}:{page}, or user_page:{login}:{page}:{state} when not open (/user view Prev/Next)
+    UserPage { login: String, page: u32, state: StateFilter },
+    // user:connect (starts the GitHub device flow)
+    Connect,
+    // edit_item_* and proj_page_{title}_{page} buttons from older bot versions
+    Outdated,
+}
+
+impl CustomId {
+    pub fn parse(id: &str) -> Option<Self> {
+        if let Some(rest) = id.strip_prefix("proj_page:") {
+            let (number, page) = rest.split_once(':')?;
+            return Some(CustomId::ProjPage { number: number.parse().ok()?, page: page.parse().ok()? });
+        }
+        if let Some(rest) = id.strip_prefix("issues_page:") {
+            // The label goes last so it may contain ':'
+            let parts: Vec<&str> = rest.splitn(5, ':').collect();
+            if parts[0].is_empty() { return None; }
+            let opt = |s: &str| if s.is_empty() { None } else { Some(s.to_string()) };
+            let page = parts.get(1)?.parse().ok()?;
+            return match parts.len() {
+                2 => Some(CustomId::IssuesPage { repo: parts[0].to_string(), page, state: "open".to_string(), assignee: None, label: None }),
+                5 if !parts[2].is_empty() => Some(CustomId::IssuesPage {
+                    repo: parts[0].to_string(),
+                    page,
+                    state: parts[2].to_string(),
+                    assignee: opt(parts[3]),
+                    label: opt(parts[4]),
+                }),
+                _ => None,
+            };
+        }
+        if let Some(repo) = id.strip_prefix("refresh_issues_") {
+            // Repo names may contain underscores, so take everything after the prefix
+            if repo.is_empty() { return None; }
+            return Some(CustomId::RefreshIssues { repo: repo.to_string() });
+        }
+        if let Some(proj_id) = id.strip_prefix("proj_select_") {
+            if proj_id.is_empty() { return None; }
+            return Some(CustomId::ProjSelect { proj_id: proj_id.to_string() });
+        }
+        if let Some(rest) = id.strip_prefix("proj:refresh:") {
+            // The label goes last so it may contain ':'
+            let parts: Vec<&str> = rest.splitn(6, ':').collect();
+            if parts.len() != 6 || parts[0].is_empty() { return None; }
+            let opt = |s: &str| if s.is_empty() { None } else { Some(s.to_string()) };
+            return Some(CustomId::ProjRefresh {
+                proj_id: parts[0].to_string(),
+                page: parts[1].parse().ok()?,
+                filter: parts[2].to_string(),
+                show_field: opt(parts[3]),
+                group_by: opt(parts[4]),
+                label: opt(parts[5]),
+            });
+        }
+        if let Some(rest) = id.strip_prefix("create_draft:") {
+            let (proj_id, status) = match rest.split_once(':') {
+                Some((p, s)) if !s.is_empty() => (p, Some(s.to_string())),
+                Some(_) => return None,
+                None => (rest, None),
+            };
+            if proj_id.is_empty() { return None; }
+            return Some(CustomId::CreateDraft { proj_id: proj_id.to_string(), status });
+        }
+        if let Some(repo) = id.strip_prefix("create_issue:") {
+            if repo.is_empty() { return None; }
+            return Some(CustomId::CreateIssue { repo: repo.to_string() });
+        }
+        if let Some(rest) = id.strip_prefix("user_page:") {
+            let parts: Vec<&str> = rest.split(':').collect();
+            if parts[0].is_empty() { return None; }
+            let state = match parts.len() {
+                2 => StateFilter::Open,
+                3 => StateFilter::from_name(parts[2])?,
+                _ => return None,
+            };
+            return Some(CustomId::UserPage { login: parts[0].to_string(), page: parts[1].parse().ok()?, state });
+        }
+        if id == "user:connect" {
+            return Some(CustomId::Connect);
+        }
+        if id == "target:cancel" {
+            return Some(CustomId::TargetCancel);
+        }
+        if id.starts_with("edit_item_") || id.starts_with("proj_page_") {
+            return Some(CustomId::Outdated);
+        }
+
+        let (kind, rest) = if let Some(rest) = id.strip_prefix("edit:item:") {
+            ("edit:item", rest)
+        } else if let Some(rest) = id.strip_prefix("field:sel:") {
+            ("field:sel", rest)
+        } else if let Some(rest) = id.strip_prefix("val:sel:") {
+            ("val:sel", rest)
+        } else if let Some(rest) = id.strip_prefix("val:modal:") {
+            ("val:modal", rest)
+        } else if let Some(rest) = id.strip_prefix("body:more:") {
+            ("body:more", rest)
+        } else if let Some(rest) = id.strip_prefix("comment:modal:") {
+            ("comment:modal", rest)
+        } else if let Some(rest) = id.strip_prefix("target:confirm:") {
+            ("target:confirm", rest)
+        } else {
+            return None;
+        };
+
+        // The trailing segment keeps any further ':' so values containing colons survive
+        let parts: Vec<&str> = rest.splitn(3, ':').collect();
+        let first = parts.first().filter(|s| !s.is_empty())?.to_string();
+        match (kind, parts.len()) {
+            ("edit:item", 2) => Some(CustomId::EditItem { proj_id: first, num: parts[1].parse().ok()? }),
+            ("field:sel", 2) => Some(CustomId::FieldSelect { proj_id: first, num: parts[1].parse().ok()? }),
+            ("val:sel", 3) if !parts[2].is_empty() => Some(CustomId::ValueSelect { proj_id: first, num: parts[1].parse().ok()?, field_id: parts[2].to_string() }),
+            ("val:modal", 3) if !parts[2].is_empty() => Some(CustomId::ValueModal { proj_id: first, num: parts[1].parse().ok()?, field_id: parts[2].to_string() }),
+            ("body:more", 2) => Some(CustomId::BodyMore { repo: first, num: parts[1].parse().ok()? }),
+            ("comment:modal", 2) => Some(CustomId::CommentModal { repo: first, num: parts[1].parse().ok()? }),
+            ("target:confirm", 3) if !parts[2].is_empty() => Some(CustomId::TargetConfirm { repo: first, num: parts[1].parse().ok()?, args: parts[2].to_string() }),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CustomId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CustomId::ProjPage { number, page } => write!(f, "proj_page:{}:{}", number, page),
+            CustomId::ProjRefresh { proj_id, page, filter, show_field, group_by, label } => write!(
+                f, "proj:refresh:{}:{}:{}:{}:{}:{}", proj_id, page, filter,
+                show_field.as_deref().unwrap_or(""), group_by.as_deref().unwrap_or(""), label.as_deref().unwrap_or("")
+            ),
+            CustomId::IssuesPage { repo, page, state, assignee: None, label: None } if state == "open" => write!(f, "issues_page:{}:{}", repo, page),
+            CustomId::IssuesPage { repo, page, state, assignee, label } => write!(
+                f, "issues_page:{}:{}:{}:{}:{}", repo, page, state, assignee.as_deref().unwrap_or(""), label.as_deref().unwrap_or("")
+            ),
+            CustomId::RefreshIssues { repo } => write!(f, "refresh_issues_{}", repo),
+            CustomId::ProjSelect { proj_id } => write!(f, "proj_select_{}", proj_id),
+            CustomId::EditItem { proj_id, num } => write!(f, "edit:item:{}:{}", proj_id, num),
+            CustomId::FieldSelect { proj_id, num } => write!(f, "field:sel:{}:{}", proj_id, num),
+            CustomId::ValueSelect { proj_id, num, field_id } => write!(f, "val:sel:{}:{}:{}", proj_id, num, field_id),
+            CustomId::ValueModal { proj_id, num, field_id } => write!(f, "val:modal:{}:{}:{}", proj_id, num, field_id),
+            CustomId::BodyMore { repo, num } => write!(f, "body:more:{}:{}", repo, num),
+            CustomId::CreateDraft { proj_id, status: Some(status) } => write!(f, "create_draft:{}:{}", proj_id, status),
+            CustomId::CreateDraft { proj_id, status: None } => write!(f, "create_draft:{}", proj_id),
+            CustomId::CreateIssue { repo } => write!(f, "create_issue:{}", repo),
+            CustomId::CommentModal { repo, num } => write!(f, "comment:modal:{}:{}", repo, num),
+            CustomId::TargetConfirm { repo, num, args } => write!(f, "target:confirm:{}:{}:{}", repo, num, args),
+            CustomId::TargetCancel => write!(f, "target:cancel"),
+            CustomId::UserPage { login, page, state: StateFilter::Open } => write!(f, "user_page:{}:{}", login, page),
+            CustomId::UserPage { login, page, state } => write!(f, "user_page:{}:{}:{}", login, page, state.name()),
+            CustomId::Connect => write!(f, "user:connect"),
+            CustomId::Outdated => write!(f, "edit_item_"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(id: CustomId) {
+        let text = id.to_string();
+        assert_eq!(CustomId::parse(&text), Some(id), "round trip of {}", text);
+    }
+
+    #[test]
+    fn every_variant_round_trips() {
+        let ids = vec![
+            CustomId::ProjPage { number: 7, page: 2 },
+            CustomId::ProjRefresh { proj_id: "PVT_kw1".into(), page: 3, filter: "all".into(), show_field: Some("Status".into()), group_by: Some("Priority".into()), label: Some("bug".into()) },
+            CustomId::ProjRefresh { proj_id: "PVT_kw1".into(), page: 1, filter: "active".into(), show_field: None, group_by: None, label: None },
+            CustomId::IssuesPage { repo: "api".into(), page: 2, state: "open".into(), assignee: None, label: None },
+            CustomId::IssuesPage { repo: "api".into(), page: 4, state: "closed".into(), assignee: Some("octocat".into()), label: Some("good first issue".into()) },
+            CustomId::RefreshIssues { repo: "api".into() },
+            CustomId::ProjSelect { proj_id: "PVT_kw1".into() },
+            CustomId::EditItem { proj_id: "PVT_kw1".into(), num: 12 },
+            CustomId::FieldSelect { proj_id: "PVT_kw1".into(), num: 12 },
+            CustomId::ValueSelect { proj_id: "PVT_kw1".into(), num: 12, field_id: "PVTF_x".into() },
+            CustomId::ValueModal { proj_id: "PVT_kw1".into(), num: 12, field_id: "PVTF_x".into() },
+            CustomId::BodyMore { repo: "api".into(), num: 5 },
+            CustomId::CreateDraft { proj_id: "PVT_kw1".into(), status: None },
+            CustomId::CreateDraft { proj_id: "PVT_kw1".into(), status: Some("f75ad846".into()) },
+            CustomId::CreateIssue { repo: "api".into() },
+            CustomId::CommentModal { repo: "api".into(), num: 5 },
+            CustomId::TargetConfirm { repo: "api".into(), num: 5, args: "due:2024-06-01 milestone:v1".into() },
+            CustomId::TargetCancel,
+            CustomId::UserPage { login: "octocat".into(), page: 2, state: StateFilter::Open },
+            CustomId::UserPage { login: "octocat".into(), page: 3, state: StateFilter::Closed },
+            CustomId::Connect,
+        ];
+        for id in ids {
+            round_trip(id);
+        }
+    }
+
+    #[test]
+    fn trailing_segments_keep_colons() {
+        round_trip(CustomId::TargetConfirm { repo: "api".into(), num: 5, args: "milestone:Sprint: 3 due:+2w".into() });
+        round_trip(CustomId::IssuesPage { repo: "api".into(), page: 1, state: "all".into(), assignee: None, label: Some("area:backend".into()) });
+        round_trip(CustomId::ProjRefresh { proj_id: "PVT_kw1".into(), page: 2, filter: "all".into(), show_field: None, group_by: None, label: Some("scope:ui".into()) });
+        assert_eq!(
+            CustomId::parse("target:confirm:api:5:milestone:v1:rc"),
+            Some(CustomId::TargetConfirm { repo: "api".into(), num: 5, args: "milestone:v1:rc".into() })
+        );
+    }
+
+    #[test]
+    fn underscores_in_names() {
+        round_trip(CustomId::RefreshIssues { repo: "my_cool_repo".into() });
+        assert_eq!(CustomId::parse("refresh_issues_my_cool_repo"), Some(CustomId::RefreshIssues { repo: "my_cool_repo".into() }));
+        round_trip(CustomId::IssuesPage { repo: "my_cool_repo".into(), page: 3, state: "open".into(), assignee: None, label: None });
+        round_trip(CustomId::ProjSelect { proj_id: "PVT_kwDO_ab".into() });
+    }
+
+    #[test]
+    fn empty_segments_are_rejected() {
+        for id in [
+            "refresh_issues_", "proj_select_", "create_issue:", "create_draft:", "create_draft:PVT_kw1:",
+            "edit:item::3", "val:sel:PVT_kw1:3:", "val:modal:PVT_kw1:3:", "target:confirm:api:5:",
+            "issues_page::2", "issues_page:api:2::octocat:", "proj:refresh::1:all:::", "user_page::2",
+        ] {
+            assert_eq!(CustomId::parse(id), None, "{} should not parse", id);
+        }
+    }
+
+    #[test]
+    fn non_numeric_pages_and_numbers_are_rejected() {
+        for id in [
+            "proj_page:7:two", "proj_page:seven:2", "issues_page:api:next", "proj:refresh:PVT_kw1:x:all:::",
+            "user_page:octocat:-1", "edit:item:PVT_kw1:abc", "body:more:api:5x", "comment:modal:api:",
+        ] {
+            assert_eq!(CustomId::parse(id), None, "{} should not parse", id);
+        }
+    }
+
+    #[test]
+    fn malformed_ids_are_rejected() {
+        assert_eq!(CustomId::parse(""), None);
+        assert_eq!(CustomId::parse("something:else"), None);
+        assert_eq!(CustomId::parse("user_page:octocat:2:sometimes"), None);
+        assert_eq!(CustomId::parse("proj:refresh:PVT_kw1:1:all"), None);
+        assert_eq!(CustomId::parse("edit:item:PVT_kw1:3:extra"), None);
+    }
+
+    #[test]
+    fn old_buttons_are_outdated() {
+        assert_eq!(CustomId::parse("edit_item_PVT_kw1_3"), Some(CustomId::Outdated));
+        assert_eq!(CustomId::parse("proj_page_My Board_2"), Some(CustomId::Outdated));
+    }
+
+    // Regression: titles with '_' used to be split apart when parsing proj_page_{title}_{page}
+    #[test]
+    fn page_ids_find_my_cool_project_by_number() {
+        let boards = [("my_cool_project_v2", 9), ("my_cool_project", 4)];
+        let id = CustomId::ProjPage { number: 4, page: 2 }.to_string();
+        let Some(CustomId::ProjPage { number, page }) = CustomId::parse(&id) else { panic!("{} is not a proj_page id", id) };
+        assert_eq!(page, 2);
+        assert_eq!(boards.iter().find(|(_, n)| *n == number).map(|(title, _)| *title), Some("my_cool_project"));
+    }
+}
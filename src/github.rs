@@ -0,0 +1,512 @@
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use crate::types::{BotError, BotState, Error};
+use crate::stats::Api;
+use tracing::{error, info, warn};
+use crate::logging::loggable_variables;
+use crate::utils::{item_field_value, repo_name};
+
+const MUTATION_ATTEMPTS: u32 = 3;
+const RATE_LIMIT_ATTEMPTS: u32 = 4;
+// Longest single rate-limit wait; a reset further out than this fails the call instead of hanging it
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// GitHub requests (searches and GraphQL calls) in flight at once, across every command and task
+pub const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+// Waits until nobody else is writing to this project item. Hold the guard across the
+// read-then-mutate sequence; locks nobody holds are dropped on the next call.
+pub async fn lock_item(state: &BotState, item_id: &str) -> tokio::sync::OwnedMutexGuard<()> {
+    let lock = {
+        let mut locks = state.item_locks.lock().unwrap_or_else(|e| e.into_inner());
+        locks.retain(|_, l| Arc::strong_count(l) > 1);
+        locks.entry(item_id.to_string()).or_default().clone()
+    };
+    lock.lock_owned().await
+}
+
+// Waits for a free request slot. Hold the permit only for the request itself, never across
+// another acquire, so fan-outs can't deadlock each other.
+async fn request_permit(state: &BotState) -> tokio::sync::SemaphorePermit<'_> {
+    // The semaphore is never closed
+    state.github_permits.acquire().await.expect("GitHub request semaphore closed")
+}
+
+// A GraphQL query, bounded by the shared request limit and retried when rate limited.
+// Every attempt is recorded under `source`.
+pub async fn graphql(state: &BotState, source: &str, query: &serde_json::Value) -> Result<serde_json::Value, octocrab::Error> {
+    let res = backoff(state, source, Api::Graphql, "GraphQL query", |res| match res {
+        Ok(resp) => graphql_rate_limited(resp),
+        Err(e) => is_rate_limited(e),
+    }, || async {
+        let _permit = request_permit(state).await;
+        state.octocrab.graphql(query).await
+    }).await;
+
+    // GitHub reports most GraphQL failures as a 200 with an `errors` array
+    match &res {
+        Ok(resp) => if let Some(errors) = resp.get("errors").filter(|e| !e.is_null()) {
+            error!(variables = %loggable_variables(query), "❌ GraphQL query returned errors: {}", errors);
+        },
+        Err(e) => error!(variables = %loggable_variables(query), "❌ GraphQL query failed: {}", e),
+    }
+    res
+}
+
+// GitHub's `errors` array as one message, None when there is none. GraphQL failures (a missing
+// field, a node that doesn't resolve, a missing scope) come back as a 200 with partial or null data.
+pub fn graphql_errors(resp: &serde_json::Value) -> Option<String> {
+    let errors = resp.get("errors")?.as_array().filter(|e| !e.is_empty())?;
+    Some(errors.iter()
+        .map(|e| e.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error"))
+        .collect::<Vec<_>>()
+        .join("; "))
+}
+
+// A GraphQL query whose `errors` count as a failure, so callers report the real problem instead
+// of reading the missing data as "not found"
+pub async fn graphql_checked(state: &BotState, source: &str, query: &serde_json::Value) -> Result<serde_json::Value, BotError> {
+    reject_errors(graphql(state, source, query).await?)
+}
+
+// A 200 response that carries an `errors` array as BotError::GraphQl
+fn reject_errors(resp: serde_json::Value) -> Result<serde_json::Value, BotError> {
+    match graphql_errors(&resp) {
+        Some(msg) => Err(BotError::GraphQl(msg)),
+        None => Ok(resp),
+    }
+}
+
+// Runs a single-request REST call, waiting out GitHub rate limits (up to RATE_LIMIT_ATTEMPTS
+// tries). Every attempt is recorded under `source`.
+pub async fn with_backoff<T, F, Fut>(state: &BotState, source: &str, what: &str, call: F) -> Result<T, octocrab::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, octocrab::Error>>,
+{
+    backoff(state, source, Api::Rest, what, |res| matches!(res, Err(e) if is_rate_limited(e)), call).await
+}
+
+// The items of `first` and of every page after it. Each following page is its own request,
+// retried when rate limited and recorded under `source`.
+pub async fn all_pages<T: serde::de::DeserializeOwned>(state: &BotState, source: &str, what: &str, mut first: octocrab::Page<T>) -> Result<Vec<T>, octocrab::Error> {
+    let mut items = first.take_items();
+    let mut next = first.next;
+    while let Some(url) = next {
+        let uri = Some(url);
+        let Some(mut page) = with_backoff(state, source, what, || state.octocrab.get_page::<T>(&uri)).await? else { break; };
+        items.append(&mut page.take_items());
+        next = page.next;
+    }
+    Ok(items)
+}
+
+async fn backoff<T, F, Fut>(state: &BotState, source: &str, api: Api, what: &str, limited: impl Fn(&Result<T, octocrab::Error>) -> bool, mut call: F) -> Result<T, octocrab::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, octocrab::Error>>,
+{
+    let mut delay = Duration::from_secs(2);
+    let mut attempt = 1;
+    loop {
+        state.api_stats.record(source, api);
+        let res = call().await;
+        if attempt >= RATE_LIMIT_ATTEMPTS || !limited(&res) {
+            return res;
+        }
+        // Wait for the reset when the primary limit is spent, otherwise (secondary limit) back off
+        let wait = match primary_reset_wait(state, api).await {
+            Some(wait) if wait > MAX_BACKOFF => {
+                warn!("⚠️ GitHub {} rate limit exhausted on {}, resets in {}s; giving up", api.as_str(), what, wait.as_secs());
+                return res;
+            }
+            Some(wait) => wait,
+            None => delay,
+        };
+        info!("⏳ GitHub rate limit hit on {} (attempt {}/{}), backing off {}s", what, attempt, RATE_LIMIT_ATTEMPTS, wait.as_secs());
+        tokio::time::sleep(wait).await;
+        delay = (delay * 2).min(MAX_BACKOFF);
+        attempt += 1;
+    }
+}
+
+// 403/429 with a rate limit message (primary or secondary limit)
+fn is_rate_limited(err: &octocrab::Error) -> bool {
+    matches!(err, octocrab::Error::GitHub { source, .. }
+        if matches!(source.status_code.as_u16(), 403 | 429) && source.message.to_lowercase().contains("rate limit"))
+}
+
+// GraphQL reports a spent budget as a 200 with a RATE_LIMITED error
+fn graphql_rate_limited(resp: &serde_json::Value) -> bool {
+    resp.get("errors").and_then(|e| e.as_array())
+        .is_some_and(|errors| errors.iter().any(|e| e.get("type").and_then(|t| t.as_str()) == Some("RATE_LIMITED")))
+}
+
+// Time until the primary limit resets, or None when it isn't spent. octocrab doesn't hand back
+// response headers (x-ratelimit-remaining/reset, retry-after), so ask /rate_limit, which is free.
+async fn primary_reset_wait(state: &BotState, api: Api) -> Option<Duration> {
+    let limits = state.octocrab.ratelimit().get().await.ok()?;
+    let rate = match api {
+        Api::Rest => limits.resources.core,
+        Api::Graphql => limits.resources.graphql?,
+    };
+    if rate.remaining > 0 {
+        return None;
+    }
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(Duration::from_secs(rate.reset.saturating_sub(now) + 1))
+}
+
+// Issue/PR search, bounded by the shared request limit and recorded under `source`
+pub async fn search_issues(state: &BotState, source: &str, query: &str, per_page: u8, page: u32) -> Result<octocrab::Page<octocrab::models::issues::Issue>, octocrab::Error> {
+    let _permit = request_permit(state).await;
+    state.api_stats.record(source, Api::Rest);
+    state.octocrab.search().issues_and_pull_requests(query).per_page(per_page).page(page).send().await
+}
+
+// Runs a GraphQL mutation, retrying transient failures (5xx, non-JSON gateway pages, connection
+// errors) with exponential backoff. 4xx responses are returned on the first attempt.
+// A failure may come after GitHub already applied the write, so this is only for mutations that
+// set a value (updateProjectV2ItemFieldValue, updateProjectV2) and can safely run twice.
+// Creates and deletes go through `graphql` once. Like `graphql_checked`, a rejected mutation (a 200
+// with `errors`, e.g. a value of the wrong type) is an error.
+pub async fn graphql_mutation(state: &BotState, source: &str, mutation: &serde_json::Value) -> Result<serde_json::Value, BotError> {
+    let mut delay = Duration::from_millis(500);
+    let mut attempt = 1;
+    loop {
+        match graphql(state, source, mutation).await {
+            Err(e) if attempt < MUTATION_ATTEMPTS && is_transient(&e) => {
+                warn!("⚠️ GraphQL mutation failed (attempt {}/{}), retrying in {}ms: {}", attempt, MUTATION_ATTEMPTS, delay.as_millis(), e);
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            res => return reject_errors(res?),
+        }
+    }
+}
+
+// GitHub answered 404 (missing repo/issue/label, or no access to it)
+pub fn is_not_found(err: &octocrab::Error) -> bool {
+    matches!(err, octocrab::Error::GitHub { source, .. } if source.status_code.as_u16() == 404)
+}
+
+fn is_transient(err: &octocrab::Error) -> bool {
+    match err {
+        octocrab::Error::GitHub { source, .. } => source.status_code.is_server_error(),
+        // A 502/504 from GitHub's edge comes back as HTML, which fails to parse as an error body
+        octocrab::Error::Serde { .. } => true,
+        octocrab::Error::Hyper { .. } | octocrab::Error::Service { .. } => true,
+        _ => false,
+    }
+}
+
+// Most items read from one board; anything past this is dropped (and logged)
+pub const MAX_PROJECT_ITEMS: usize = 1000;
+
+// Every item of a project, following the items cursor 100 at a time. `item_fields` is the
+// selection for one item node. Each page is its own GraphQL call, recorded under `source`.
+pub async fn fetch_project_items(state: &BotState, source: &str, project_id: &str, item_fields: &str) -> Result<Vec<serde_json::Value>, Error> {
+    let query_text = format!(
+        "query($id: ID!, $after: String) {{ node(id: $id) {{ ... on ProjectV2 {{ items(first: 100, after: $after) {{ pageInfo {{ hasNextPage endCursor }} nodes {{ {} }} }} }} }} }}",
+        item_fields
+    );
+    let mut items = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let query = serde_json::json!({
+            "query": query_text,
+            "variables": { "id": project_id, "after": cursor }
+        });
+        let json_resp = graphql_checked(state, source, &query).await?;
+        let Some(connection) = json_resp.get("data").and_then(|d| d.get("node")).and_then(|n| n.get("items")) else { break; };
+        if let Some(nodes) = connection.get("nodes").and_then(|n| n.as_array()) {
+            items.extend(nodes.iter().cloned());
+        }
+
+        let page_info = connection.get("pageInfo");
+        let has_next = page_info.and_then(|p| p.get("hasNextPage")).and_then(|h| h.as_bool()).unwrap_or(false);
+        cursor = page_info.and_then(|p| p.get("endCursor")).and_then(|c| c.as_str()).map(|c| c.to_string());
+        if !has_next || cursor.is_none() {
+            break;
+        }
+        if items.len() >= MAX_PROJECT_ITEMS {
+            warn!("⚠️ Project {} has more than {} items, only the first {} are used", project_id, MAX_PROJECT_ITEMS, MAX_PROJECT_ITEMS);
+            break;
+        }
+    }
+    items.truncate(MAX_PROJECT_ITEMS);
+    Ok(items)
+}
+
+// What fetch_project_item returns for an item: node id, content and field values
+const ITEM_FIELDS: &str = r#"
+    id
+    content {
+        ... on Issue { number title repository { name } }
+        ... on PullRequest { number title repository { name } }
+    }
+    fieldValues(first: 20) {
+        nodes {
+            ... on ProjectV2ItemFieldTextValue { text field { ... on ProjectV2FieldCommon { name } } }
+            ... on ProjectV2ItemFieldDateValue { date field { ... on ProjectV2FieldCommon { name } } }
+            ... on ProjectV2ItemFieldSingleSelectValue { name field { ... on ProjectV2FieldCommon { name } } }
+            ... on ProjectV2ItemFieldNumberValue { number field { ... on ProjectV2FieldCommon { name } } }
+            ... on ProjectV2ItemFieldIterationValue { title field { ... on ProjectV2FieldCommon { name } } }
+        }
+    }
+"#;
+
+// Item node id for an issue/PR number from the project cache
+async fn cached_item_id(state: &BotState, project_id: &str, number: i64) -> Option<String> {
+    let projects = state.projects.read().await;
+    projects.iter().find(|p| p.id == project_id)?
+        .items.iter().find(|i| i.number == number && !i.id.is_empty())
+        .map(|i| i.id.clone())
+}
+
+// The project item for an issue/PR number, with its node id, title and field values.
+// Fetches just that item when its node id is cached, otherwise scans the whole board.
+pub async fn fetch_project_item(state: &BotState, source: &str, project_id: &str, number: i64) -> Result<Option<serde_json::Value>, Error> {
+    if let Some(item_id) = cached_item_id(state, project_id, number).await {
+        if let Some(item) = fetch_item_node(state, source, &item_id).await? {
+            return Ok(Some(item));
+        }
+    }
+
+    let items = fetch_project_items(state, source, project_id, ITEM_FIELDS).await?;
+    Ok(items.into_iter().find(|item| {
+        item.get("content").and_then(|c| c.get("number")).and_then(|n| n.as_i64()) == Some(number)
+    }))
+}
+
+// A project item (drafts included) by node id, with the same fields as fetch_project_item.
+// None when the item is no longer on the board.
+pub async fn fetch_item_node(state: &BotState, source: &str, item_id: &str) -> Result<Option<serde_json::Value>, Error> {
+    let query = serde_json::json!({
+        "query": format!("query($id: ID!) {{ node(id: $id) {{ ... on ProjectV2Item {{ {} }} }} }}", ITEM_FIELDS),
+        "variables": { "id": item_id }
+    });
+    // Not graphql_checked: an item that left the board comes back as a null node with a
+    // "could not resolve" error
+    let json_resp = graphql(state, source, &query).await?;
+    Ok(json_resp.get("data").and_then(|d| d.get("node")).filter(|n| n.get("id").is_some()).cloned())
+}
+
+// Current value of one field of an item, for the audit log's old value ("Empty" when unset).
+// Call it under the item lock, right before the write. None when it couldn't be read.
+pub async fn current_field_value(state: &BotState, source: &str, item_id: &str, field_name: &str) -> Option<String> {
+    let item = fetch_item_node(state, source, item_id).await.ok().flatten()?;
+    Some(item_field_value(&item, field_name).unwrap_or_else(|| "Empty".to_string()))
+}
+
+// Node id of the project item for an issue/PR number: from the cache, or a scan of the
+// board on a miss
+pub async fn item_node_id(state: &BotState, source: &str, project_id: &str, number: i64) -> Result<Option<String>, Error> {
+    if let Some(id) = cached_item_id(state, project_id, number).await {
+        return Ok(Some(id));
+    }
+    let items = fetch_project_items(state, source, project_id, "id content { ... on Issue { number } ... on PullRequest { number } }").await?;
+    let id = items.iter()
+        .find(|item| item.get("content").and_then(|c| c.get("number")).and_then(|n| n.as_i64()) == Some(number))
+        .and_then(|item| item.get("id")).and_then(|s| s.as_str())
+        .map(|s| s.to_string());
+    Ok(id)
+}
+
+// Startup probe of what the token can read, reported as one line per org so scope problems
+// don't have to be picked out of the first refresh's logs. Failures are warnings, never fatal.
+pub async fn check_token(state: &BotState) {
+    for org in &state.github_orgs {
+        check_org_token(state, org).await;
+    }
+}
+
+async fn check_org_token(state: &BotState, org: &str) {
+    let octocrab = &state.octocrab;
+
+    state.api_stats.record("startup", Api::Rest);
+    let repos = octocrab.orgs(org).list_repos().per_page(1).send().await.map(|_| ()).map_err(|e| e.to_string());
+    state.api_stats.record("startup", Api::Rest);
+    let members = octocrab.orgs(org).list_members().per_page(1).send().await.map(|_| ()).map_err(|e| e.to_string());
+
+    // Missing project scopes come back as a 200 with an `errors` array
+    let query = serde_json::json!({
+        "query": "query($org: String!) { organization(login: $org) { projectsV2(first: 1) { totalCount } } }",
+        "variables": { "org": org }
+    });
+    let projects = graphql_checked(state, "startup", &query).await.map(|_| ()).map_err(|e| e.to_string());
+
+    let checks = [
+        ("repos", "repo", repos),
+        ("members", "read:org", members),
+        ("projects", "read:project", projects),
+    ];
+    let summary: Vec<String> = checks.iter().map(|(name, scope, res)| match res {
+        Ok(()) => format!("✅ {}", name),
+        Err(_) => format!("❌ {} (token missing {}?)", name, scope),
+    }).collect();
+    info!("🔑 Token check for {}: {}", org, summary.join(", "));
+    for (name, _, res) in &checks {
+        if let Err(e) = res {
+            warn!("⚠️ Token can't read {} of {}: {}", name, org, e);
+        }
+    }
+}
+
+// Open milestone of a repo by title (case-insensitive)
+pub async fn find_milestone(state: &BotState, repo: &str, title: &str) -> Result<Option<octocrab::models::Milestone>, octocrab::Error> {
+    let route = format!("/repos/{}/{}/milestones", state.repo_org(repo).await, repo_name(repo));
+    let milestones: Vec<octocrab::models::Milestone> = state.octocrab
+        .get(route, Some(&serde_json::json!({ "state": "open", "per_page": 100 })))
+        .await?;
+    Ok(milestones.into_iter().find(|m| m.title.eq_ignore_ascii_case(title)))
+}
+
+// GitHub wants milestone due dates as timestamps; midnight UTC of the day
+fn milestone_due_on(date: chrono::NaiveDate) -> String {
+    format!("{}T00:00:00Z", date.format("%Y-%m-%d"))
+}
+
+pub async fn create_milestone(state: &BotState, repo: &str, title: &str, due: Option<chrono::NaiveDate>) -> Result<octocrab::models::Milestone, octocrab::Error> {
+    let route = format!("/repos/{}/{}/milestones", state.repo_org(repo).await, repo_name(repo));
+    let mut body = serde_json::json!({ "title": title });
+    if let Some(d) = due {
+        body["due_on"] = serde_json::json!(milestone_due_on(d));
+    }
+    state.octocrab.post(route, Some(&body)).await
+}
+
+pub async fn set_milestone_due(state: &BotState, repo: &str, number: i64, due: chrono::NaiveDate) -> Result<octocrab::models::Milestone, octocrab::Error> {
+    let route = format!("/repos/{}/{}/milestones/{}", state.repo_org(repo).await, repo_name(repo), number);
+    state.octocrab.patch(route, Some(&serde_json::json!({ "due_on": milestone_due_on(due) }))).await
+}
+
+// octocrab has add_assignees but no remove counterpart. `client` is the acting user's (BotState::octocrab_for).
+pub async fn remove_assignee(state: &BotState, client: &octocrab::Octocrab, repo: &str, number: u64, login: &str) -> Result<octocrab::models::issues::Issue, octocrab::Error> {
+    let route = format!("/repos/{}/{}/issues/{}/assignees", state.repo_org(repo).await, repo_name(repo), number);
+    client.delete(route, Some(&serde_json::json!({ "assignees": [login] }))).await
+}
+
+// Creates a draft issue on a project and returns the new item id. Err holds the request error
+// or GitHub's message when the mutation was rejected.
+pub async fn add_draft_item(state: &BotState, source: &str, project_id: &str, title: &str, body: &str) -> Result<String, String> {
+    let mutation = serde_json::json!({
+        "query": r#"
+            mutation($projectId: ID!, $title: String!, $body: String) {
+                addProjectV2DraftIssue(input: { projectId: $projectId, title: $title, body: $body }) { projectItem { id } }
+            }
+        "#,
+        "variables": { "projectId": project_id, "title": title, "body": body }
+    });
+    // Not retried: a resend after a timeout would create a second draft
+    let resp = graphql(state, source, &mutation).await.map_err(|e| e.to_string())?;
+    if let Some(id) = resp.get("data").and_then(|d| d.get("addProjectV2DraftIssue")).and_then(|a| a.get("projectItem"))
+        .and_then(|i| i.get("id")).and_then(|id| id.as_str()) {
+        return Ok(id.to_string());
+    }
+    Err(graphql_errors(&resp).unwrap_or_else(|| "unknown error".to_string()))
+}
+
+// Sets a single-select field (e.g. Status) on a project item. Err holds the request error
+// or GitHub's message when the mutation was rejected.
+pub async fn set_item_option(state: &BotState, source: &str, project_id: &str, item_id: &str, field_id: &str, option_id: &str) -> Result<(), String> {
+    let mutation = serde_json::json!({
+        "query": r#"
+            mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $optionId: String!) {
+                updateProjectV2ItemFieldValue(input: {
+                    projectId: $projectId, itemId: $itemId, fieldId: $fieldId, value: { singleSelectOptionId: $optionId }
+                }) { projectV2Item { id } }
+            }
+        "#,
+        "variables": { "projectId": project_id, "itemId": item_id, "fieldId": field_id, "optionId": option_id }
+    });
+    let resp = graphql_mutation(state, source, &mutation).await.map_err(|e| e.to_string())?;
+    if resp.get("data").and_then(|d| d.get("updateProjectV2ItemFieldValue")).is_some_and(|u| !u.is_null()) {
+        return Ok(());
+    }
+    Err(graphql_errors(&resp).unwrap_or_else(|| "unknown error".to_string()))
+}
+
+// octocrab 0.38 can list reviews but not submit them. `client` is the acting user's (BotState::octocrab_for).
+pub async fn create_review(state: &BotState, client: &octocrab::Octocrab, repo: &str, number: u64, event: &str, body: &str) -> Result<octocrab::models::pulls::Review, octocrab::Error> {
+    let route = format!("/repos/{}/{}/pulls/{}/reviews", state.repo_org(repo).await, repo_name(repo), number);
+    client.post(route, Some(&serde_json::json!({ "event": event, "body": body }))).await
+}
+
+// Adds an issue/PR (by node id) to a project and returns the item id. GitHub returns the
+// existing item when the content is already on the board.
+pub async fn add_project_item(state: &BotState, source: &str, project_id: &str, content_id: &str) -> Result<Option<String>, octocrab::Error> {
+    let mutation = serde_json::json!({
+        "query": r#"
+            mutation($projectId: ID!, $contentId: ID!) {
+                addProjectV2ItemById(input: { projectId: $projectId, contentId: $contentId }) { item { id } }
+            }
+        "#,
+        "variables": { "projectId": project_id, "contentId": content_id }
+    });
+    let resp = graphql(state, source, &mutation).await?;
+    Ok(resp.get("data").and_then(|d| d.get("addProjectV2ItemById")).and_then(|a| a.get("item"))
+        .and_then(|i| i.get("id")).and_then(|id| id.as_str()).map(|s| s.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graphql_errors_without_data() {
+        let resp = serde_json::json!({
+            "errors": [
+                { "type": "NOT_FOUND", "path": ["organization"], "message": "Could not resolve to an Organization with the login of 'acme'." },
+                { "message": "Your token has not been granted the required scopes." }
+            ]
+        });
+        assert_eq!(
+            graphql_errors(&resp).as_deref(),
+            Some("Could not resolve to an Organization with the login of 'acme'.; Your token has not been granted the required scopes.")
+        );
+    }
+
+    #[test]
+    fn graphql_errors_with_partial_data() {
+        let resp = serde_json::json!({
+            "data": { "organization": { "projectV2": null } },
+            "errors": [{ "type": "NOT_FOUND", "path": ["organization", "projectV2"] }]
+        });
+        assert_eq!(graphql_errors(&resp).as_deref(), Some("unknown error"));
+    }
+
+    #[test]
+    fn graphql_errors_none_when_clean() {
+        assert_eq!(graphql_errors(&serde_json::json!({ "data": { "viewer": { "login": "octocat" } } })), None);
+        assert_eq!(graphql_errors(&serde_json::json!({ "data": {}, "errors": [] })), None);
+        assert_eq!(graphql_errors(&serde_json::json!({ "data": {}, "errors": null })), None);
+    }
+
+    #[test]
+    fn rate_limited_graphql_errors() {
+        let resp = serde_json::json!({ "errors": [{ "type": "RATE_LIMITED", "message": "API rate limit exceeded" }] });
+        assert!(graphql_rate_limited(&resp));
+        assert_eq!(graphql_errors(&resp).as_deref(), Some("API rate limit exceeded"));
+    }
+
+    #[test]
+    fn rejected_field_update_is_a_failure() {
+        // What GitHub sends back (as a 200) for a text value written to a Number field
+        let resp = serde_json::json!({
+            "data": { "updateProjectV2ItemFieldValue": null },
+            "errors": [{
+                "type": "INVALID_ARGUMENTS",
+                "path": ["updateProjectV2ItemFieldValue"],
+                "message": "The value provided for the field is not a valid number."
+            }]
+        });
+        match reject_errors(resp) {
+            Err(BotError::GraphQl(msg)) => assert_eq!(msg, "The value provided for the field is not a valid number."),
+            other => panic!("expected a GraphQL error, got {:?}", other.map(|_| ()).map_err(|e| e.to_string())),
+        }
+        let ok = serde_json::json!({ "data": { "updateProjectV2ItemFieldValue": { "projectV2Item": { "id": "PVTI_1" } } } });
+        assert!(reject_errors(ok).is_ok());
+    }
+}
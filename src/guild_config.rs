@@ -0,0 +1,187 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use crate::types::{Context, Error, write_atomically};
+
+pub const DEFAULT_DUE_SOON_DAYS: u32 = 3;
+
+// Commands that change something on GitHub. Add new write commands here so the
+// "write" group can switch them off.
+pub const WRITE_COMMANDS: &[&str] = &[
+    "repo assign",
+    "repo unassign",
+    "pr merge",
+    "pr review",
+    "repo target",
+    "proj edit",
+    "proj bulk-move",
+    "repo triage",
+    "repo close",
+    "repo comment",
+    "repo create-issue",
+    "repo label-add",
+    "repo label-remove",
+    "repo reopen",
+    "repo reopen-issue",
+    "proj set-description",
+    "proj add-item",
+    "proj remove-item",
+    "proj create-draft",
+];
+
+// Top-level shortcuts (see commands::aliases) and the grouped command each one stands for,
+// so disabling a group also disables its shortcuts
+pub const ALIASES: &[(&str, &str)] = &[
+    ("issues", "repo issues"),
+    ("board", "proj view"),
+    ("item", "proj view-item"),
+];
+
+// The grouped name for a shortcut, anything else unchanged
+pub fn canonical_name(qualified_name: &str) -> &str {
+    ALIASES.iter().find(|(alias, _)| *alias == qualified_name).map(|(_, full)| *full).unwrap_or(qualified_name)
+}
+
+// Command groups a server can switch off
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq, Debug)]
+pub enum CommandGroup {
+    #[name = "repo"]
+    Repo,
+    #[name = "pr"]
+    Pr,
+    #[name = "proj"]
+    Proj,
+    #[name = "user"]
+    User,
+    #[name = "write"]
+    Write,
+}
+
+impl CommandGroup {
+    pub fn key(self) -> &'static str {
+        match self {
+            CommandGroup::Repo => "repo",
+            CommandGroup::Pr => "pr",
+            CommandGroup::Proj => "proj",
+            CommandGroup::User => "user",
+            CommandGroup::Write => "write",
+        }
+    }
+
+    // Whether a command, by qualified name ("repo assign"), belongs to the group stored as `key`
+    fn contains(key: &str, qualified_name: &str) -> bool {
+        match key {
+            "write" => WRITE_COMMANDS.contains(&qualified_name),
+            group => qualified_name.split(' ').next() == Some(group),
+        }
+    }
+}
+
+// A label family such as Priority = [P0, P1, P2]. An issue's value is the first of its
+// labels that appears in the list.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct LabelCategory {
+    pub name: String,
+    pub labels: Vec<String>,
+}
+
+impl LabelCategory {
+    pub fn value_of(&self, issue_labels: &[String]) -> Option<String> {
+        self.labels.iter()
+            .find(|l| issue_labels.iter().any(|il| il.eq_ignore_ascii_case(l)))
+            .cloned()
+    }
+
+    pub fn contains(&self, label: &str) -> bool {
+        self.labels.iter().any(|l| l.eq_ignore_ascii_case(label))
+    }
+}
+
+// Board that `/repo assign due:...` adds the issue to, and the Date field it sets there
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct DefaultBoard {
+    pub project: String, // Project title, as in the cache
+    pub date_field: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Default, Clone)]
+pub struct GuildConfig {
+    pub disabled_groups: HashSet<String>,
+    // Optional label taxonomy, see LabelCategory
+    #[serde(default)]
+    pub taxonomy: Vec<LabelCategory>,
+    // Lowercased project field name -> emoji shown before it in item embeds
+    #[serde(default)]
+    pub field_emoji: HashMap<String, String>,
+    #[serde(default)]
+    pub default_board: Option<DefaultBoard>,
+    // Days ahead that count as "due soon" in item details (DEFAULT_DUE_SOON_DAYS when unset)
+    #[serde(default)]
+    pub due_soon_days: Option<u32>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Default, Clone)]
+pub struct GuildConfigs {
+    // Guild ID -> Config
+    pub guilds: HashMap<u64, GuildConfig>,
+}
+
+impl GuildConfigs {
+    pub fn load(path: &Path) -> Self {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        write_atomically(path, &serde_json::to_string_pretty(self)?, false)
+    }
+
+    // Label taxonomy of a guild (empty outside guilds or when none is configured)
+    pub fn taxonomy(&self, guild_id: Option<u64>) -> Vec<LabelCategory> {
+        guild_id.and_then(|g| self.guilds.get(&g)).map(|c| c.taxonomy.clone()).unwrap_or_default()
+    }
+
+    pub fn field_emoji(&self, guild_id: Option<u64>) -> HashMap<String, String> {
+        guild_id.and_then(|g| self.guilds.get(&g)).map(|c| c.field_emoji.clone()).unwrap_or_default()
+    }
+
+    pub fn default_board(&self, guild_id: Option<u64>) -> Option<DefaultBoard> {
+        guild_id.and_then(|g| self.guilds.get(&g)).and_then(|c| c.default_board.clone())
+    }
+
+    pub fn due_soon_days(&self, guild_id: Option<u64>) -> i64 {
+        guild_id.and_then(|g| self.guilds.get(&g)).and_then(|c| c.due_soon_days).unwrap_or(DEFAULT_DUE_SOON_DAYS) as i64
+    }
+
+    // Name of the disabled group that blocks this command in this guild, if any
+    pub fn blocking_group(&self, guild_id: u64, qualified_name: &str) -> Option<String> {
+        let config = self.guilds.get(&guild_id)?;
+        let qualified_name = canonical_name(qualified_name);
+        config.disabled_groups.iter()
+            .find(|key| CommandGroup::contains(key, qualified_name))
+            .cloned()
+    }
+}
+
+// Framework-wide command check: refuses commands whose group is disabled in the current guild
+pub async fn command_check(ctx: Context<'_>) -> Result<bool, Error> {
+    let Some(guild_id) = ctx.guild_id() else { return Ok(true); };
+    let qualified_name = &ctx.command().qualified_name;
+    // Never lock admins out of the config command itself
+    if qualified_name.starts_with("config") {
+        return Ok(true);
+    }
+
+    let blocking = ctx.data().guild_configs.read().await.blocking_group(guild_id.get(), qualified_name);
+    match blocking {
+        Some(group) => {
+            ctx.send(poise::CreateReply::default()
+                .content(format!("🚫 This command is disabled here (group `{}`).", group))
+                .ephemeral(true)).await?;
+            Ok(false)
+        }
+        None => Ok(true),
+    }
+}
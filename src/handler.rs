@@ -1,7 +1,15 @@
 use poise::serenity_prelude as serenity;
+use poise::ChoiceParameter;
 use std::sync::Arc;
-use crate::types::{BotState, Error};
-use crate::utils::build_item_embed;
+use crate::types::{BotState, Error, StateFilter};
+use crate::utils::{build_item_embed, body_more_button, chunk_text, is_valid_login, is_valid_repo_name, repo_name, validate_field_value, MAX_TEXT_FIELD_LEN};
+use crate::audit::{record_edit, AuditEntry};
+use crate::github::{graphql_mutation, fetch_project_items, item_node_id, lock_item, current_field_value};
+use crate::custom_id::CustomId;
+use crate::stats::Api;
+use crate::oauth::{request_device_code, instructions_embed, complete_device_flow};
+use crate::presets::ViewPreset;
+use crate::commands::{apply_target, build_issue_list, build_project_view, build_user_workload, file_draft, file_issue, post_comment, DETAIL_ITEM_FIELDS};
 use serenity::all::{CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, CreateModal, CreateInputText};
 
 // Event Handler for Components
@@ -16,204 +24,159 @@ pub async fn event_handler(
             serenity::Interaction::Component(component) => {
                 let custom_id = &component.data.custom_id;
                 
-                // Format: proj_page_<title>_<page_num>
-                if custom_id.starts_with("proj_page_") {
-                    let parts: Vec<&str> = custom_id.split('_').collect();
-                    if parts.len() >= 4 {
-                        let title = parts[2];
-                        let page_num: usize = parts[3].parse().unwrap_or(1);
-                        
+                match CustomId::parse(custom_id) {
+                    Some(CustomId::ProjPage { number: proj_number, page: page_num }) => {
                         let _ = component.defer(ctx).await;
-                        
-                         let project_opt = {
-                            let projects = data.projects.read().await;
-                            projects.iter().find(|p| p.title.eq_ignore_ascii_case(title)).cloned()
+                        // Only used when the full view didn't fit in a custom_id, so this is the board's default view
+                        let project = data.projects.read().await.iter().find(|p| p.number == proj_number).map(|p| p.title.clone());
+                        let Some(project) = project else {
+                            let _ = component.create_followup(ctx, serenity::CreateInteractionResponseFollowup::new()
+                                .content("❌ This board is no longer in the cache. Try /refresh?").ephemeral(true)).await;
+                            return Ok(());
                         };
-
-                        if let Some(proj) = project_opt {
-                             let query = serde_json::json!({
-                                "query": r#"query($id: ID!) { node(id: $id) { ... on ProjectV2 { items(first: 100) { nodes { content { ... on Issue { title number url repository { name } state } ... on PullRequest { title number url repository { name } state } ... on DraftIssue { title } } } } } } }"#,
-                                "variables": { "id": proj.id }
-                            });
-                            
-                            if let Ok(resp) = data.octocrab.graphql::<serde_json::Value>(&query).await {
-                                 let json_resp: serde_json::Value = resp;
-                                 
-                                 // Redoing the list building logic (simplified)
-                                 let mut display_lines = Vec::new();
-                                 let mut menu_options = Vec::new();
-
-                                 if let Some(nodes) = json_resp.get("data").and_then(|d| d.get("node")).and_then(|d| d.get("items")).and_then(|d| d.get("nodes")).and_then(|d| d.as_array()) {
-                                     for item in nodes {
-                                         let content = item.get("content");
-                                          if let Some(issue) = content.and_then(|c| c.get("number")) {
-                                                let title = content.and_then(|c| c.get("title")).and_then(|t| t.as_str()).unwrap_or("?");
-                                                let repo = content.and_then(|c| c.get("repository")).and_then(|r| r.get("name")).and_then(|n| n.as_str()).unwrap_or("?");
-                                                let number = issue.as_i64().unwrap_or(0);
-                                                let url = content.and_then(|c| c.get("url")).and_then(|u| u.as_str()).unwrap_or("");
-                                                let state = content.and_then(|c| c.get("state")).and_then(|s| s.as_str()).unwrap_or("");
-                                                
-                                                let icon = match state {
-                                                    "OPEN" => "<:issue:1458877117176742065>",
-                                                    "CLOSED" => "<:issue_neutral:1458877524015579209>",
-                                                    "MERGED" => "<:pr_merged:1458877132414517360>",
-                                                    _ => "⚪",
-                                                };
-                                                display_lines.push(format!("{} **{}/[#{}]({})** {}", icon, repo, number, url, title));
-                                                menu_options.push((number, title.to_string(), repo.to_string()));
-                                          } else if let Some(draft) = content.and_then(|c| c.get("title")).and_then(|t| t.as_str()) {
-                                                display_lines.push(format!("<:issue_draft:1458883679777460434> **Draft:** {}", draft));
-                                          }
-                                     }
-                                 }
-                                 
-                                 let page_size = 20;
-                                 let total_items = display_lines.len();
-                                 let start_idx = (page_num - 1) * page_size;
-                                 let end_idx = std::cmp::min(start_idx + page_size, total_items);
-                                 
-                                 if start_idx < total_items {
-                                     let page_display = &display_lines[start_idx..end_idx];
-                                     let page_menu_opts = menu_options.iter().skip(start_idx).take(page_size);
-                                     
-                                     let embed = serenity::CreateEmbed::new()
-                                        .title(format!("Project: {}", proj.title))
-                                        .url(&proj.url)
-                                        .description(page_display.join("\n"))
-                                        .footer(serenity::CreateEmbedFooter::new(format!("Page {}/{} • Total: {}", page_num, (total_items + page_size - 1) / page_size, total_items)))
-                                        .color(0xEB459E);
-                                        
-                                     let mut components = Vec::new();
-
-                                     // Select Menu
-                                    let mut select_opts_vec = Vec::new();
-                                    for (num, title, repo) in page_menu_opts {
-                                        let label = format!("{} #{}: {}", repo, num, title);
-                                        let label = if label.len() > 95 { format!("{}...", &label[..95]) } else { label };
-                                        select_opts_vec.push(CreateSelectMenuOption::new(label, num.to_string()));
-                                    }
-                                    if !select_opts_vec.is_empty() {
-                                        let menu_id = format!("proj_select_{}", proj.id);
-                                        let menu = CreateSelectMenu::new(menu_id, CreateSelectMenuKind::String { options: select_opts_vec })
-                                            .placeholder("🔍 Select an item to view details...");
-                                        components.push(serenity::CreateActionRow::SelectMenu(menu));
-                                    }
-                                        
-                                     let mut buttons = Vec::new();
-                                    if page_num > 1 {
-                                        buttons.push(serenity::CreateButton::new(format!("proj_page_{}_{}", title, page_num - 1)).label("◀️ Prev").style(serenity::ButtonStyle::Secondary));
-                                    }
-                                    buttons.push(serenity::CreateButton::new_link(&proj.url).label("Open Board"));
-                                    if end_idx < total_items {
-                                         buttons.push(serenity::CreateButton::new(format!("proj_page_{}_{}", title, page_num + 1)).label("Next ▶️").style(serenity::ButtonStyle::Secondary));
-                                    }
-                                    components.push(serenity::CreateActionRow::Buttons(buttons));
-                                    
-                                    let _ = component.edit_response(ctx, serenity::EditInteractionResponse::new().embed(embed).components(components)).await;
-                                 }
+                        let view = ViewPreset { project, ..Default::default() };
+                        rerender_project_view(ctx, component, data, &view, page_num).await;
+                    }
+                    Some(CustomId::ProjRefresh { proj_id, page, filter, show_field, group_by, label }) => {
+                        let _ = component.defer(ctx).await;
+                        let project = data.projects.read().await.iter().find(|p| p.id == proj_id).map(|p| p.title.clone());
+                        let Some(project) = project else {
+                            let _ = component.create_followup(ctx, serenity::CreateInteractionResponseFollowup::new()
+                                .content("❌ This board is no longer in the cache. Try /refresh?").ephemeral(true)).await;
+                            return Ok(());
+                        };
+                        let view = ViewPreset { project, filter: Some(filter), show_field, group_by, label };
+                        rerender_project_view(ctx, component, data, &view, page).await;
+                    }
+                    Some(CustomId::IssuesPage { repo, page, state, assignee, label })
+                        if is_valid_repo_name(&repo) && page > 0 && assignee.as_deref().is_none_or(is_valid_login) =>
+                    {
+                        let Some(issue_state) = StateFilter::from_name(&state) else { return Ok(()); };
+                        let _ = component.defer(ctx).await;
+                        match build_issue_list(data, "component", &repo, assignee.as_deref(), label.as_deref(), issue_state, page).await {
+                            Ok((rendered, components)) => {
+                                // Stay in whichever form the original /repo issues reply used
+                                let edit = if component.message.embeds.is_empty() {
+                                    serenity::EditInteractionResponse::new().content(rendered.to_plain())
+                                } else {
+                                    serenity::EditInteractionResponse::new().embed(rendered.to_embed())
+                                };
+                                let _ = component.edit_response(ctx, edit.components(components)).await;
+                            }
+                            Err(msg) => {
+                                let _ = component.create_followup(ctx, serenity::CreateInteractionResponseFollowup::new().content(msg).ephemeral(true)).await;
                             }
                         }
                     }
-                } else if custom_id.starts_with("proj_select_") {
-                     let proj_id = custom_id.trim_start_matches("proj_select_");
+                    Some(CustomId::UserPage { login, page, state }) if is_valid_login(&login) && page > 0 => {
+                        let _ = component.defer(ctx).await;
+                        let (rendered, components) = build_user_workload(data, "component", &login, state, page).await;
+                        // Stay in whichever form the original /user view reply used
+                        let edit = if component.message.embeds.is_empty() {
+                            serenity::EditInteractionResponse::new().content(rendered.to_plain())
+                        } else {
+                            serenity::EditInteractionResponse::new().embed(rendered.to_embed())
+                        };
+                        let _ = component.edit_response(ctx, edit.components(components)).await;
+                    }
+                    Some(CustomId::RefreshIssues { repo }) if is_valid_repo_name(&repo) => {
+                        let _ = component.defer(ctx).await;
+                        match build_issue_list(data, "component", &repo, None, None, StateFilter::Open, 1).await {
+                            Ok((rendered, components)) => {
+                                // Stay in whichever form the original /repo issues reply used
+                                let edit = if component.message.embeds.is_empty() {
+                                    serenity::EditInteractionResponse::new().content(rendered.to_plain())
+                                } else {
+                                    serenity::EditInteractionResponse::new().embed(rendered.to_embed())
+                                };
+                                let _ = component.edit_response(ctx, edit.components(components)).await;
+                            }
+                            Err(msg) => {
+                                let _ = component.create_followup(ctx, serenity::CreateInteractionResponseFollowup::new().content(msg).ephemeral(true)).await;
+                            }
+                        }
+                    }
+                    Some(CustomId::ProjSelect { proj_id }) => {
+                         let proj_id = proj_id.as_str();
                      
-                     if let serenity::ComponentInteractionDataKind::StringSelect { values } = &component.data.kind {
-                         if let Some(val) = values.first() {
-                             let target_num: i64 = val.parse().unwrap_or(0);
-                             let _ = component.defer(ctx).await;
+                         if let serenity::ComponentInteractionDataKind::StringSelect { values } = &component.data.kind {
+                             if let Some(val) = values.first() {
+                                 let target_num: i64 = val.parse().unwrap_or(0);
+                                 let _ = component.defer(ctx).await;
                              
-                             let query = serde_json::json!({
-                                "query": r#"
-                                    query($id: ID!) {
-                                        node(id: $id) {
-                                            ... on ProjectV2 {
-                                                items(first: 100) {
-                                                    nodes {
-                                                        content {
-                                                            ... on Issue {
-                                                                title number body url repository { name } state assignees(first: 3) { nodes { login } } labels(first: 5) { nodes { name } }
-                                                            }
-                                                            ... on PullRequest {
-                                                                title number body url repository { name } state assignees(first: 3) { nodes { login } }
-                                                            }
-                                                        }
-                                                        fieldValues(first: 20) {
-                                                            nodes {
-                                                                ... on ProjectV2ItemFieldTextValue { text field { ... on ProjectV2FieldCommon { name } } }
-                                                                ... on ProjectV2ItemFieldDateValue { date field { ... on ProjectV2FieldCommon { name } } }
-                                                                ... on ProjectV2ItemFieldSingleSelectValue { name field { ... on ProjectV2FieldCommon { name } } }
-                                                                ... on ProjectV2ItemFieldNumberValue { number field { ... on ProjectV2FieldCommon { name } } }
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                "#,
-                                "variables": { "id": proj_id }
-                            });
-
-                            if let Ok(resp) = data.octocrab.graphql::<serde_json::Value>(&query).await {
-                                 let json_resp: serde_json::Value = resp;
-                                 if let Some(nodes) = json_resp.get("data").and_then(|d| d.get("node")).and_then(|d| d.get("items")).and_then(|d| d.get("nodes")).and_then(|d| d.as_array()) {
-                                     for item in nodes {
-                                         if let Some(embed) = build_item_embed(item, target_num) {
-                                             let components = vec![serenity::CreateActionRow::Buttons(vec![
-                                                serenity::CreateButton::new(format!("edit:item:{}:{}", proj_id, target_num))
+                                 let result = fetch_project_items(data, "component", proj_id, DETAIL_ITEM_FIELDS).await;
+                                 if let Err(e) = &result {
+                                     let _ = component.create_followup(ctx, serenity::CreateInteractionResponseFollowup::new().content(format!("❌ Failed to load the item: {}", e)).ephemeral(true)).await;
+                                 }
+                                 if let Ok(nodes) = result {
+                                     let (field_emoji, due_soon_days) = {
+                                         let configs = data.guild_configs.read().await;
+                                         let guild_id = component.guild_id.map(|g| g.get());
+                                         (configs.field_emoji(guild_id), configs.due_soon_days(guild_id))
+                                     };
+                                     let users = data.users.read().await.clone();
+                                     for item in &nodes {
+                                         if let Some(embed) = build_item_embed(item, target_num, &field_emoji, &users, due_soon_days) {
+                                             let mut buttons = vec![
+                                                serenity::CreateButton::new(CustomId::EditItem { proj_id: proj_id.to_string(), num: target_num }.to_string())
                                                     .label("✏️ Edit Item")
                                                     .style(serenity::ButtonStyle::Secondary)
-                                             ])];
+                                             ];
+                                             buttons.extend(body_more_button(item));
+                                             let components = vec![serenity::CreateActionRow::Buttons(buttons)];
                                              let _ = component.create_followup(ctx, serenity::CreateInteractionResponseFollowup::new().embed(embed).components(components).ephemeral(true)).await;
                                              return Ok(());
                                          }
                                      }
                                      let _ = component.create_followup(ctx, serenity::CreateInteractionResponseFollowup::new().content("❌ Item not found (it might have been moved).").ephemeral(true)).await;
-                                 }
-                            }
+                                }
+                             }
                          }
-                     }
-                } else if custom_id.starts_with("edit:item:") {
-                    // edit:item:{proj_id}:{num}
-                    let parts: Vec<&str> = custom_id.split(':').collect();
-                    if parts.len() >= 4 {
-                        let proj_id = parts[2];
-                        let target_num: i64 = parts[3].parse().unwrap_or(0);
+                    }
+                    Some(CustomId::EditItem { proj_id, num: target_num }) => {
+                        // The button flow is the component twin of `/proj edit`, so it honors the same toggle
+                        let blocked = match component.guild_id {
+                            Some(g) => data.guild_configs.read().await.blocking_group(g.get(), "proj edit"),
+                            None => None,
+                        };
+                        if let Some(group) = blocked {
+                            let _ = component.create_response(ctx, serenity::CreateInteractionResponse::Message(
+                                serenity::CreateInteractionResponseMessage::new().content(format!("🚫 Editing is disabled here (group `{}`).", group)).ephemeral(true)
+                            )).await;
+                        } else {
+                            let proj_id = proj_id.as_str();
                         
-                        let projects = data.projects.read().await;
-                        if let Some(proj) = projects.iter().find(|p| p.id == proj_id) {
-                             let mut options = Vec::new();
-                             for f in &proj.fields {
-                                 if f.name == "Title" || f.name == "Assignees" || f.name == "Labels" || f.name == "Repository" || f.name == "Milestone" || f.name == "Linked pull requests" { continue; }
-                                 let label = format!("{} ({})", f.name, f.data_type);
-                                 options.push(CreateSelectMenuOption::new(label, &f.id));
-                             }
+                            let projects = data.projects.read().await;
+                            if let Some(proj) = projects.iter().find(|p| p.id == proj_id) {
+                                 let mut options = Vec::new();
+                                 for f in &proj.fields {
+                                     if f.name == "Title" || f.name == "Assignees" || f.name == "Labels" || f.name == "Repository" || f.name == "Milestone" || f.name == "Linked pull requests" { continue; }
+                                     let label = format!("{} ({})", f.name, f.data_type);
+                                     options.push(CreateSelectMenuOption::new(label, &f.id));
+                                 }
                              
-                             if options.is_empty() {
-                                 let _ = component.create_response(ctx, serenity::CreateInteractionResponse::Message(
-                                     serenity::CreateInteractionResponseMessage::new().content("⚠️ No editable custom fields found.").ephemeral(true)
-                                 )).await;
-                             } else {
-                                 let menu_id = format!("field:sel:{}:{}", proj_id, target_num);
-                                 let menu = CreateSelectMenu::new(menu_id, CreateSelectMenuKind::String { options })
-                                     .placeholder("Select a field to edit...");
+                                 if options.is_empty() {
+                                     let _ = component.create_response(ctx, serenity::CreateInteractionResponse::Message(
+                                         serenity::CreateInteractionResponseMessage::new().content("⚠️ No editable custom fields found.").ephemeral(true)
+                                     )).await;
+                                 } else {
+                                     let menu_id = CustomId::FieldSelect { proj_id: proj_id.to_string(), num: target_num }.to_string();
+                                     let menu = CreateSelectMenu::new(menu_id, CreateSelectMenuKind::String { options })
+                                         .placeholder("Select a field to edit...");
                                  
-                                 let _ = component.create_response(ctx, serenity::CreateInteractionResponse::Message(
-                                     serenity::CreateInteractionResponseMessage::new()
-                                         .content(format!("Select a field to edit for Item #{}", target_num))
-                                         .components(vec![serenity::CreateActionRow::SelectMenu(menu)])
-                                         .ephemeral(true)
-                                 )).await;
-                             }
+                                     let _ = component.create_response(ctx, serenity::CreateInteractionResponse::Message(
+                                         serenity::CreateInteractionResponseMessage::new()
+                                             .content(format!("Select a field to edit for Item #{}", target_num))
+                                             .components(vec![serenity::CreateActionRow::SelectMenu(menu)])
+                                             .ephemeral(true)
+                                     )).await;
+                                 }
+                            }
                         }
                     }
-                } else if custom_id.starts_with("field:sel:") {
-                    // field:sel:{proj_id}:{num}
-                    let parts: Vec<&str> = custom_id.split(':').collect();
-                    if parts.len() >= 4 {
-                        let proj_id = parts[2];
-                        let target_num: i64 = parts[3].parse().unwrap_or(0);
-                        
+                    Some(CustomId::FieldSelect { proj_id, num: target_num }) => {
+                        let proj_id = proj_id.as_str();
+                    
                         if let serenity::ComponentInteractionDataKind::StringSelect { values } = &component.data.kind {
                             if let Some(field_id) = values.first() {
                                 let projects = data.projects.read().await;
@@ -223,21 +186,21 @@ pub async fn event_handler(
                                             "SINGLE_SELECT" | "ITERATION" | "STATUS" => {
                                                 let mut sorted_opts: Vec<_> = field.options.iter().collect();
                                                 sorted_opts.sort_by_key(|(name, _)| name.to_lowercase());
-                                                
+                                            
                                                 let mut options = Vec::new();
                                                 for (name, id) in sorted_opts {
                                                     options.push(CreateSelectMenuOption::new(name, id));
                                                 }
-                                                
+                                            
                                                 if options.is_empty() {
                                                      let _ = component.create_response(ctx, serenity::CreateInteractionResponse::Message(
                                                         serenity::CreateInteractionResponseMessage::new().content("❌ No options found.").ephemeral(true)
                                                     )).await;
                                                 } else {
-                                                    let menu_id = format!("val:sel:{}:{}:{}", proj_id, target_num, field_id);
+                                                    let menu_id = CustomId::ValueSelect { proj_id: proj_id.to_string(), num: target_num, field_id: field_id.clone() }.to_string();
                                                     let menu = CreateSelectMenu::new(menu_id, CreateSelectMenuKind::String { options })
                                                         .placeholder(format!("Select value for {}...", field.name));
-                                                    
+                                                
                                                     let _ = component.create_response(ctx, serenity::CreateInteractionResponse::UpdateMessage(
                                                         serenity::CreateInteractionResponseMessage::new()
                                                             .content(format!("Update **{}** for Item #{}", field.name, target_num))
@@ -246,13 +209,17 @@ pub async fn event_handler(
                                                 }
                                             },
                                             _ => {
-                                                let modal_id = format!("val:modal:{}:{}:{}", proj_id, target_num, field_id);
+                                                let modal_id = CustomId::ValueModal { proj_id: proj_id.to_string(), num: target_num, field_id: field_id.clone() }.to_string();
                                                 let input = CreateInputText::new(serenity::InputTextStyle::Short, "Value", "value")
-                                                    .placeholder(format!("Enter new {}...", field.data_type.to_lowercase()));
-                                                
+                                                    .placeholder(match field.data_type.as_str() {
+                                                        "DATE" => "YYYY-MM-DD or today".to_string(),
+                                                        other => format!("Enter new {}...", other.to_lowercase()),
+                                                    })
+                                                    .max_length(MAX_TEXT_FIELD_LEN as u16);
+                                            
                                                 let modal = CreateModal::new(modal_id, format!("Edit {} (#{})", field.name, target_num))
                                                     .components(vec![serenity::CreateActionRow::InputText(input)]);
-                                                
+                                            
                                                 let _ = component.create_response(ctx, serenity::CreateInteractionResponse::Modal(modal)).await;
                                             }
                                         }
@@ -261,157 +228,306 @@ pub async fn event_handler(
                             }
                         }
                     }
-                } else if custom_id.starts_with("val:sel:") {
-                    // val:sel:{proj_id}:{num}:{field_id}
-                    let parts: Vec<&str> = custom_id.split(':').collect();
-                    if parts.len() >= 5 {
-                        let proj_id = parts[2];
-                        let target_num: i64 = parts[3].parse().unwrap_or(0);
-                        let field_id = parts[4];
-                        
+                    Some(CustomId::ValueSelect { proj_id, num: target_num, field_id }) => {
+                        let proj_id = proj_id.as_str();
+                        let field_id = field_id.as_str();
+                    
                         if let serenity::ComponentInteractionDataKind::StringSelect { values } = &component.data.kind {
                              if let Some(opt_id) = values.first() {
                                  let _ = component.defer(ctx).await;
-                                 
-                                 // Fetch Item Node ID
-                                 let query = serde_json::json!({
-                                    "query": r#"query($id: ID!) { node(id: $id) { ... on ProjectV2 { items(first: 100) { nodes { id content { ... on Issue { number } ... on PullRequest { number } } } } } } }"#,
-                                    "variables": { "id": proj_id }
-                                });
-                                
-                                let mut item_node_id = String::new();
-                                if let Ok(resp) = data.octocrab.graphql::<serde_json::Value>(&query).await {
-                                     let json_resp: serde_json::Value = resp;
-                                     if let Some(nodes) = json_resp.get("data").and_then(|d| d.get("node")).and_then(|d| d.get("items")).and_then(|d| d.get("nodes")).and_then(|d| d.as_array()) {
-                                         for item in nodes {
-                                             if let Some(num) = item.get("content").and_then(|c| c.get("number")).and_then(|n| n.as_i64()) {
-                                                 if num == target_num {
-                                                     item_node_id = item.get("id").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                                                     break;
-                                                 }
-                                             }
-                                         }
+                             
+                                 // Item node id, from the cache when possible
+                                 let item_node_id = match item_node_id(data, "component", proj_id, target_num).await {
+                                     Ok(Some(id)) => id,
+                                     result => {
+                                         let _ = component.edit_response(ctx, serenity::EditInteractionResponse::new()
+                                             .content(item_lookup_failure(target_num, result.err())).components(vec![])).await;
+                                         return Ok(());
                                      }
-                                }
-                                
-                                if !item_node_id.is_empty() {
-                                    let mutation = serde_json::json!({
-                                        "query": r#"
-                                            mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $optionId: String!) {
-                                                updateProjectV2ItemFieldValue(input: {
-                                                    projectId: $projectId, itemId: $itemId, fieldId: $fieldId, value: { singleSelectOptionId: $optionId } 
-                                                }) { projectV2Item { id } }
-                                            }
-                                        "#,
-                                        "variables": { "projectId": proj_id, "itemId": item_node_id, "fieldId": field_id, "optionId": opt_id }
-                                    });
-                                    
-                                    if let Ok(_) = data.octocrab.graphql::<serde_json::Value>(&mutation).await {
-                                         let _ = component.edit_response(ctx, serenity::EditInteractionResponse::new().content("✅ Updated successfully!").components(vec![])).await;
-                                    } else {
-                                         let _ = component.edit_response(ctx, serenity::EditInteractionResponse::new().content("❌ Update failed.").components(vec![])).await;
+                                 };
+
+                                 // Readable names for the audit log
+                                 let (proj_title, field_name, opt_name) = {
+                                     let projects = data.projects.read().await;
+                                     let proj = projects.iter().find(|p| p.id == proj_id);
+                                     let field = proj.and_then(|p| p.fields.iter().find(|f| f.id == field_id));
+                                     (
+                                         proj.map(|p| p.title.clone()).unwrap_or_default(),
+                                         field.map(|f| f.name.clone()).unwrap_or_else(|| field_id.to_string()),
+                                         field.and_then(|f| f.options.iter().find(|(_, id)| *id == opt_id)).map(|(name, _)| name.clone()).unwrap_or_else(|| opt_id.clone()),
+                                     )
+                                 };
+
+                                let _item_guard = lock_item(data, &item_node_id).await;
+                                let old_value = current_field_value(data, "component", &item_node_id, &field_name).await;
+                                let mutation = serde_json::json!({
+                                    "query": r#"
+                                        mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $optionId: String!) {
+                                            updateProjectV2ItemFieldValue(input: {
+                                                projectId: $projectId, itemId: $itemId, fieldId: $fieldId, value: { singleSelectOptionId: $optionId } 
+                                            }) { projectV2Item { id } }
+                                        }
+                                    "#,
+                                    "variables": { "projectId": proj_id, "itemId": item_node_id, "fieldId": field_id, "optionId": opt_id }
+                                });
+                            
+                                match graphql_mutation(data, "component", &mutation).await {
+                                    Ok(_) => {
+                                        let github_user = data.user_mapping.read().await.map.get(&component.user.id.get()).cloned();
+                                        record_edit(&ctx.http, data, AuditEntry {
+                                            timestamp: chrono::Utc::now().timestamp(),
+                                            discord_id: component.user.id.get(),
+                                            github_user,
+                                            project_id: proj_id.to_string(),
+                                            project_title: proj_title,
+                                            item_number: target_num,
+                                            field: field_name,
+                                            old_value,
+                                            new_value: opt_name,
+                                        }).await;
+
+                                        let _ = component.edit_response(ctx, serenity::EditInteractionResponse::new().content("✅ Updated successfully!").components(vec![])).await;
+                                    }
+                                    Err(e) => {
+                                        let _ = component.edit_response(ctx, serenity::EditInteractionResponse::new().content(format!("❌ Update failed: {}", e)).components(vec![])).await;
                                     }
                                 }
                              }
                         }
                     }
-                } else if custom_id.starts_with("edit_item_") {
-                     let _ = component.create_response(ctx, serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content("⚠️ This button is outdated. Please run `/proj view` again.").ephemeral(true)
-                    )).await;
-                }
-            },
-            serenity::Interaction::Modal(modal) => {
-                let custom_id = &modal.data.custom_id;
-                 if custom_id.starts_with("val:modal:") {
-                    // val:modal:{proj_id}:{num}:{field_id}
-                    let parts: Vec<&str> = custom_id.split(':').collect();
-                     if parts.len() >= 5 {
-                        let proj_id = parts[2];
-                        let target_num: i64 = parts[3].parse().unwrap_or(0);
-                        let field_id = parts[4];
-                        
-                        let mut value_opt = None;
-                        for row in &modal.data.components {
-                            for comp in &row.components {
-                                if let serenity::all::ActionRowComponent::InputText(input) = comp {
-                                    value_opt = input.value.clone();
-                                    break;
+                    Some(CustomId::BodyMore { repo, num: number }) if is_valid_repo_name(&repo) => {
+                        let repo = repo.as_str();
+                        let _ = component.defer_ephemeral(ctx).await;
+
+                        data.api_stats.record("component", Api::Rest);
+                        match data.octocrab.issues(data.repo_org(repo).await, repo_name(repo)).get(number).await {
+                            Ok(issue) => {
+                                let body = issue.body.unwrap_or_default();
+                                for chunk in chunk_text(&body, 2000) {
+                                    let _ = component.create_followup(ctx, serenity::CreateInteractionResponseFollowup::new().content(chunk).ephemeral(true)).await;
                                 }
                             }
+                            Err(e) => {
+                                let _ = component.create_followup(ctx, serenity::CreateInteractionResponseFollowup::new().content(format!("❌ Failed to fetch body: {}", e)).ephemeral(true)).await;
+                            }
                         }
+                    }
+                    Some(CustomId::Connect) => {
+                        let discord_id = component.user.id.get();
+                        let linked = data.user_mapping.read().await.map.get(&discord_id).cloned();
+                        if let Some(gh_user) = linked {
+                            let _ = component.create_response(ctx, serenity::CreateInteractionResponse::Message(
+                                serenity::CreateInteractionResponseMessage::new()
+                                    .content(format!("✅ You are already connected as GitHub user **{}**.", gh_user))
+                                    .ephemeral(true)
+                            )).await;
+                            return Ok(());
+                        }
+
+                        let _ = component.defer_ephemeral(ctx).await;
+                        let code = match request_device_code(data).await {
+                            Ok(c) => c,
+                            Err(e) => {
+                                let _ = component.edit_response(ctx, serenity::EditInteractionResponse::new().content(format!("❌ {}", e))).await;
+                                return Ok(());
+                            }
+                        };
+                        let _ = component.edit_response(ctx, serenity::EditInteractionResponse::new().embed(instructions_embed(&code))).await;
 
-                        if let Some(value) = value_opt {
-                                     let _ = modal.defer(ctx).await;
-                                     
-                                     // Get data type
-                                     let mut data_type = "TEXT".to_string();
-                                     {
-                                         let projects = data.projects.read().await;
-                                         if let Some(p) = projects.iter().find(|p| p.id == proj_id) {
-                                             if let Some(f) = p.fields.iter().find(|f| f.id == field_id) {
-                                                 data_type = f.data_type.clone();
-                                             }
+                        // Polling takes minutes, don't hold up the event handler
+                        let (http, data, component) = (ctx.http.clone(), data.clone(), component.clone());
+                        tokio::spawn(async move {
+                            let message = match complete_device_flow(&data, discord_id, &code).await {
+                                Ok(outcome) => outcome.message(),
+                                Err(e) => format!("❌ **Error**: {}", e),
+                            };
+                            let _ = component.edit_response(&http, serenity::EditInteractionResponse::new().content(message).embeds(vec![])).await;
+                        });
+                    }
+                    Some(CustomId::TargetConfirm { repo, num, args }) => {
+                        if !is_invoker(component) {
+                            let _ = component.create_response(ctx, serenity::CreateInteractionResponse::Message(
+                                serenity::CreateInteractionResponseMessage::new()
+                                    .content("🚫 Only the person who ran `/repo target` can confirm it.").ephemeral(true)
+                            )).await;
+                            return Ok(());
+                        }
+                        if !is_valid_repo_name(&repo) {
+                            return Ok(());
+                        }
+                        let _ = component.defer(ctx).await;
+                        let embed = match apply_target(&ctx.http, data, component.guild_id.map(|g| g.get()), component.user.id.get(), &repo, num, &args).await {
+                            Ok(msg) => serenity::CreateEmbed::new().title("✅ Target Set").description(msg).color(0x57F287),
+                            Err(msg) => serenity::CreateEmbed::new().title("❌ Target Failed").description(msg).color(0xED4245),
+                        };
+                        let _ = component.edit_response(ctx, serenity::EditInteractionResponse::new().embed(embed).components(vec![])).await;
+                    }
+                    Some(CustomId::TargetCancel) => {
+                        if !is_invoker(component) {
+                            let _ = component.create_response(ctx, serenity::CreateInteractionResponse::Message(
+                                serenity::CreateInteractionResponseMessage::new()
+                                    .content("🚫 Only the person who ran `/repo target` can cancel it.").ephemeral(true)
+                            )).await;
+                            return Ok(());
+                        }
+                        let embed = serenity::CreateEmbed::new().title("Target Cancelled").description("❌ Cancelled, nothing was changed.").color(0x95A5A6);
+                        let _ = component.create_response(ctx, serenity::CreateInteractionResponse::UpdateMessage(
+                            serenity::CreateInteractionResponseMessage::new().embed(embed).components(vec![])
+                        )).await;
+                    }
+                    Some(CustomId::Outdated) => {
+                         let _ = component.create_response(ctx, serenity::CreateInteractionResponse::Message(
+                            serenity::CreateInteractionResponseMessage::new()
+                                .content("⚠️ This button is outdated. Please run `/proj view` again.").ephemeral(true)
+                        )).await;
+                    }
+                    _ => {}
+                }
+            },
+            serenity::Interaction::Modal(modal) => {
+                let custom_id = &modal.data.custom_id;
+                if let Some(CustomId::CommentModal { repo, num }) = CustomId::parse(custom_id) {
+                    if !is_valid_repo_name(&repo) {
+                        return Ok(());
+                    }
+                    let body = modal_text(modal).unwrap_or_default();
+                    if body.trim().is_empty() {
+                        let _ = modal.create_response(ctx, serenity::CreateInteractionResponse::Message(
+                            serenity::CreateInteractionResponseMessage::new().content("❌ The comment is empty, nothing was posted.").ephemeral(true)
+                        )).await;
+                        return Ok(());
+                    }
+                    let _ = modal.defer(ctx).await;
+                    let embed = post_comment(data, "component", modal.user.id.get(), &repo, num, &body).await;
+                    let _ = modal.edit_response(ctx, serenity::EditInteractionResponse::new().embed(embed)).await;
+                    return Ok(());
+                }
+                if let Some(CustomId::CreateDraft { proj_id, status }) = CustomId::parse(custom_id) {
+                    let title = modal_input(modal, "title").unwrap_or_default();
+                    if title.trim().is_empty() {
+                        let _ = modal.create_response(ctx, serenity::CreateInteractionResponse::Message(
+                            serenity::CreateInteractionResponseMessage::new().content("❌ The draft needs a title, nothing was created.").ephemeral(true)
+                        )).await;
+                        return Ok(());
+                    }
+                    let body = modal_input(modal, "body").unwrap_or_default();
+                    let _ = modal.defer(ctx).await;
+                    let embed = file_draft(&ctx.http, data, modal.user.id.get(), &proj_id, status.as_deref(), title.trim(), &body).await;
+                    let _ = modal.edit_response(ctx, serenity::EditInteractionResponse::new().embed(embed)).await;
+                    return Ok(());
+                }
+                if let Some(CustomId::CreateIssue { repo }) = CustomId::parse(custom_id) {
+                    if !is_valid_repo_name(&repo) {
+                        return Ok(());
+                    }
+                    let title = modal_input(modal, "title").unwrap_or_default();
+                    if title.trim().is_empty() {
+                        let _ = modal.create_response(ctx, serenity::CreateInteractionResponse::Message(
+                            serenity::CreateInteractionResponseMessage::new().content("❌ The issue needs a title, nothing was filed.").ephemeral(true)
+                        )).await;
+                        return Ok(());
+                    }
+                    let body = modal_input(modal, "body").unwrap_or_default();
+                    let _ = modal.defer(ctx).await;
+                    let assignee = data.user_mapping.read().await.map.get(&modal.user.id.get()).cloned();
+                    let embed = file_issue(data, modal.user.id.get(), &repo, title.trim(), &body, assignee).await;
+                    let _ = modal.edit_response(ctx, serenity::EditInteractionResponse::new().embed(embed)).await;
+                    return Ok(());
+                }
+                 if let Some(CustomId::ValueModal { proj_id, num: target_num, field_id }) = CustomId::parse(custom_id) {
+                    let proj_id = proj_id.as_str();
+                    let field_id = field_id.as_str();
+                    
+                    if let Some(value) = modal_text(modal) {
+                                 // Get data type (and names for the audit log)
+                                 let mut data_type = "TEXT".to_string();
+                                 let mut proj_title = String::new();
+                                 let mut field_name = field_id.to_string();
+                                 {
+                                     let projects = data.projects.read().await;
+                                     if let Some(p) = projects.iter().find(|p| p.id == proj_id) {
+                                         proj_title = p.title.clone();
+                                         if let Some(f) = p.fields.iter().find(|f| f.id == field_id) {
+                                             data_type = f.data_type.clone();
+                                             field_name = f.name.clone();
                                          }
                                      }
+                                 }
 
-                                    // Fetch Item Node ID
-                                    let query = serde_json::json!({
-                                        "query": r#"query($id: ID!) { node(id: $id) { ... on ProjectV2 { items(first: 100) { nodes { id content { ... on Issue { number } ... on PullRequest { number } } } } } } }"#,
-                                        "variables": { "id": proj_id }
-                                    });
-                                    
-                                    let mut item_node_id = String::new();
-                                    if let Ok(resp) = data.octocrab.graphql::<serde_json::Value>(&query).await {
-                                         let json_resp: serde_json::Value = resp;
-                                         if let Some(nodes) = json_resp.get("data").and_then(|d| d.get("node")).and_then(|d| d.get("items")).and_then(|d| d.get("nodes")).and_then(|d| d.as_array()) {
-                                             for item in nodes {
-                                                 if let Some(num) = item.get("content").and_then(|c| c.get("number")).and_then(|n| n.as_i64()) {
-                                                     if num == target_num {
-                                                         item_node_id = item.get("id").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                                                         break;
-                                                     }
-                                                 }
-                                             }
-                                         }
+                                 // Same checks as /proj edit; reject before touching GitHub
+                                 let value = match validate_field_value(&data_type, &value) {
+                                     Ok(v) => v,
+                                     Err(msg) => {
+                                         let _ = modal.create_response(ctx, serenity::CreateInteractionResponse::Message(
+                                             serenity::CreateInteractionResponseMessage::new()
+                                                 .content(format!("❌ {} Nothing was changed.", msg))
+                                                 .ephemeral(true)
+                                         )).await;
+                                         return Ok(());
+                                     }
+                                 };
+                                 let _ = modal.defer(ctx).await;
+
+                                // Item node id, from the cache when possible
+                                let item_node_id = match item_node_id(data, "component", proj_id, target_num).await {
+                                    Ok(Some(id)) => id,
+                                    result => {
+                                        let _ = modal.edit_response(ctx, serenity::EditInteractionResponse::new()
+                                            .content(item_lookup_failure(target_num, result.err())).components(vec![])).await;
+                                        return Ok(());
                                     }
-                                    
-                                    if !item_node_id.is_empty() {
-                                        let mutation = match data_type.as_str() {
-                                            "NUMBER" => {
-                                                let num_val = value.parse::<f64>().unwrap_or(0.0);
-                                                serde_json::json!({
-                                                    "query": r#"mutation($p: ID!, $i: ID!, $f: ID!, $v: Float!) { updateProjectV2ItemFieldValue(input: { projectId: $p, itemId: $i, fieldId: $f, value: { number: $v } }) { projectV2Item { id } } }"#,
-                                                    "variables": { "p": proj_id, "i": item_node_id, "f": field_id, "v": num_val }
-                                                })
-                                            },
-                                            "DATE" => {
-                                                let date_val = if value.eq_ignore_ascii_case("Today") {
-                                                    chrono::Utc::now().format("%Y-%m-%d").to_string()
-                                                } else {
-                                                    value.clone()
-                                                };
-                                                 serde_json::json!({
-                                                    "query": r#"mutation($p: ID!, $i: ID!, $f: ID!, $v: Date!) { updateProjectV2ItemFieldValue(input: { projectId: $p, itemId: $i, fieldId: $f, value: { date: $v } }) { projectV2Item { id } } }"#,
-                                                    "variables": { "p": proj_id, "i": item_node_id, "f": field_id, "v": date_val }
-                                                })
-                                            },
-                                            _ => {
-                                                serde_json::json!({
-                                                    "query": r#"mutation($p: ID!, $i: ID!, $f: ID!, $v: String!) { updateProjectV2ItemFieldValue(input: { projectId: $p, itemId: $i, fieldId: $f, value: { text: $v } }) { projectV2Item { id } } }"#,
-                                                    "variables": { "p": proj_id, "i": item_node_id, "f": field_id, "v": value }
-                                                })
-                                            }
-                                        };
+                                };
 
-                                        if let Ok(_) = data.octocrab.graphql::<serde_json::Value>(&mutation).await {
-                                             let _ = modal.edit_response(ctx, serenity::EditInteractionResponse::new().content(format!("✅ Updated to: {}", value)).components(vec![])).await;
+                                let _item_guard = lock_item(data, &item_node_id).await;
+                                let old_value = current_field_value(data, "component", &item_node_id, &field_name).await;
+                                let mutation = match data_type.as_str() {
+                                    "NUMBER" => {
+                                        let num_val = value.parse::<f64>().unwrap_or(0.0);
+                                        serde_json::json!({
+                                            "query": r#"mutation($p: ID!, $i: ID!, $f: ID!, $v: Float!) { updateProjectV2ItemFieldValue(input: { projectId: $p, itemId: $i, fieldId: $f, value: { number: $v } }) { projectV2Item { id } } }"#,
+                                            "variables": { "p": proj_id, "i": item_node_id, "f": field_id, "v": num_val }
+                                        })
+                                    },
+                                    "DATE" => {
+                                        let date_val = if value.eq_ignore_ascii_case("Today") {
+                                            chrono::Utc::now().format("%Y-%m-%d").to_string()
                                         } else {
-                                             let _ = modal.edit_response(ctx, serenity::EditInteractionResponse::new().content("❌ Update failed.").components(vec![])).await;
-                                        }
+                                            value.clone()
+                                        };
+                                         serde_json::json!({
+                                            "query": r#"mutation($p: ID!, $i: ID!, $f: ID!, $v: Date!) { updateProjectV2ItemFieldValue(input: { projectId: $p, itemId: $i, fieldId: $f, value: { date: $v } }) { projectV2Item { id } } }"#,
+                                            "variables": { "p": proj_id, "i": item_node_id, "f": field_id, "v": date_val }
+                                        })
+                                    },
+                                    _ => {
+                                        serde_json::json!({
+                                            "query": r#"mutation($p: ID!, $i: ID!, $f: ID!, $v: String!) { updateProjectV2ItemFieldValue(input: { projectId: $p, itemId: $i, fieldId: $f, value: { text: $v } }) { projectV2Item { id } } }"#,
+                                            "variables": { "p": proj_id, "i": item_node_id, "f": field_id, "v": value }
+                                        })
                                     }
-                        }
+                                };
+
+                                match graphql_mutation(data, "component", &mutation).await {
+                                    Ok(_) => {
+                                         let github_user = data.user_mapping.read().await.map.get(&modal.user.id.get()).cloned();
+                                         record_edit(&ctx.http, data, AuditEntry {
+                                             timestamp: chrono::Utc::now().timestamp(),
+                                             discord_id: modal.user.id.get(),
+                                             github_user,
+                                             project_id: proj_id.to_string(),
+                                             project_title: proj_title,
+                                             item_number: target_num,
+                                             field: field_name,
+                                             old_value,
+                                             new_value: value.clone(),
+                                         }).await;
+
+                                         let _ = modal.edit_response(ctx, serenity::EditInteractionResponse::new().content(format!("✅ Updated to: {}", value)).components(vec![])).await;
+                                    }
+                                    Err(e) => {
+                                         let _ = modal.edit_response(ctx, serenity::EditInteractionResponse::new().content(format!("❌ Update failed: {}", e)).components(vec![])).await;
+                                    }
+                                }
                     }
                  }
              },
@@ -420,3 +536,59 @@ pub async fn event_handler(
     }
     Ok(())
 }
+
+// Reply for a board edit whose item couldn't be found: gone from the board (None) or the lookup failed
+fn item_lookup_failure(number: i64, err: Option<Error>) -> String {
+    match err {
+        Some(e) => format!("❌ Couldn't look up item #{}: {}", number, e),
+        None => format!("❌ Item #{} is no longer on the board, try /refresh", number),
+    }
+}
+
+// Value of the text input with this custom_id in a submitted modal
+fn modal_input(modal: &serenity::ModalInteraction, input_id: &str) -> Option<String> {
+    modal.data.components.iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|comp| match comp {
+            serenity::all::ActionRowComponent::InputText(input) if input.custom_id == input_id => Some(input.value.clone()),
+            _ => None,
+        })
+        .flatten()
+}
+
+// Value of the first text input in a submitted modal
+fn modal_text(modal: &serenity::ModalInteraction) -> Option<String> {
+    modal.data.components.iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|comp| match comp {
+            serenity::all::ActionRowComponent::InputText(input) => Some(input.value.clone()),
+            _ => None,
+        })
+        .flatten()
+}
+
+// Whether the user clicking a component is the one who ran the slash command that posted it
+fn is_invoker(component: &serenity::ComponentInteraction) -> bool {
+    match component.message.interaction_metadata.as_deref() {
+        Some(serenity::MessageInteractionMetadata::Command(meta)) => meta.user.id == component.user.id,
+        _ => false,
+    }
+}
+
+// Edits a /proj view message to show `page` of `view` (Prev/Next/Refresh buttons)
+async fn rerender_project_view(ctx: &serenity::Context, component: &serenity::ComponentInteraction, data: &BotState, view: &ViewPreset, page: usize) {
+    match build_project_view(data, "component", view, page).await {
+        Ok((rendered, components)) => {
+            // Stay in whichever form the original /proj view reply used
+            let edit = if component.message.embeds.is_empty() {
+                serenity::EditInteractionResponse::new().content(rendered.to_plain())
+            } else {
+                serenity::EditInteractionResponse::new().embed(rendered.to_embed())
+            };
+            let _ = component.edit_response(ctx, edit.components(components)).await;
+        }
+        Err(msg) => {
+            let _ = component.create_followup(ctx, serenity::CreateInteractionResponseFollowup::new().content(msg).ephemeral(true)).await;
+        }
+    }
+}
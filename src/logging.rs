@@ -0,0 +1,73 @@
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
+use tracing::Instrument;
+use tracing_subscriber::EnvFilter;
+use crate::types::{BotState, Error};
+
+type Command = poise::Command<Arc<BotState>, Error>;
+type SlashAction = for<'a> fn(poise::ApplicationContext<'a, Arc<BotState>, Error>) -> poise::BoxFuture<'a, Result<(), poise::FrameworkError<'a, Arc<BotState>, Error>>>;
+
+// Log lines go to stdout, filtered by RUST_LOG (default `info`, e.g. RUST_LOG=usthingy=debug)
+pub fn init() {
+    // An empty RUST_LOG (as in .env.example) counts as unset
+    let directives = std::env::var("RUST_LOG").ok().filter(|s| !s.trim().is_empty()).unwrap_or_else(|| "info".to_string());
+    let filter = EnvFilter::try_new(&directives).unwrap_or_else(|_| EnvFilter::new("info"));
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+}
+
+// Runs every slash command (subcommands included) inside a `command` span with its name, the
+// invoking user and guild. `org` is filled in once the command resolves a repo's org.
+// The original action is parked in `custom_data`, which the commands don't use otherwise.
+pub fn instrument_commands(commands: &mut [Command]) {
+    for command in commands {
+        instrument_commands(&mut command.subcommands);
+        if let Some(action) = command.slash_action.take() {
+            command.custom_data = Box::new(action as SlashAction);
+            command.slash_action = Some(traced_action);
+        }
+    }
+}
+
+fn traced_action(ctx: poise::ApplicationContext<'_, Arc<BotState>, Error>) -> poise::BoxFuture<'_, Result<(), poise::FrameworkError<'_, Arc<BotState>, Error>>> {
+    let span = tracing::info_span!(
+        "command",
+        name = %ctx.command.qualified_name,
+        user = ctx.interaction.user.id.get(),
+        guild = ?ctx.interaction.guild_id.map(|g| g.get()),
+        org = tracing::field::Empty,
+    );
+    let action = ctx.command.custom_data.downcast_ref::<SlashAction>().copied();
+    Box::pin(async move {
+        match action {
+            Some(action) => action(ctx).await,
+            None => Ok(()),
+        }
+    }.instrument(span))
+}
+
+// Span for a button/select/modal interaction, the component twin of the command span
+pub fn event_span(event: &serenity::FullEvent) -> tracing::Span {
+    let serenity::FullEvent::InteractionCreate { interaction } = event else { return tracing::Span::none(); };
+    let (custom_id, user) = match interaction {
+        serenity::Interaction::Component(c) => (c.data.custom_id.as_str(), c.user.id.get()),
+        serenity::Interaction::Modal(m) => (m.data.custom_id.as_str(), m.user.id.get()),
+        _ => return tracing::Span::none(),
+    };
+    tracing::info_span!("component", custom_id = %custom_id, user, org = tracing::field::Empty)
+}
+
+// GraphQL variables for a log line, with anything that looks like a credential masked
+pub fn loggable_variables(query: &serde_json::Value) -> String {
+    let Some(vars) = query.get("variables").and_then(|v| v.as_object()) else { return "{}".to_string(); };
+    let masked: serde_json::Map<String, serde_json::Value> = vars.iter()
+        .map(|(k, v)| {
+            let key = k.to_lowercase();
+            if key.contains("token") || key.contains("secret") || key.contains("password") {
+                (k.clone(), serde_json::Value::String("***".to_string()))
+            } else {
+                (k.clone(), v.clone())
+            }
+        })
+        .collect();
+    serde_json::Value::Object(masked).to_string()
+}
@@ -4,27 +4,97 @@ mod cache;
 mod autocomplete;
 mod commands;
 mod handler;
+mod custom_id;
+mod audit;
+mod webhook;
+mod github;
+mod guild_config;
+mod stats;
+mod presets;
+mod oauth;
+mod render;
+mod logging;
+mod metrics;
 
 use poise::serenity_prelude as serenity;
 use dotenv::dotenv;
 use std::env;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use octocrab::Octocrab;
 use reqwest::Client as HttpClient;
-use crate::types::{BotState, UserMapping};
-use crate::cache::refresh_cache;
-use crate::commands::{repo, proj, user, refresh};
+use crate::types::{BotState, UserMapping, Snoozes};
+use crate::guild_config::{GuildConfigs, command_check};
+use crate::cache::{refresh_cache, CacheSnapshot};
+use crate::github::check_token;
+use crate::commands::{repo, pr, proj, user, whoami, me, config, refresh, status, stats, eval_graphql, aliases};
 use crate::handler::event_handler;
+use crate::utils::is_valid_login;
+use crate::stats::{ApiStats, CommandCounts};
+use crate::presets::Presets;
+use tracing::{error, info, warn, Instrument};
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
+    logging::init();
     
     let discord_token = env::var("DISCORD_TOKEN").expect("missing DISCORD_TOKEN");
     let github_token = env::var("GITHUB_TOKEN").expect("missing GITHUB_TOKEN");
-    let github_org = env::var("GITHUB_ORG").expect("missing GITHUB_ORG");
+    // Comma-separated; GITHUB_ORG still works for a single org
+    let github_orgs: Vec<String> = env::var("GITHUB_ORGS").ok().filter(|s| !s.trim().is_empty())
+        .or_else(|| env::var("GITHUB_ORG").ok())
+        .expect("missing GITHUB_ORGS (or GITHUB_ORG)")
+        .split(',')
+        .map(|o| o.trim().to_string())
+        .filter(|o| !o.is_empty())
+        .fold(Vec::new(), |mut orgs, o| {
+            if !orgs.iter().any(|x: &String| x.eq_ignore_ascii_case(&o)) { orgs.push(o); }
+            orgs
+        });
     let github_client_id = env::var("GITHUB_CLIENT_ID").expect("missing GITHUB_CLIENT_ID (OAuth App)");
+    let github_client_secret = env::var("GITHUB_CLIENT_SECRET").ok().filter(|s| !s.is_empty());
+    let user_mapping_path = std::path::PathBuf::from(env::var("USER_MAPPING_PATH").ok()
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| "user_mapping.json".to_string()));
+    let snoozes_path = std::path::PathBuf::from(env::var("SNOOZES_PATH").ok()
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| "snoozes.json".to_string()));
+    let guild_config_path = std::path::PathBuf::from(env::var("GUILD_CONFIG_PATH").ok()
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| "guild_config.json".to_string()));
+    let presets_path = std::path::PathBuf::from(env::var("PRESETS_PATH").ok()
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| "presets.json".to_string()));
+    let audit_channel = env::var("AUDIT_CHANNEL_ID").ok()
+        .filter(|id| !id.is_empty())
+        .map(|id| serenity::ChannelId::new(id.parse().expect("AUDIT_CHANNEL_ID must be a channel ID")));
+    let activity_channel = env::var("ACTIVITY_CHANNEL_ID").ok()
+        .filter(|id| !id.is_empty())
+        .map(|id| serenity::ChannelId::new(id.parse().expect("ACTIVITY_CHANNEL_ID must be a channel ID")));
+    let webhook_secret = env::var("WEBHOOK_SECRET").ok().filter(|s| !s.is_empty());
+    let webhook_addr: Option<std::net::SocketAddr> = env::var("WEBHOOK_ADDR").ok()
+        .filter(|a| !a.is_empty())
+        .map(|a| a.parse().expect("WEBHOOK_ADDR must be host:port"));
+    let metrics_addr: Option<std::net::SocketAddr> = env::var("METRICS_ADDR").ok()
+        .filter(|a| !a.is_empty())
+        .map(|a| a.parse().expect("METRICS_ADDR must be host:port"));
+    // 0 turns the periodic refresh off
+    let cache_refresh_secs: u64 = env::var("CACHE_REFRESH_SECS").ok()
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().expect("CACHE_REFRESH_SECS must be a number of seconds"))
+        .unwrap_or(600);
+    // Guild-scoped registration is instant, global registration can take up to an hour
+    let dev_guild = env::var("DEV_GUILD_ID").ok()
+        .filter(|id| !id.is_empty())
+        .map(|id| serenity::GuildId::new(id.parse().expect("DEV_GUILD_ID must be a guild ID")));
+    assert!(!github_orgs.is_empty(), "GITHUB_ORGS lists no orgs");
+    for org in &github_orgs {
+        assert!(is_valid_login(org), "invalid org in GITHUB_ORGS: {:?}", org);
+    }
 
     let octocrab = Octocrab::builder()
         .personal_token(github_token)
@@ -33,46 +103,170 @@ async fn main() {
 
     let http_client = HttpClient::new();
 
+    // Last run's cache, replaced by the startup refresh below
+    let snapshot = CacheSnapshot::load();
+
     // Initialize state
     let state = Arc::new(BotState {
         octocrab,
         http_client,
-        github_org: github_org.clone(),
+        github_orgs,
         github_client_id,
-        repos: RwLock::new(Vec::new()),
-        users: RwLock::new(Vec::new()),
-        projects: RwLock::new(Vec::new()),
-        user_mapping: RwLock::new(UserMapping::load()),
+        github_client_secret,
+        repos_loaded: AtomicBool::new(!snapshot.repos.is_empty()),
+        projects_loaded: AtomicBool::new(!snapshot.projects.is_empty()),
+        repos: RwLock::new(snapshot.repos),
+        users: RwLock::new(snapshot.users),
+        teams: RwLock::new(Vec::new()),
+        projects: RwLock::new(snapshot.projects),
+        issues: RwLock::new(HashMap::new()),
+        labels: RwLock::new(HashMap::new()),
+        branches: RwLock::new(HashMap::new()),
+        discussion_categories: RwLock::new(HashMap::new()),
+        user_mapping: RwLock::new(UserMapping::load(&user_mapping_path)),
+        user_mapping_path,
+        snoozes: RwLock::new(Snoozes::load(&snoozes_path)),
+        snoozes_path,
+        presets: RwLock::new(Presets::load(&presets_path)),
+        presets_path,
+        guild_configs: RwLock::new(GuildConfigs::load(&guild_config_path)),
+        guild_config_path,
+        audit_channel,
+        webhook_secret,
+        activity_channel,
+        api_stats: ApiStats::default(),
+        command_counts: CommandCounts::default(),
+        ready: AtomicBool::new(false),
+        last_refreshed: RwLock::new(HashMap::new()),
+        item_locks: std::sync::Mutex::new(HashMap::new()),
+        github_permits: tokio::sync::Semaphore::new(github::MAX_CONCURRENT_REQUESTS),
     });
 
-    // Initial cache population (don't block main too long, spawn it)
+    // Token self-check and initial cache population (don't block main too long, spawn it)
     let state_clone = state.clone();
     tokio::spawn(async move {
+        check_token(&state_clone).await;
         if let Err(e) = refresh_cache(&state_clone).await {
-            eprintln!("Failed initial cache refresh: {}", e);
+            error!("Failed initial cache refresh: {}", e);
         }
     });
 
-    let framework = poise::Framework::builder()
-        .options(poise::FrameworkOptions {
-            commands: vec![repo(), proj(), user(), refresh()],
-            event_handler: |ctx, event, framework, data| {
-                Box::pin(event_handler(ctx, event, framework, data))
-            },
-            ..Default::default()
-        })
-        .setup(|ctx, _ready, framework| {
-            Box::pin(async move {
-                poise::builtins::register_globally(ctx, &framework.options().commands).await?;
-                println!("Bot registered globally!");
-                Ok(state)
+    // Periodic cache refresh. Each run gets its own task so an error or panic only costs that run.
+    if cache_refresh_secs > 0 {
+        let state_clone = state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(cache_refresh_secs));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval.tick().await; // The first tick is immediate, the startup refresh covers it
+            loop {
+                interval.tick().await;
+                let state = state_clone.clone();
+                let started = Instant::now();
+                match tokio::spawn(async move { refresh_cache(&state).await.map_err(|e| e.to_string()) }).await {
+                    Ok(Ok(_)) => info!("🔄 Periodic cache refresh done in {}ms", started.elapsed().as_millis()),
+                    Ok(Err(e)) => warn!("⚠️ Periodic cache refresh failed: {}", e),
+                    Err(e) => error!("⚠️ Periodic cache refresh panicked: {}", e),
+                }
+            }
+        });
+    }
+
+    // GitHub webhook receiver (project board activity feed)
+    if let Some(addr) = webhook_addr {
+        if state.webhook_secret.is_some() {
+            let http = Arc::new(serenity::Http::new(&discord_token));
+            tokio::spawn(webhook::serve(addr, state.clone(), http));
+        } else {
+            error!("⚠️ WEBHOOK_ADDR is set but WEBHOOK_SECRET is not, the webhook endpoint is not started");
+        }
+    }
+
+    // Liveness and Prometheus metrics for operators
+    if let Some(addr) = metrics_addr {
+        tokio::spawn(metrics::serve(addr, state.clone()));
+    }
+
+    // Serenity reconnects dropped shards on its own; this loop only catches errors that escape
+    // `start()` and rebuilds the client with backoff. Auth/intent errors exit immediately.
+    let mut backoff = Duration::from_secs(5);
+    loop {
+        let ready_state = state.clone();
+        let state = state.clone();
+        let framework = poise::Framework::builder()
+            .options(poise::FrameworkOptions {
+                commands: {
+                    let mut commands = vec![repo(), pr(), proj(), user(), whoami(), me(), config(), refresh(), status(), stats(), eval_graphql()];
+                    commands.extend(aliases());
+                    logging::instrument_commands(&mut commands);
+                    commands
+                },
+                command_check: Some(|ctx| Box::pin(command_check(ctx))),
+                on_error: |error| Box::pin(utils::on_error(error)),
+                pre_command: |ctx| Box::pin(async move {
+                    ctx.data().command_counts.record(&ctx.command().qualified_name);
+                }),
+                event_handler: |ctx, event, framework, data| {
+                    Box::pin(event_handler(ctx, event, framework, data).instrument(logging::event_span(event)))
+                },
+                ..Default::default()
             })
-        })
-        .build();
+            .setup(move |ctx, _ready, framework| {
+                Box::pin(async move {
+                    match dev_guild {
+                        Some(guild_id) => {
+                            poise::builtins::register_in_guild(ctx, &framework.options().commands, guild_id).await?;
+                            info!("Bot registered in dev guild {}!", guild_id);
+                        }
+                        None => {
+                            poise::builtins::register_globally(ctx, &framework.options().commands).await?;
+                            info!("Bot registered globally!");
+                        }
+                    }
+                    state.ready.store(true, Ordering::Relaxed);
+                    Ok(state)
+                })
+            })
+            .build();
+
+        let started_at = Instant::now();
+        let result = match serenity::ClientBuilder::new(&discord_token, serenity::GatewayIntents::non_privileged())
+            .framework(framework)
+            .await
+        {
+            Ok(mut client) => client.start().await,
+            Err(e) => Err(e),
+        };
 
-    let client = serenity::ClientBuilder::new(discord_token, serenity::GatewayIntents::non_privileged())
-        .framework(framework)
-        .await;
+        // Not ready again until the next client's setup runs
+        ready_state.ready.store(false, Ordering::Relaxed);
+        match result {
+            Ok(()) => {
+                info!("Gateway connection closed, shutting down.");
+                break;
+            }
+            Err(e) if is_fatal_gateway_error(&e) => {
+                error!("❌ Fatal gateway error, not reconnecting: {}", e);
+                std::process::exit(1);
+            }
+            Err(e) => {
+                // A long healthy run means this is a fresh failure, not a crash loop
+                if started_at.elapsed() > Duration::from_secs(300) {
+                    backoff = Duration::from_secs(5);
+                }
+                warn!("⚠️ Gateway error: {}. Reconnecting in {}s...", e, backoff.as_secs());
+                tokio::time::sleep(backoff).await;
+                backoff = std::cmp::min(backoff * 2, Duration::from_secs(300));
+            }
+        }
+    }
+}
 
-    client.unwrap().start().await.unwrap();
+// Errors that retrying cannot fix (bad token or intents)
+fn is_fatal_gateway_error(err: &serenity::Error) -> bool {
+    matches!(err, serenity::Error::Gateway(
+        serenity::GatewayError::InvalidAuthentication
+            | serenity::GatewayError::NoAuthentication
+            | serenity::GatewayError::InvalidGatewayIntents
+            | serenity::GatewayError::DisallowedGatewayIntents
+    ))
 }
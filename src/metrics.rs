@@ -0,0 +1,66 @@
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use axum::{Router, routing::get, extract::State, http::{StatusCode, header}, response::IntoResponse};
+use tracing::{info, warn};
+use crate::types::BotState;
+use crate::cache::refresh_times;
+
+// Serves GET /healthz and GET /metrics (Prometheus text format) until the process exits
+pub async fn serve(addr: SocketAddr, bot: Arc<BotState>) {
+    let app = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/metrics", get(metrics))
+        .with_state(bot);
+
+    match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => {
+            info!("📈 Metrics endpoint listening on {}", addr);
+            if let Err(e) = axum::serve(listener, app).await {
+                warn!("⚠️ Metrics server stopped: {}", e);
+            }
+        }
+        Err(e) => warn!("⚠️ Failed to bind metrics endpoint on {}: {}", addr, e),
+    }
+}
+
+// 200 once the Discord framework is set up, 503 while (re)connecting
+async fn healthz(State(bot): State<Arc<BotState>>) -> StatusCode {
+    if bot.ready.load(Ordering::Relaxed) { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE }
+}
+
+async fn metrics(State(bot): State<Arc<BotState>>) -> impl IntoResponse {
+    let mut out = String::new();
+    let gauges = [
+        ("usthingy_cached_repos", "Repos in the cache", bot.repos.read().await.len() as i64),
+        ("usthingy_cached_users", "Users in the cache", bot.users.read().await.len() as i64),
+        ("usthingy_cached_projects", "Projects in the cache", bot.projects.read().await.len() as i64),
+        ("usthingy_up", "1 when the Discord framework is set up", bot.ready.load(Ordering::Relaxed) as i64),
+    ];
+    for (name, help, value) in gauges {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} gauge\n{} {}", name, help, name, name, value);
+    }
+
+    let _ = writeln!(out, "# HELP usthingy_cache_last_refresh_timestamp_seconds Unix time each cache part last finished refreshing (0 = not since startup)\n# TYPE usthingy_cache_last_refresh_timestamp_seconds gauge");
+    for (part, at) in refresh_times(&bot).await {
+        let _ = writeln!(out, "usthingy_cache_last_refresh_timestamp_seconds{{cache=\"{}\"}} {}", part, at.map(|t| t.timestamp()).unwrap_or(0));
+    }
+
+    let _ = writeln!(out, "# HELP usthingy_command_invocations_total Slash command invocations\n# TYPE usthingy_command_invocations_total counter");
+    for (command, count) in bot.command_counts.snapshot() {
+        let _ = writeln!(out, "usthingy_command_invocations_total{{command=\"{}\"}} {}", label_value(&command), count);
+    }
+
+    let _ = writeln!(out, "# HELP usthingy_github_api_calls_total GitHub API call sites per source\n# TYPE usthingy_github_api_calls_total counter");
+    for (source, api, count) in bot.api_stats.snapshot() {
+        let _ = writeln!(out, "usthingy_github_api_calls_total{{source=\"{}\",api=\"{}\"}} {}", label_value(&source), api.as_str(), count);
+    }
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], out)
+}
+
+// Escapes a Prometheus label value
+fn label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
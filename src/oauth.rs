@@ -0,0 +1,212 @@
+use poise::serenity_prelude as serenity;
+use std::time::Duration;
+use crate::types::{BotState, Error, UserToken};
+use tracing::warn;
+
+// Started device flow: what the user has to enter where, and what we poll with
+pub struct DeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub interval: u64,
+    // Seconds until the device code stops working
+    pub expires_in: u64,
+}
+
+pub enum ConnectOutcome {
+    Linked(String),
+    // Linked in memory, but the mapping file couldn't be written
+    LinkedUnsaved(String),
+    Denied,
+    TimedOut,
+    // GitHub invalidated the device code before the user finished
+    Expired,
+    Failed(String),
+}
+
+impl ConnectOutcome {
+    pub fn message(&self) -> String {
+        match self {
+            ConnectOutcome::Linked(login) => format!("✅ **Success!** Linked to GitHub account **{}**.", login),
+            ConnectOutcome::LinkedUnsaved(login) => format!(
+                "⚠️ Linked to GitHub account **{}** for now, but the link couldn't be saved and will be lost when the bot restarts. Please tell the bot owner.",
+                login
+            ),
+            ConnectOutcome::Denied => "❌ **Denied**: Access denied by user.".to_string(),
+            ConnectOutcome::TimedOut => "⏰ **Timeout**: Connection cancelled.".to_string(),
+            ConnectOutcome::Expired => "⏰ **Expired**: The code is no longer valid, run `/user connect` again.".to_string(),
+            ConnectOutcome::Failed(reason) => format!("❌ **Error**: {}", reason),
+        }
+    }
+}
+
+// 1. Request Device Code
+pub async fn request_device_code(state: &BotState) -> Result<DeviceCode, Error> {
+    let params = [("client_id", state.github_client_id.as_str()), ("scope", "read:user repo")];
+    let res = state.http_client.post("https://github.com/login/device/code")
+        .header("Accept", "application/json")
+        .form(&params)
+        .send()
+        .await?;
+
+    if !res.status().is_success() {
+        return Err(format!("Failed to initiate device flow: {}", res.status()).into());
+    }
+
+    let body: serde_json::Value = res.json().await?;
+    Ok(DeviceCode {
+        device_code: body["device_code"].as_str().ok_or("missing device_code")?.to_string(),
+        user_code: body["user_code"].as_str().ok_or("missing user_code")?.to_string(),
+        verification_uri: body["verification_uri"].as_str().ok_or("missing verification_uri")?.to_string(),
+        interval: body["interval"].as_u64().unwrap_or(5),
+        expires_in: body["expires_in"].as_u64().unwrap_or(900),
+    })
+}
+
+// 2. Instruct User
+pub fn instructions_embed(code: &DeviceCode) -> serenity::CreateEmbed {
+    serenity::CreateEmbed::new()
+        .title("🔗 Connect to GitHub")
+        .description(format!("To link your account, please complete the device flow:\n\n1. Click **[Login to GitHub]({})**\n2. Enter code: `{}`", code.verification_uri, code.user_code))
+        .footer(serenity::CreateEmbedFooter::new("I will automatically check when you are done..."))
+        .color(0x5865F2)
+}
+
+// 3-5. Poll for the token, look up the login and save the mapping
+pub async fn complete_device_flow(state: &BotState, discord_id: u64, code: &DeviceCode) -> Result<ConnectOutcome, Error> {
+    let start_time = std::time::Instant::now();
+    let mut interval = code.interval;
+
+    let token = loop {
+        if start_time.elapsed().as_secs() > code.expires_in {
+            return Ok(ConnectOutcome::TimedOut);
+        }
+
+        tokio::time::sleep(Duration::from_secs(interval + 1)).await;
+
+        let params = [
+            ("client_id", state.github_client_id.as_str()),
+            ("device_code", code.device_code.as_str()),
+            ("grant_type", "urn:ietf:params:oauth:grant-type:device_code")
+        ];
+
+        let res = state.http_client.post("https://github.com/login/oauth/access_token")
+            .header("Accept", "application/json")
+            .form(&params)
+            .send()
+            .await?;
+
+        if let Ok(body) = res.json::<serde_json::Value>().await {
+            if let Some(token) = token_from_response(&body) {
+                break token;
+            }
+            if let Some(err) = body["error"].as_str() {
+                match err {
+                    "access_denied" => return Ok(ConnectOutcome::Denied),
+                    "expired_token" => return Ok(ConnectOutcome::Expired),
+                    // Polling too fast: GitHub sends the new interval, otherwise add 5s (RFC 8628)
+                    "slow_down" => interval = body["interval"].as_u64().unwrap_or(interval + 5),
+                    // "authorization_pending" is expected, continue loop
+                    _ => {}
+                }
+            }
+        }
+    };
+
+    link_token(state, discord_id, token).await
+}
+
+// Looks up the token's GitHub login and saves the link; shared by the device flow and
+// tokens pasted into /user connect
+pub async fn link_token(state: &BotState, discord_id: u64, token: UserToken) -> Result<ConnectOutcome, Error> {
+    let user_res = state.http_client.get("https://api.github.com/user")
+        .header("Authorization", format!("Bearer {}", token.access_token))
+        .header("User-Agent", "usthingy-bot")
+        .send()
+        .await?;
+
+    if !user_res.status().is_success() {
+        return Ok(ConnectOutcome::Failed(format!("GitHub didn't accept the token ({}).", user_res.status())));
+    }
+
+    let user_body: serde_json::Value = user_res.json().await?;
+    let github_login = user_body["login"].as_str().ok_or("missing login")?.to_string();
+
+    // Save Mapping; the in-memory link stays even if the write fails
+    let saved = {
+        let mut mapping = state.user_mapping.write().await;
+        mapping.map.insert(discord_id, github_login.clone());
+        mapping.tokens.insert(discord_id, token);
+        mapping.save(&state.user_mapping_path)
+    };
+
+    match saved {
+        Ok(()) => Ok(ConnectOutcome::Linked(github_login)),
+        Err(e) => {
+            warn!("⚠️ Failed to save user mapping for {} ({}): {}", discord_id, github_login, e);
+            Ok(ConnectOutcome::LinkedUnsaved(github_login))
+        }
+    }
+}
+
+// access_token plus, for expiring tokens, refresh_token and expires_in (seconds)
+fn token_from_response(body: &serde_json::Value) -> Option<UserToken> {
+    let access_token = body["access_token"].as_str()?.to_string();
+    Some(UserToken {
+        access_token,
+        refresh_token: body["refresh_token"].as_str().map(|t| t.to_string()),
+        expires_at: body["expires_in"].as_i64().map(|secs| chrono::Utc::now().timestamp() + secs),
+    })
+}
+
+// The user's access token, refreshed first when it has expired. None when they never
+// connected (or connected before tokens were kept), or the refresh failed; an unusable
+// token is dropped so /user connect starts over cleanly.
+pub async fn user_access_token(state: &BotState, discord_id: u64) -> Option<String> {
+    let token = state.user_mapping.read().await.tokens.get(&discord_id).cloned();
+    let Some(token) = token else {
+        warn!("⚠️ No GitHub token stored for Discord user {}, acting with the bot's token", discord_id);
+        return None;
+    };
+    if !token.is_expired() {
+        return Some(token.access_token);
+    }
+
+    let refreshed = match refresh_token(state, &token).await {
+        Ok(t) => Some(t),
+        Err(e) => {
+            warn!("⚠️ GitHub token for Discord user {} expired and couldn't be refreshed ({}), acting with the bot's token", discord_id, e);
+            None
+        }
+    };
+    let mut mapping = state.user_mapping.write().await;
+    match &refreshed {
+        Some(t) => { mapping.tokens.insert(discord_id, t.clone()); }
+        None => { mapping.tokens.remove(&discord_id); }
+    }
+    if let Err(e) = mapping.save(&state.user_mapping_path) {
+        warn!("⚠️ Failed to save user mapping after a token refresh for {}: {}", discord_id, e);
+    }
+    refreshed.map(|t| t.access_token)
+}
+
+async fn refresh_token(state: &BotState, token: &UserToken) -> Result<UserToken, Error> {
+    let refresh = token.refresh_token.as_deref().ok_or("no refresh token")?;
+    let secret = state.github_client_secret.as_deref().ok_or("GITHUB_CLIENT_SECRET is not set")?;
+    let params = [
+        ("client_id", state.github_client_id.as_str()),
+        ("client_secret", secret),
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh),
+    ];
+    let res = state.http_client.post("https://github.com/login/oauth/access_token")
+        .header("Accept", "application/json")
+        .form(&params)
+        .send()
+        .await?;
+    let body: serde_json::Value = res.json().await?;
+    if let Some(err) = body["error"].as_str() {
+        return Err(err.into());
+    }
+    token_from_response(&body).ok_or_else(|| "missing access_token".into())
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::path::Path;
+use crate::types::write_atomically;
+
+// The /proj view arguments worth replaying (everything but the page)
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+pub struct ViewPreset {
+    pub project: String,
+    pub filter: Option<String>,
+    pub show_field: Option<String>,
+    pub group_by: Option<String>,
+    pub label: Option<String>,
+}
+
+impl ViewPreset {
+    // "Backlog (filter: all, group_by: Status)"
+    pub fn describe(&self) -> String {
+        let opts: Vec<String> = [
+            ("filter", &self.filter),
+            ("show_field", &self.show_field),
+            ("group_by", &self.group_by),
+            ("label", &self.label),
+        ].iter()
+            .filter_map(|(k, v)| v.as_ref().map(|v| format!("{}: {}", k, v)))
+            .collect();
+        if opts.is_empty() {
+            self.project.clone()
+        } else {
+            format!("{} ({})", self.project, opts.join(", "))
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Default, Clone)]
+pub struct Presets {
+    // Discord ID -> preset name -> view arguments
+    pub users: HashMap<u64, HashMap<String, ViewPreset>>,
+}
+
+impl Presets {
+    pub fn load(path: &Path) -> Self {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        write_atomically(path, &serde_json::to_string_pretty(self)?, false)
+    }
+}
@@ -0,0 +1,99 @@
+use poise::serenity_prelude as serenity;
+
+// Discord's message content limit
+const MAX_CONTENT_LEN: usize = 2000;
+
+// A command result that can be sent either as an embed or as plain markdown (the `plain` option).
+// Commands fill this in once and pick the representation when replying.
+#[derive(Clone, Debug, Default)]
+pub struct Rendered {
+    pub title: String,
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub fields: Vec<(String, String)>,
+    pub footer: Option<String>,
+    pub thumbnail: Option<String>,
+    pub color: u32,
+}
+
+impl Rendered {
+    pub fn new(title: impl Into<String>, color: u32) -> Self {
+        Rendered { title: title.into(), color, ..Default::default() }
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    pub fn thumbnail(mut self, url: impl Into<String>) -> Self {
+        self.thumbnail = Some(url.into());
+        self
+    }
+
+    pub fn to_embed(&self) -> serenity::CreateEmbed {
+        let mut embed = serenity::CreateEmbed::new().title(&self.title).color(self.color);
+        if let Some(url) = &self.url {
+            embed = embed.url(url);
+        }
+        if let Some(description) = &self.description {
+            embed = embed.description(description);
+        }
+        for (name, value) in &self.fields {
+            embed = embed.field(name, value, false);
+        }
+        if let Some(footer) = &self.footer {
+            embed = embed.footer(serenity::CreateEmbedFooter::new(footer));
+        }
+        if let Some(thumbnail) = &self.thumbnail {
+            embed = embed.thumbnail(thumbnail);
+        }
+        embed
+    }
+
+    // Markdown that survives copy-paste; cut at the content limit
+    pub fn to_plain(&self) -> String {
+        let mut out = match &self.url {
+            // <url> keeps Discord from unfurling the link into an embed again
+            Some(url) => format!("## {}\n<{}>", self.title, url),
+            None => format!("## {}", self.title),
+        };
+        if let Some(description) = &self.description {
+            out.push_str(&format!("\n{}", description));
+        }
+        for (name, value) in &self.fields {
+            out.push_str(&format!("\n**{}**\n{}", name, value));
+        }
+        if let Some(footer) = &self.footer {
+            out.push_str(&format!("\n-# {}", footer));
+        }
+        if out.chars().count() > MAX_CONTENT_LEN {
+            out = out.chars().take(MAX_CONTENT_LEN - 1).collect();
+            out.push('…');
+        }
+        out
+    }
+
+    pub fn reply(&self, plain: bool) -> poise::CreateReply {
+        if plain {
+            poise::CreateReply::default().content(self.to_plain())
+        } else {
+            poise::CreateReply::default().embed(self.to_embed())
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use crate::types::Context;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Api {
+    Rest,
+    Graphql,
+}
+
+impl Api {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Api::Rest => "rest",
+            Api::Graphql => "graphql",
+        }
+    }
+}
+
+// GitHub API calls per source (command qualified name, "component", "refresh", ...) and API kind.
+// Counts requests: every page of a paginated fetch and every retry is one more.
+#[derive(Default)]
+pub struct ApiStats {
+    counts: Mutex<HashMap<(String, Api), u64>>,
+}
+
+impl ApiStats {
+    pub fn record(&self, source: &str, api: Api) {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        *counts.entry((source.to_string(), api)).or_insert(0) += 1;
+    }
+
+    // Highest counts first
+    pub fn snapshot(&self) -> Vec<(String, Api, u64)> {
+        let counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        let mut rows: Vec<(String, Api, u64)> = counts.iter().map(|((s, a), n)| (s.clone(), *a, *n)).collect();
+        rows.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+        rows
+    }
+}
+
+// Slash command invocations per qualified name (/metrics)
+#[derive(Default)]
+pub struct CommandCounts {
+    counts: Mutex<HashMap<String, u64>>,
+}
+
+impl CommandCounts {
+    pub fn record(&self, command: &str) {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        *counts.entry(command.to_string()).or_insert(0) += 1;
+    }
+
+    // Sorted by command name
+    pub fn snapshot(&self) -> Vec<(String, u64)> {
+        let counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        let mut rows: Vec<(String, u64)> = counts.iter().map(|(c, n)| (c.clone(), *n)).collect();
+        rows.sort();
+        rows
+    }
+}
+
+// Records one call on behalf of the running slash command
+pub fn count(ctx: Context<'_>, api: Api) {
+    ctx.data().api_stats.record(&ctx.command().qualified_name, api);
+}
+
+// Source name of the running slash command, for the request wrappers in github.rs
+pub fn source<'a>(ctx: Context<'a>) -> &'a str {
+    &ctx.command().qualified_name
+}
@@ -3,30 +3,79 @@ use tokio::sync::RwLock;
 use octocrab::Octocrab;
 use reqwest::Client as HttpClient;
 use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use crate::guild_config::GuildConfigs;
+use crate::stats::{ApiStats, CommandCounts};
+use crate::presets::Presets;
+use tracing::warn;
 
-#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct CachedRepo {
+    // Owning org (one of GITHUB_ORGS)
+    #[serde(default)]
+    pub org: String,
     pub name: String,
-    #[allow(dead_code)]
+    // "owner/name", unique even when short names collide
     pub full_name: String,
+    pub default_branch: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+impl CachedRepo {
+    pub fn display_name(&self) -> &str {
+        if self.full_name.is_empty() { &self.name } else { &self.full_name }
+    }
+
+    // Whether a user-supplied repo ("name" or "owner/name") refers to this repo
+    pub fn matches(&self, repo: &str) -> bool {
+        match repo.split_once('/') {
+            Some((owner, name)) => self.org.eq_ignore_ascii_case(owner) && self.name.eq_ignore_ascii_case(name),
+            None => self.name.eq_ignore_ascii_case(repo),
+        }
+    }
+
+    // What autocomplete offers: the short name, or "owner/name" when another org has a repo
+    // of the same name (the short name would always resolve to the first org)
+    pub fn key(&self, repos: &[CachedRepo]) -> String {
+        let shared = repos.iter().filter(|r| r.name.eq_ignore_ascii_case(&self.name)).count() > 1;
+        if shared && !self.org.is_empty() { format!("{}/{}", self.org, self.name) } else { self.name.clone() }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct CachedUser {
+    #[serde(default)]
+    pub org: String,
     pub login: String,
-    #[allow(dead_code)]
     pub avatar_url: String,
 }
 
 #[derive(Clone, Debug)]
+pub struct CachedTeam {
+    pub org: String,
+    pub slug: String,
+    pub name: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct CachedIssue {
+    pub number: u64,
+    pub title: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct CachedItem {
+    // Project item node id (not the issue's), empty when unknown
+    #[serde(default)]
+    pub id: String,
     pub title: String,
     pub number: i64,
     pub repo_name: String,
     pub state: String,
+    pub assignees: Vec<String>, // Logins, first few only
 }
 
-#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct CachedField {
     pub id: String,
     pub name: String,
@@ -34,13 +83,15 @@ pub struct CachedField {
     pub options: HashMap<String, String>, // Option Name -> Option ID
 }
 
-#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct CachedProject {
+    #[serde(default)]
+    pub org: String,
     pub id: String,
     pub title: String,
     pub url: String,
-    #[allow(dead_code)]
     pub number: i64,
+    pub short_description: Option<String>,
     pub items: Vec<CachedItem>,
     pub fields: Vec<CachedField>,
 }
@@ -49,36 +100,330 @@ pub struct CachedProject {
 pub struct UserMapping {
     // Discord ID -> GitHub Username
     pub map: HashMap<u64, String>,
+    // Discord ID -> OAuth token from /user connect, used to act as that user.
+    // Links made before tokens were kept have none and fall back to the bot's token.
+    #[serde(default)]
+    pub tokens: HashMap<u64, UserToken>,
+}
+
+// A device-flow token. Stored in plain text, so the mapping file is written owner-only.
+// No Debug on purpose, it would end up in logs.
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct UserToken {
+    pub access_token: String,
+    // Only expiring tokens (GitHub Apps) come with a refresh token and an expiry
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    // Unix seconds
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
+
+impl UserToken {
+    // Treat tokens as expired a minute early so they don't lapse mid-request
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|t| t <= chrono::Utc::now().timestamp() + 60)
+    }
 }
 
 impl UserMapping {
-    pub fn load() -> Self {
-        if let Ok(content) = std::fs::read_to_string("user_mapping.json") {
+    pub fn load(path: &Path) -> Self {
+        if let Ok(content) = std::fs::read_to_string(path) {
             serde_json::from_str(&content).unwrap_or_default()
         } else {
             Self::default()
         }
     }
 
-    pub fn save(&self) {
-        if let Ok(content) = serde_json::to_string_pretty(self) {
-            let _ = std::fs::write("user_mapping.json", content);
+    // Callers report failures: a link that isn't on disk silently disappears on restart.
+    // Callers hold the write lock, so saves don't race.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        // It holds users' OAuth tokens
+        write_atomically(path, &serde_json::to_string_pretty(self)?, true)
+    }
+}
+
+// Writes a temp file and renames it over `path`, so a crash mid-write can't leave a truncated
+// store behind. Missing parent directories are created; `private` files are owner-only (0600).
+pub fn write_atomically(path: &Path, content: &str, private: bool) -> std::io::Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, content)?;
+    if private {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))?;
         }
     }
+    std::fs::rename(&tmp, path)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Default, Clone)]
+pub struct Snoozes {
+    // Discord ID -> unix seconds until which the bot won't mention them
+    pub until: HashMap<u64, i64>,
+}
+
+impl Snoozes {
+    pub fn load(path: &Path) -> Self {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        write_atomically(path, &serde_json::to_string_pretty(self)?, false)
+    }
+
+    pub fn is_snoozed(&self, discord_id: u64) -> bool {
+        self.until.get(&discord_id).is_some_and(|&t| t > chrono::Utc::now().timestamp())
+    }
 }
 
 pub struct BotState {
     pub octocrab: Octocrab,
     pub http_client: HttpClient,
-    pub github_org: String,
+    // GITHUB_ORGS, in configured order; never empty, the first is the default
+    pub github_orgs: Vec<String>,
     pub github_client_id: String,
+    // Needed to refresh expiring user tokens (GitHub Apps); OAuth App tokens don't expire
+    pub github_client_secret: Option<String>,
     // Caches protected by RwLock for concurrent access
     pub repos: RwLock<Vec<CachedRepo>>,
     pub users: RwLock<Vec<CachedUser>>,
+    pub teams: RwLock<Vec<CachedTeam>>,
     pub projects: RwLock<Vec<CachedProject>>,
-    // User mapping (Discord -> GitHub)
+    // Set once the repo/project lists have been fetched successfully, so an empty
+    // list can be told apart from one that never loaded
+    pub repos_loaded: AtomicBool,
+    pub projects_loaded: AtomicBool,
+    // One lock per project item node id, so writes to the same item don't interleave
+    pub item_locks: std::sync::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    // Bounds concurrent searches/GraphQL calls; go through github::graphql / github::search_issues
+    pub github_permits: tokio::sync::Semaphore,
+    // GitHub API calls per command
+    pub api_stats: ApiStats,
+    // Slash command invocations, and whether the Discord framework is up (METRICS_ADDR endpoints)
+    pub command_counts: CommandCounts,
+    pub ready: AtomicBool,
+    // When each part of the cache (cache::CACHE_PARTS) last finished refreshing
+    pub last_refreshed: RwLock<HashMap<String, chrono::DateTime<chrono::Utc>>>,
+    // Open issues per repo name, filled lazily on first lookup
+    pub issues: RwLock<HashMap<String, Vec<CachedIssue>>>,
+    // Label names per repo name, filled lazily on first lookup
+    pub labels: RwLock<HashMap<String, Vec<String>>>,
+    // Branch names per repo name with their fetch time, refetched after BRANCH_TTL
+    pub branches: RwLock<HashMap<String, (std::time::Instant, Vec<String>)>>,
+    // Discussion categories (name, node id) per repo name, filled lazily
+    pub discussion_categories: RwLock<HashMap<String, Vec<(String, String)>>>,
+    // User mapping (Discord -> GitHub) and where it's saved (USER_MAPPING_PATH)
     pub user_mapping: RwLock<UserMapping>,
+    pub user_mapping_path: PathBuf,
+    // Users who muted bot mentions for a while
+    pub snoozes: RwLock<Snoozes>,
+    pub snoozes_path: PathBuf,
+    // Saved /proj view arguments per user
+    pub presets: RwLock<Presets>,
+    pub presets_path: PathBuf,
+    // Per-guild settings (disabled command groups)
+    pub guild_configs: RwLock<GuildConfigs>,
+    pub guild_config_path: PathBuf,
+    // Optional channel that mirrors every project edit
+    pub audit_channel: Option<poise::serenity_prelude::ChannelId>,
+    // Webhook: shared secret for signature checks and the channel for board activity
+    pub webhook_secret: Option<String>,
+    pub activity_channel: Option<poise::serenity_prelude::ChannelId>,
+}
+
+impl BotState {
+    pub fn default_org(&self) -> &str {
+        &self.github_orgs[0]
+    }
+
+    // The org owning a repo. "owner/name" names it explicitly. A short name is looked up across
+    // all orgs, so when two orgs share a name the first configured one wins (autocomplete offers
+    // "owner/name" for those); unknown repos go to the default org. Pair with utils::repo_name.
+    pub async fn repo_org(&self, repo: &str) -> String {
+        let org = match repo.split_once('/') {
+            Some((owner, _)) => self.github_orgs.iter().find(|o| o.eq_ignore_ascii_case(owner)).cloned().unwrap_or_else(|| owner.to_string()),
+            None => self.repos.read().await.iter()
+                .find(|r| r.matches(repo))
+                .map(|r| r.org.clone())
+                .filter(|o| !o.is_empty())
+                .unwrap_or_else(|| self.default_org().to_string()),
+        };
+        // Tags the surrounding command/component span, a no-op elsewhere
+        tracing::Span::current().record("org", org.as_str());
+        org
+    }
+
+    // "org:a org:b" search qualifiers covering every configured org
+    pub fn org_qualifiers(&self) -> String {
+        self.github_orgs.iter().map(|o| format!("org:{}", o)).collect::<Vec<_>>().join(" ")
+    }
+
+    // A client acting as the linked GitHub user, so GitHub attributes writes to them.
+    // None when they have no usable token (logged); see octocrab_for.
+    pub async fn user_octocrab(&self, discord_id: u64) -> Option<Octocrab> {
+        let token = crate::oauth::user_access_token(self, discord_id).await?;
+        match Octocrab::builder().user_access_token(token).build() {
+            Ok(client) => Some(client),
+            Err(e) => {
+                warn!("⚠️ Failed to build a GitHub client for Discord user {}: {}", discord_id, e);
+                None
+            }
+        }
+    }
+
+    // The user's client, or the bot's token when they have none
+    pub async fn octocrab_for(&self, discord_id: u64) -> Octocrab {
+        self.user_octocrab(discord_id).await.unwrap_or_else(|| self.octocrab.clone())
+    }
+}
+
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq, Debug)]
+pub enum StateFilter {
+    #[name = "open"]
+    Open,
+    #[name = "closed"]
+    Closed,
+    #[name = "all"]
+    All,
+}
+
+impl StateFilter {
+    pub fn as_param(self) -> octocrab::params::State {
+        match self {
+            StateFilter::Open => octocrab::params::State::Open,
+            StateFilter::Closed => octocrab::params::State::Closed,
+            StateFilter::All => octocrab::params::State::All,
+        }
+    }
+
+    // Search qualifier for the filter, empty for all
+    pub fn search_qualifier(self) -> &'static str {
+        match self {
+            StateFilter::Open => " is:open",
+            StateFilter::Closed => " is:closed",
+            StateFilter::All => "",
+        }
+    }
+
+    // Word put before "issues"/"PRs" in counts, with a trailing space; empty for all
+    pub fn adjective(self) -> &'static str {
+        match self {
+            StateFilter::Open => "open ",
+            StateFilter::Closed => "closed ",
+            StateFilter::All => "",
+        }
+    }
+}
+
+// What /refresh reloads
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq, Debug)]
+pub enum RefreshScope {
+    #[name = "all"]
+    All,
+    #[name = "repos"]
+    Repos,
+    #[name = "users"]
+    Users,
+    #[name = "projects"]
+    Projects,
+}
+
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq, Debug)]
+pub enum MergeMethod {
+    #[name = "merge"]
+    Merge,
+    #[name = "squash"]
+    Squash,
+    #[name = "rebase"]
+    Rebase,
+}
+
+impl MergeMethod {
+    pub fn as_param(self) -> octocrab::params::pulls::MergeMethod {
+        match self {
+            MergeMethod::Merge => octocrab::params::pulls::MergeMethod::Merge,
+            MergeMethod::Squash => octocrab::params::pulls::MergeMethod::Squash,
+            MergeMethod::Rebase => octocrab::params::pulls::MergeMethod::Rebase,
+        }
+    }
+}
+
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq, Debug)]
+pub enum ReviewEvent {
+    #[name = "approve"]
+    Approve,
+    #[name = "request changes"]
+    RequestChanges,
+    #[name = "comment"]
+    Comment,
+}
+
+impl ReviewEvent {
+    // The `event` value of the create-review endpoint
+    pub fn as_api(self) -> &'static str {
+        match self {
+            ReviewEvent::Approve => "APPROVE",
+            ReviewEvent::RequestChanges => "REQUEST_CHANGES",
+            ReviewEvent::Comment => "COMMENT",
+        }
+    }
 }
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
+// Errors a command can return with `?` that utils::on_error answers with a proper reply,
+// instead of poise's generic "an error occurred". Anything else can still be boxed into Error.
+#[derive(Debug)]
+pub enum BotError {
+    // No GitHub account linked to the caller's Discord account
+    NotAuthenticated,
+    // A REST/GraphQL request to GitHub failed (boxed, octocrab's error is large)
+    GitHub(Box<octocrab::Error>),
+    // GitHub answered a GraphQL query with an `errors` array
+    GraphQl(String),
+    // A project, repo, item... the user named doesn't resolve; holds the message for them
+    NotFound(String),
+    // User input rejected before it reached GitHub; holds the message for them
+    Invalid(String),
+}
+
+impl std::fmt::Display for BotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BotError::NotAuthenticated => write!(f, "User not authenticated"),
+            BotError::GitHub(e) => match e.as_ref() {
+                octocrab::Error::GitHub { source, .. } => write!(f, "GitHub API error: {}", source.message),
+                e => write!(f, "GitHub API error: {}", e),
+            },
+            BotError::GraphQl(msg) => write!(f, "GitHub GraphQL error: {}", msg),
+            BotError::NotFound(msg) | BotError::Invalid(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BotError::GitHub(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<octocrab::Error> for BotError {
+    fn from(e: octocrab::Error) -> Self {
+        BotError::GitHub(Box::new(e))
+    }
+}
 pub type Context<'a> = poise::Context<'a, Arc<BotState>, Error>;
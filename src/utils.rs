@@ -1,25 +1,434 @@
 use poise::serenity_prelude as serenity;
-use crate::types::{Context, Error};
+use std::sync::Arc;
+use crate::custom_id::CustomId;
+use crate::types::{BotError, BotState, CachedProject, CachedUser, Context, Error};
+use crate::cache::repo_issues;
+use crate::guild_config::LabelCategory;
+use tracing::warn;
 
 // --- Helper: Check Permissions ---
-// Returns GitHub username if authenticated, Error if not
-pub async fn check_auth(ctx: Context<'_>) -> Result<String, Error> {
-    let discord_id = ctx.author().id.get();
-    let state = ctx.data();
-    let mapping = state.user_mapping.read().await;
-    
-    if let Some(gh_user) = mapping.map.get(&discord_id) {
-        Ok(gh_user.clone())
+// Returns the caller's GitHub username, or BotError::NotAuthenticated without replying
+pub async fn check_auth(ctx: Context<'_>) -> Result<String, BotError> {
+    let mapping = ctx.data().user_mapping.read().await;
+    mapping.map.get(&ctx.author().id.get()).cloned().ok_or(BotError::NotAuthenticated)
+}
+
+// What the user sees when a command fails with a BotError
+pub fn bot_error_reply(error: &BotError) -> poise::CreateReply {
+    match error {
+        BotError::NotAuthenticated => poise::CreateReply::default()
+            .content("⛔ **Permission Denied**: You must connect your GitHub account to perform this action.\nClick the button below to log in through GitHub, or run `/user connect` (optionally with `token:` set to a personal access token).")
+            .components(vec![serenity::CreateActionRow::Buttons(vec![connect_button()])])
+            .ephemeral(true),
+        BotError::GitHub(_) | BotError::GraphQl(_) => poise::CreateReply::default().content(format!("❌ {}", error)),
+        BotError::NotFound(msg) | BotError::Invalid(msg) => poise::CreateReply::default().content(msg),
+    }
+}
+
+// --- Helper: Framework Errors ---
+// BotErrors get their own reply; everything else keeps poise's default reply/logging
+pub async fn on_error(error: poise::FrameworkError<'_, Arc<BotState>, Error>) {
+    if let poise::FrameworkError::Command { error: e, ctx, .. } = &error {
+        if let Some(bot_error) = e.downcast_ref::<BotError>() {
+            if matches!(bot_error, BotError::GitHub(_) | BotError::GraphQl(_)) {
+                warn!("⚠️ /{} failed: {}", ctx.command().qualified_name, bot_error);
+            }
+            if let Err(e) = ctx.send(bot_error_reply(bot_error)).await {
+                warn!("⚠️ Failed to report an error for /{}: {}", ctx.command().qualified_name, e);
+            }
+            return;
+        }
+    }
+    if let Err(e) = poise::builtins::on_error(error).await {
+        warn!("⚠️ Failed to report a command error: {}", e);
+    }
+}
+
+// Starts the device flow when clicked (handled in handler.rs)
+pub fn connect_button() -> serenity::CreateButton {
+    serenity::CreateButton::new(CustomId::Connect.to_string())
+        .label("🔗 Connect GitHub")
+        .style(serenity::ButtonStyle::Primary)
+}
+
+// --- Helper: Defer ---
+// A failed defer (interaction expired or already acknowledged) shouldn't abort the
+// command: log it and let the reply still go out.
+pub async fn defer(ctx: Context<'_>) {
+    if let Err(e) = ctx.defer().await {
+        warn!("⚠️ Failed to defer /{}: {}", ctx.command().qualified_name, e);
+    }
+}
+
+pub async fn defer_ephemeral(ctx: Context<'_>) {
+    if let Err(e) = ctx.defer_ephemeral().await {
+        warn!("⚠️ Failed to defer /{}: {}", ctx.command().qualified_name, e);
+    }
+}
+
+// --- Helper: Validate GitHub Names ---
+// Org/user logins: alphanumerics and single hyphens, no leading/trailing hyphen, max 39 chars
+pub fn is_valid_login(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 39
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && !name.contains("--")
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+// Repo names as users give them: a short name, or "owner/name" to pick the org
+pub fn is_valid_repo_name(name: &str) -> bool {
+    match name.split_once('/') {
+        Some((owner, repo)) => is_valid_login(owner) && is_valid_short_repo_name(repo),
+        None => is_valid_short_repo_name(name),
+    }
+}
+
+// Short repo names: alphanumerics, '-', '_' and '.', max 100 chars, and not "." or ".."
+fn is_valid_short_repo_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 100
+        && name != "."
+        && name != ".."
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+// The repo name without its "owner/" prefix, for API paths (the owner comes from BotState::repo_org)
+pub fn repo_name(repo: &str) -> &str {
+    repo.split_once('/').map(|(_, name)| name).unwrap_or(repo)
+}
+
+// Rejects user-supplied repo names before they reach any API path or query.
+// The Err is reported to the user by on_error.
+pub fn check_repo_name(repo: &str) -> Result<(), BotError> {
+    if is_valid_repo_name(repo) {
+        Ok(())
+    } else {
+        Err(BotError::Invalid(format!("❌ `{}` is not a valid repository name.", repo)))
+    }
+}
+
+// Same as check_repo_name but for GitHub user logins
+pub fn check_login(login: &str) -> Result<(), BotError> {
+    if is_valid_login(login) {
+        Ok(())
+    } else {
+        Err(BotError::Invalid(format!("❌ `{}` is not a valid GitHub username.", login)))
+    }
+}
+
+// --- Helper: Resolve Issue References ---
+// Accepts "123", "#123" or an autocomplete value like "#123: Title"
+pub fn parse_issue_ref(input: &str) -> Option<u64> {
+    let input = input.trim();
+    let rest = input.strip_prefix('#').unwrap_or(input);
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.is_empty() {
+        return None;
+    }
+    let tail = &rest[digits.len()..];
+    // "2024 roadmap" is a title, "#2024 roadmap" is a reference
+    if tail.is_empty() || tail.starts_with(':') || (input.starts_with('#') && tail.starts_with(' ')) {
+        digits.parse().ok()
     } else {
-        ctx.say("⛔ **Permission Denied**: You must connect your GitHub account to perform this action.\nUse `/user connect <github_username>` first.").await?;
-        Err("User not authenticated".into())
+        None
+    }
+}
+
+// Resolves a number or (fuzzy) issue title to an issue number using the repo's issue cache
+pub async fn resolve_issue_number(state: &BotState, repo: &str, input: &str) -> Option<u64> {
+    if let Some(num) = parse_issue_ref(input) {
+        return Some(num);
+    }
+
+    let query = input.trim().to_lowercase();
+    let issues = repo_issues(state, repo).await;
+    issues.iter()
+        .find(|i| i.title.to_lowercase() == query)
+        .or_else(|| issues.iter().find(|i| i.title.to_lowercase().contains(&query)))
+        .map(|i| i.number)
+}
+
+// --- Helper: Links ---
+// GraphQL content nodes can come back without a url (e.g. items we can't fully see).
+// Only http(s) URLs are used; anything else means "no link".
+pub fn usable_url(url: Option<&str>) -> Option<&str> {
+    url.filter(|u| u.starts_with("https://") || u.starts_with("http://"))
+}
+
+// "[text](url)", or just the text when there is no usable URL
+pub fn md_link(text: &str, url: Option<&str>) -> String {
+    match usable_url(url) {
+        Some(u) => format!("[{}]({})", text, u),
+        None => text.to_string(),
+    }
+}
+
+// --- Helper: Validate Field Input ---
+// GitHub caps text field values; longer ones are rejected by the API
+pub const MAX_TEXT_FIELD_LEN: usize = 1024;
+// Discord's limit for a modal text input
+pub const MAX_MODAL_TEXT_LEN: u16 = 4000;
+
+// Checks free-form input for a project field of `data_type` and normalizes it
+// ("today" -> YYYY-MM-DD). Err holds a message for the user.
+pub fn validate_field_value(data_type: &str, value: &str) -> Result<String, String> {
+    let value = value.trim();
+    match data_type {
+        "NUMBER" => match value.parse::<f64>() {
+            Ok(n) if n.is_finite() => Ok(value.to_string()),
+            _ => Err(format!("`{}` is not a number.", value)),
+        },
+        "DATE" => {
+            if value.eq_ignore_ascii_case("today") {
+                return Ok(chrono::Utc::now().format("%Y-%m-%d").to_string());
+            }
+            match chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                Ok(d) => Ok(d.format("%Y-%m-%d").to_string()),
+                Err(_) => Err(format!("`{}` is not a date. Use YYYY-MM-DD or `today`.", value)),
+            }
+        }
+        "SINGLE_SELECT" | "ITERATION" => Err(format!("`{}` is not one of the field's options.", value)),
+        _ if value.is_empty() => Err("The value can't be empty.".to_string()),
+        _ if value.chars().count() > MAX_TEXT_FIELD_LEN => Err(format!("Text values are limited to {} characters.", MAX_TEXT_FIELD_LEN)),
+        _ => Ok(value.to_string()),
+    }
+}
+
+// --- Helper: Find Project by Title ---
+// Exact (case-insensitive) title first; otherwise titles that contain the input or are a couple of
+// typos away from it, ignoring extra whitespace. Err holds a message for the user, listing the
+// candidates when more than one project matches.
+pub fn find_project(projects: &[CachedProject], title: &str) -> Result<CachedProject, String> {
+    if let Some(p) = projects.iter().find(|p| p.title.eq_ignore_ascii_case(title)) {
+        return Ok(p.clone());
+    }
+
+    let normalize = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    let wanted = normalize(title);
+    if wanted.is_empty() {
+        return Err("Give a project title.".to_string());
+    }
+    let candidates: Vec<&CachedProject> = projects.iter()
+        .filter(|p| {
+            let t = normalize(&p.title);
+            t == wanted || t.contains(&wanted) || edit_distance(&t, &wanted) <= 2
+        })
+        .collect();
+    match candidates.as_slice() {
+        [p] => Ok((*p).clone()),
+        [] => Err(format!("Project '{}' not found in cache. Try /refresh?", title)),
+        many => Err(format!(
+            "🤔 '{}' matches several projects: {}. Which one did you mean?",
+            title, many.iter().map(|p| format!("**{}**", p.title)).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+// Levenshtein distance over chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            cur[j + 1] = (prev[j] + usize::from(ca != *cb)).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
     }
+    prev[b.len()]
+}
+
+// --- Helper: Parse Durations ---
+// "30m", "4h", "2d", "1w" -> seconds
+pub fn parse_duration(input: &str) -> Option<i64> {
+    let input = input.trim().to_lowercase();
+    let unit = input.chars().last()?;
+    let amount: i64 = input[..input.len() - unit.len_utf8()].parse().ok().filter(|n| *n > 0)?;
+    let secs = match unit {
+        'm' => 60,
+        'h' => 3600,
+        'd' => 86400,
+        'w' => 604800,
+        _ => return None,
+    };
+    amount.checked_mul(secs)
+}
+
+// --- Helper: Parse Target Arguments ---
+// A date given to /repo target: absolute, or relative to the current due date (today if none)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TargetDate {
+    On(chrono::NaiveDate),
+    Days(i64),
+    Months(u32),
+}
+
+impl TargetDate {
+    pub fn resolve(self, base: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+        match self {
+            TargetDate::On(d) => Some(d),
+            TargetDate::Days(n) => base.checked_add_signed(chrono::Duration::days(n)),
+            TargetDate::Months(n) => base.checked_add_months(chrono::Months::new(n)),
+        }
+    }
+
+    pub fn is_relative(self) -> bool {
+        !matches!(self, TargetDate::On(_))
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TargetArgs {
+    pub date: Option<TargetDate>,
+    pub milestone: Option<String>,
+}
+
+// "+3d", "+1w", "+2m" (months), "2025-06-01", and anything else as the milestone name
+// (multiple words allowed, e.g. "v2.0 +1w" or "Q3 launch 2025-09-30"). Err holds a message for the user.
+pub fn parse_target_args(input: &str) -> Result<TargetArgs, String> {
+    let mut args = TargetArgs::default();
+    let mut name_parts = Vec::new();
+    for token in input.split_whitespace() {
+        let date = if let Some(rel) = token.strip_prefix('+') {
+            // The unit is the last char; split on a char boundary so "+3é" is rejected, not a panic
+            let unit_at = rel.char_indices().last().map(|(i, _)| i).unwrap_or(0);
+            let (count, unit) = rel.split_at(unit_at);
+            let count: u32 = count.parse().ok().filter(|n| *n > 0 && *n <= 999)
+                .ok_or_else(|| format!("`{}` is not a duration. Use e.g. `+3d`, `+1w`, or `+2m`.", token))?;
+            match unit {
+                "d" => TargetDate::Days(count as i64),
+                "w" => TargetDate::Days(count as i64 * 7),
+                "m" => TargetDate::Months(count),
+                _ => return Err(format!("`{}` is not a duration. Use e.g. `+3d`, `+1w`, or `+2m`.", token)),
+            }
+        } else if let Ok(d) = chrono::NaiveDate::parse_from_str(token, "%Y-%m-%d") {
+            TargetDate::On(d)
+        } else {
+            name_parts.push(token);
+            continue;
+        };
+        if args.date.replace(date).is_some() {
+            return Err("Give at most one date or duration.".to_string());
+        }
+    }
+    if !name_parts.is_empty() {
+        args.milestone = Some(name_parts.join(" "));
+    }
+    if args.date.is_none() && args.milestone.is_none() {
+        return Err("Give a milestone name, a date (`2025-06-01`), or a duration (`+1w`).".to_string());
+    }
+    Ok(args)
+}
+
+// --- Helper: Parse Item Query ---
+// "123", "Repo #123" or an item_autocomplete value like "Repo #123: Title (Project)" -> 123
+pub fn parse_item_number(item_query: &str) -> i64 {
+    let digits: String = match item_query.find('#') {
+        Some(idx) => item_query[idx + 1..].chars().take_while(|c| c.is_ascii_digit()).collect(),
+        None => item_query.chars().take_while(|c| c.is_ascii_digit()).collect(),
+    };
+    digits.parse().unwrap_or(0)
+}
+
+// Custom emoji for an issue/PR state (OPEN, CLOSED, MERGED)
+pub fn state_icon(state: &str) -> &'static str {
+    match state {
+        "OPEN" => "<:issue:1458877117176742065>",
+        "CLOSED" => "<:issue_neutral:1458877524015579209>",
+        "MERGED" => "<:pr_merged:1458877132414517360>",
+        _ => "⚪",
+    }
+}
+
+// --- Helper: Project Item Lines ---
+// (number, title, repo) of an item in a board's select menu
+pub type MenuEntry = (i64, String, String);
+
+// One board line for a project item node, with its select menu entry (number, title, repo).
+// Drafts get a line but no menu entry; items without content (e.g. redacted) get nothing.
+pub fn project_item_line(item: &serde_json::Value) -> Option<(String, Option<MenuEntry>)> {
+    let content = item.get("content")?;
+    let title = content.get("title").and_then(|t| t.as_str());
+    let Some(number) = content.get("number").and_then(|n| n.as_i64()) else {
+        return title.map(|draft| (format!("<:issue_draft:1458883679777460434> **Draft:** {}", draft), None));
+    };
+    let title = title.unwrap_or("?");
+    let repo = content.get("repository").and_then(|r| r.get("name")).and_then(|n| n.as_str()).unwrap_or("?");
+    let url = content.get("url").and_then(|u| u.as_str());
+    let state = content.get("state").and_then(|s| s.as_str()).unwrap_or("");
+    let line = format!("{} **{}/{}** {}", state_icon(state), repo, md_link(&format!("#{}", number), url), title);
+    Some((line, Some((number, title.to_string(), repo.to_string()))))
+}
+
+// --- Helper: Read Field Value ---
+// Display value of a named field from an item node's fieldValues, None when unset
+pub fn item_field_value(item_node: &serde_json::Value, field_name: &str) -> Option<String> {
+    let field_values = item_node.get("fieldValues").and_then(|fv| fv.get("nodes")).and_then(|n| n.as_array())?;
+    for fv in field_values {
+        let name = fv.get("field").and_then(|f| f.get("name")).and_then(|n| n.as_str()).unwrap_or("");
+        if !name.eq_ignore_ascii_case(field_name) { continue; }
+
+        if let Some(t) = fv.get("text").and_then(|s| s.as_str()) { return Some(t.to_string()); }
+        if let Some(n) = fv.get("name").and_then(|s| s.as_str()) { return Some(n.to_string()); }
+        if let Some(d) = fv.get("date").and_then(|s| s.as_str()) { return Some(d.to_string()); }
+        if let Some(n) = fv.get("number").and_then(|f| f.as_f64()) { return Some(n.to_string()); }
+        if let Some(t) = fv.get("title").and_then(|s| s.as_str()) { return Some(t.to_string()); }
+    }
+    None
+}
+
+// --- Helper: Truncate Text ---
+// Longest prefix of at most `max_bytes` bytes that doesn't split a char
+pub fn truncate_to(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut cut = max_bytes;
+    while !text.is_char_boundary(cut) { cut -= 1; }
+    &text[..cut]
+}
+
+// --- Helper: Split Long Text ---
+// Splits text into chunks of at most `max` bytes, preferring line breaks and never splitting a char
+pub fn chunk_text(text: &str, max: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while rest.len() > max {
+        let mut cut = max;
+        while !rest.is_char_boundary(cut) { cut -= 1; }
+        if let Some(nl) = rest[..cut].rfind('\n') {
+            if nl > 0 { cut = nl + 1; }
+        }
+        chunks.push(rest[..cut].to_string());
+        rest = &rest[cut..];
+    }
+    if !rest.is_empty() {
+        chunks.push(rest.to_string());
+    }
+    chunks
+}
+
+// "Show more" button for items whose body is cut off in build_item_embed
+pub fn body_more_button(item_node: &serde_json::Value) -> Option<serenity::CreateButton> {
+    let content = item_node.get("content")?;
+    let body = content.get("body").and_then(|b| b.as_str())?;
+    if body.len() <= 1000 { return None; }
+    let repo = content.get("repository").and_then(|r| r.get("name")).and_then(|n| n.as_str())?;
+    let num = content.get("number").and_then(|n| n.as_i64())?;
+    Some(serenity::CreateButton::new(CustomId::BodyMore { repo: repo.to_string(), num: num as u64 }.to_string())
+        .label("📄 Show more")
+        .style(serenity::ButtonStyle::Secondary))
 }
 
 // --- Helper: Build Item Embed ---
 pub fn build_item_embed(
     item_node: &serde_json::Value,
     target_num: i64, 
+    field_emoji: &std::collections::HashMap<String, String>,
+    users: &[CachedUser],
+    due_soon_days: i64,
 ) -> Option<serenity::CreateEmbed> {
     let content = item_node.get("content")?;
     let num = content.get("number").and_then(|n| n.as_i64())?;
@@ -29,7 +438,7 @@ pub fn build_item_embed(
     let title = content.get("title").and_then(|t| t.as_str()).unwrap_or("?");
     let body = content.get("body").and_then(|b| b.as_str()).unwrap_or("");
     let repo = content.get("repository").and_then(|r| r.get("name")).and_then(|n| n.as_str()).unwrap_or("?");
-    let url = content.get("url").and_then(|u| u.as_str()).unwrap_or("");
+    let url = usable_url(content.get("url").and_then(|u| u.as_str()));
     let state = content.get("state").and_then(|s| s.as_str()).unwrap_or("");
     
     let assignees: Vec<String> = content.get("assignees")
@@ -59,14 +468,88 @@ pub fn build_item_embed(
 
     let mut embed = serenity::CreateEmbed::new()
         .title(format!("{} {} #{} {}", title_icon, repo, num, title))
-        .url(url)
-        .description(if body.len() > 1000 { format!("{}...", &body[..1000]) } else { body.to_string() })
+        .description(if body.len() > 1000 { format!("{}...", truncate_to(body, 1000)) } else { body.to_string() })
         .field("State", state, true)
         .field("Assignees", if assignees.is_empty() { "None".to_string() } else { assignees.join(", ") }, true)
         .field("Labels", if labels.is_empty() { "None".to_string() } else { labels.join(", ") }, true)
         .color(color);
+    if let Some(url) = url {
+        embed = embed.url(url);
+    }
+    // Primary assignee as the embed author, with their cached avatar
+    if let Some(login) = assignees.first() {
+        let mut author = serenity::CreateEmbedAuthor::new(login).url(format!("https://github.com/{}", login));
+        if let Some(user) = users.iter().find(|u| u.login.eq_ignore_ascii_case(login)) {
+            author = author.icon_url(&user.avatar_url);
+        }
+        embed = embed.author(author);
+    }
+
+    // Sub-issues as a checklist, only for issues that have any
+    let sub_total = content.get("subIssuesSummary").and_then(|s| s.get("total")).and_then(|n| n.as_u64()).unwrap_or(0);
+    if sub_total > 0 {
+        let completed = content.get("subIssuesSummary").and_then(|s| s.get("completed")).and_then(|n| n.as_u64()).unwrap_or(0);
+        let mut lines: Vec<String> = content.get("subIssues")
+            .and_then(|s| s.get("nodes"))
+            .and_then(|n| n.as_array())
+            .map(|arr| arr.iter().map(|sub| {
+                let done = sub.get("state").and_then(|s| s.as_str()) == Some("CLOSED");
+                format!("{} #{} {}",
+                    if done { "☑️" } else { "⬜" },
+                    sub.get("number").and_then(|n| n.as_i64()).unwrap_or(0),
+                    sub.get("title").and_then(|t| t.as_str()).unwrap_or("?").chars().take(60).collect::<String>())
+            }).collect())
+            .unwrap_or_default();
+        if sub_total as usize > lines.len() {
+            lines.push(format!("…and {} more", sub_total as usize - lines.len()));
+        }
+        embed = embed.field(format!("Sub-issues ({}/{} done)", completed, sub_total), lines.join("\n"), false);
+    }
 
-    // Parse custom fields from fieldValues
+    // PRs that will close (or closed) this issue; omitted when there are none
+    let pr_total = content.get("closedByPullRequestsReferences").and_then(|p| p.get("totalCount")).and_then(|n| n.as_u64()).unwrap_or(0);
+    if pr_total > 0 {
+        let mut lines: Vec<String> = content.get("closedByPullRequestsReferences")
+            .and_then(|p| p.get("nodes"))
+            .and_then(|n| n.as_array())
+            .map(|arr| arr.iter().map(|pr| {
+                let icon = match pr.get("state").and_then(|s| s.as_str()) {
+                    Some("MERGED") => "<:pr_merged:1458877132414517360>",
+                    Some("CLOSED") => "🔴",
+                    _ => "🟢",
+                };
+                let pr_repo = pr.get("repository").and_then(|r| r.get("name")).and_then(|n| n.as_str()).unwrap_or("?");
+                let number = pr.get("number").and_then(|n| n.as_i64()).unwrap_or(0);
+                let pr_url = usable_url(pr.get("url").and_then(|u| u.as_str()));
+                let pr_title: String = pr.get("title").and_then(|t| t.as_str()).unwrap_or("?").chars().take(60).collect();
+                format!("{} {} {}", icon, md_link(&format!("{}#{}", pr_repo, number), pr_url), pr_title)
+            }).collect())
+            .unwrap_or_default();
+        if pr_total as usize > lines.len() {
+            lines.push(format!("…and {} more", pr_total as usize - lines.len()));
+        }
+        embed = embed.field("Linked PRs", lines.join("\n"), false);
+    }
+
+    // Parse custom fields from fieldValues; open items with a Date field in the past or within
+    // `due_soon_days` get a marker and tint the embed
+    let is_open = state == "OPEN";
+    let mut worst_due: Option<DueStatus> = None;
+    if let Some(milestone) = content.get("milestone").filter(|m| !m.is_null()) {
+        let name = milestone.get("title").and_then(|t| t.as_str()).unwrap_or("?");
+        // dueOn is a full timestamp, the date part is what GitHub shows
+        let value = match milestone.get("dueOn").and_then(|d| d.as_str()).and_then(|d| d.get(..10)) {
+            Some(due) => match due_status(due, due_soon_days).filter(|_| is_open) {
+                Some(status) => {
+                    worst_due = worst_due.max(Some(status));
+                    format!("{} (due {} {})", name, status.emoji(), due)
+                }
+                None => format!("{} (due {})", name, due),
+            },
+            None => name.to_string(),
+        };
+        embed = embed.field("🎯 Milestone", value, true);
+    }
     if let Some(field_values) = item_node.get("fieldValues").and_then(|fv| fv.get("nodes")).and_then(|n| n.as_array()) {
         for fv in field_values {
             let field_name = fv.get("field").and_then(|f| f.get("name")).and_then(|n| n.as_str());
@@ -82,19 +565,419 @@ pub fn build_item_embed(
                 } else if let Some(name) = fv.get("name").and_then(|n| n.as_str()) {
                     name.to_string() // Single select option name
                 } else if let Some(date) = fv.get("date").and_then(|d| d.as_str()) {
-                    date.to_string()
+                    match due_status(date, due_soon_days).filter(|_| is_open) {
+                        Some(status) => {
+                            worst_due = worst_due.max(Some(status));
+                            format!("{} {}", status.emoji(), date)
+                        }
+                        None => date.to_string(),
+                    }
                 } else if let Some(num) = fv.get("number").and_then(|n| n.as_f64()) {
                     num.to_string()
+                } else if let Some(iteration) = fv.get("title").and_then(|t| t.as_str()) {
+                    // Iteration (sprint), with its date range when known
+                    match iteration_range(fv) {
+                        Some(range) => format!("{} ({})", iteration, range),
+                        None => iteration.to_string(),
+                    }
                 } else {
                     continue; // Skip unknown or empty types
                 };
                 
                 if !value_str.is_empty() {
-                    embed = embed.field(name, value_str, true);
+                    let label = match field_emoji.get(&name.to_lowercase()) {
+                        Some(emoji) => format!("{} {}", emoji, name),
+                        None => name.to_string(),
+                    };
+                    embed = embed.field(label, value_str, true);
                 }
             }
         }
     }
+    if let Some(status) = worst_due {
+        embed = embed.color(status.color());
+    }
 
     Some(embed)
-}
\ No newline at end of file
+}
+
+// "Jan 5 – Jan 18" from an iteration value's startDate and duration (days)
+fn iteration_range(fv: &serde_json::Value) -> Option<String> {
+    let start = fv.get("startDate").and_then(|d| d.as_str())
+        .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())?;
+    let days = fv.get("duration").and_then(|d| d.as_i64()).filter(|d| *d > 0)?;
+    let end = start + chrono::Duration::days(days - 1);
+    Some(format!("{} – {}", start.format("%b %-d"), end.format("%b %-d")))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum DueStatus {
+    Soon,
+    Overdue,
+}
+
+impl DueStatus {
+    fn emoji(self) -> &'static str {
+        match self {
+            DueStatus::Soon => "🟡",
+            DueStatus::Overdue => "🔴",
+        }
+    }
+
+    fn color(self) -> u32 {
+        match self {
+            DueStatus::Soon => 0xFEE75C, // Yellow
+            DueStatus::Overdue => 0xED4245, // Red
+        }
+    }
+}
+
+// Where a YYYY-MM-DD date stands relative to today (UTC); None when it's further out or unparsable
+fn due_status(date: &str, soon_days: i64) -> Option<DueStatus> {
+    let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    let days_left = (date - chrono::Utc::now().date_naive()).num_days();
+    if days_left < 0 {
+        Some(DueStatus::Overdue)
+    } else if days_left <= soon_days {
+        Some(DueStatus::Soon)
+    } else {
+        None
+    }
+}
+
+// --- Helper: Build Issue Embed ---
+// Labels that belong to the guild's taxonomy are shown as their own fields (e.g. "Priority: P1")
+pub fn build_issue_embed(repo: &str, issue: &octocrab::models::issues::Issue, taxonomy: &[LabelCategory]) -> serenity::CreateEmbed {
+    let labels: Vec<String> = issue.labels.iter().map(|l| l.name.clone()).collect();
+    let other_labels: Vec<String> = labels.iter()
+        .filter(|l| !taxonomy.iter().any(|c| c.contains(l)))
+        .cloned()
+        .collect();
+    let assignees: Vec<String> = issue.assignees.iter().map(|a| a.login.clone()).collect();
+    let body = issue.body.as_deref().unwrap_or("");
+    let open = matches!(issue.state, octocrab::models::IssueState::Open);
+
+    let state = if open { "OPEN" } else { "CLOSED" };
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("{} {} #{} {}", state_icon(state), repo, issue.number, issue.title))
+        .url(issue.html_url.to_string())
+        .description(if body.chars().count() > 1000 { format!("{}...", body.chars().take(1000).collect::<String>()) } else { body.to_string() })
+        .author(serenity::CreateEmbedAuthor::new(&issue.user.login).url(issue.user.html_url.to_string()).icon_url(issue.user.avatar_url.to_string()))
+        .field("State", state, true)
+        .field("Assignees", if assignees.is_empty() { "None".to_string() } else { assignees.join(", ") }, true)
+        .field("Labels", if other_labels.is_empty() { "None".to_string() } else { other_labels.join(", ") }, true)
+        .field("💬 Comments", issue.comments.to_string(), true)
+        .color(if open { 0x57F287 } else { 0x95A5A6 });
+
+    for category in taxonomy {
+        embed = embed.field(&category.name, category.value_of(&labels).unwrap_or_else(|| "—".to_string()), true);
+    }
+    embed
+}
+
+// --- Helper: Build PR Embed ---
+// `reviews` in submission order; each reviewer's latest verdict wins
+pub fn build_pr_embed(repo: &str, pr: &octocrab::models::pulls::PullRequest, reviews: &[octocrab::models::pulls::Review]) -> serenity::CreateEmbed {
+    use octocrab::models::pulls::{MergeableState, ReviewState};
+
+    let merged = pr.merged_at.is_some();
+    let open = matches!(pr.state, Some(octocrab::models::IssueState::Open));
+    let draft = pr.draft.unwrap_or(false);
+    let (state, color) = match (merged, open, draft) {
+        (true, _, _) => ("MERGED", 0x8957E5),
+        (_, true, true) => ("DRAFT", 0x95A5A6),
+        (_, true, false) => ("OPEN", 0x57F287),
+        _ => ("CLOSED", 0xED4245),
+    };
+
+    // GitHub computes mergeability in the background, None means it isn't known yet
+    let mergeable = match (pr.mergeable, &pr.mergeable_state) {
+        _ if !open => "—".to_string(),
+        (_, Some(MergeableState::Clean)) | (_, Some(MergeableState::HasHooks)) => "✅ Ready to merge".to_string(),
+        (_, Some(MergeableState::Unstable)) => "⚠️ Mergeable, checks failing".to_string(),
+        (_, Some(MergeableState::Behind)) => "⚠️ Behind the base branch".to_string(),
+        (_, Some(MergeableState::Blocked)) => "⛔ Blocked (reviews or checks required)".to_string(),
+        (_, Some(MergeableState::Dirty)) | (Some(false), _) => "❌ Has conflicts".to_string(),
+        (_, Some(MergeableState::Draft)) => "📝 Draft".to_string(),
+        _ => "❔ Not computed yet".to_string(),
+    };
+
+    let mut verdicts: Vec<(String, ReviewState)> = Vec::new();
+    for review in reviews {
+        let (Some(user), Some(state)) = (&review.user, review.state) else { continue; };
+        match verdicts.iter_mut().find(|(login, _)| *login == user.login) {
+            // A later comment doesn't undo an approval or change request
+            Some(_) if state == ReviewState::Commented => {}
+            Some((_, prev)) => *prev = state,
+            None => verdicts.push((user.login.clone(), state)),
+        }
+    }
+    let mut review_lines: Vec<String> = verdicts.iter().map(|(login, state)| {
+        let icon = match state {
+            ReviewState::Approved => "✅",
+            ReviewState::ChangesRequested => "🔁",
+            ReviewState::Dismissed => "🚫",
+            _ => "💬",
+        };
+        format!("{} {}", icon, login)
+    }).collect();
+    for requested in pr.requested_reviewers.iter().flatten() {
+        review_lines.push(format!("⏳ {}", requested.login));
+    }
+
+    let changes = match (pr.changed_files, pr.additions, pr.deletions) {
+        (Some(files), Some(add), Some(del)) => format!("{} files (+{} −{})", files, add, del),
+        (Some(files), _, _) => format!("{} files", files),
+        _ => "?".to_string(),
+    };
+    let body = pr.body.as_deref().unwrap_or("");
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("{} #{} {}", repo, pr.number, pr.title.as_deref().unwrap_or("")))
+        .description(if body.chars().count() > 1000 { format!("{}...", body.chars().take(1000).collect::<String>()) } else { body.to_string() })
+        .field("State", state, true)
+        .field("Branches", format!("`{}` → `{}`", pr.head.ref_field, pr.base.ref_field), true)
+        .field("Changes", changes, true)
+        .field("Mergeable", mergeable, true)
+        .field("Reviews", if review_lines.is_empty() { "None".to_string() } else { review_lines.join("\n") }, true)
+        .color(color);
+    if let Some(url) = &pr.html_url {
+        embed = embed.url(url.to_string());
+    }
+    if let Some(author) = &pr.user {
+        embed = embed.footer(serenity::CreateEmbedFooter::new(format!("Opened by {}", author.login)));
+    }
+    embed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logins_accept_github_usernames() {
+        for name in ["octocat", "a", "A1", "octo-cat", "a-b-c", &"a".repeat(39)] {
+            assert!(is_valid_login(name), "{} should be valid", name);
+        }
+    }
+
+    #[test]
+    fn logins_reject_malicious_input() {
+        for name in [
+            "",
+            "-octocat",
+            "octocat-",
+            "octo--cat",
+            &"a".repeat(40),
+            "../octocat",
+            "octocat/../../orgs",
+            "octocat\") { viewer { login } }",
+            "octocat\" org:evil",
+            "octo cat",
+            "octocat\n",
+            "ôctocat",
+            "octocat😀",
+            "octo_cat",
+        ] {
+            assert!(!is_valid_login(name), "{:?} should be rejected", name);
+        }
+    }
+
+    #[test]
+    fn repo_names_accept_github_repo_names() {
+        for name in ["api", "my_cool_repo", "repo.rs", ".github", "a-b_c.d", &"r".repeat(100), "acme/api", "acme-labs/.github"] {
+            assert!(is_valid_repo_name(name), "{} should be valid", name);
+        }
+    }
+
+    #[test]
+    fn repo_names_reject_malicious_input() {
+        for name in [
+            "",
+            ".",
+            "..",
+            "../secrets",
+            "api/../../admin",
+            "acme/api/issues",
+            "acme/..",
+            "/api",
+            "acme/",
+            "-acme/api",
+            &"r".repeat(101),
+            "repo\") { id }",
+            "repo\" is:private",
+            "re po",
+            "repo\0",
+            "répo",
+            "repo😀",
+        ] {
+            assert!(!is_valid_repo_name(name), "{:?} should be rejected", name);
+        }
+    }
+
+    #[test]
+    fn repo_name_strips_the_owner() {
+        assert_eq!(repo_name("acme/api"), "api");
+        assert_eq!(repo_name("api"), "api");
+    }
+
+    fn author_json() -> serde_json::Value {
+        let api = "https://api.github.com/users/octocat";
+        serde_json::json!({
+            "login": "octocat", "id": 1, "node_id": "MDQ6VXNlcjE=", "gravatar_id": "", "type": "User", "site_admin": false,
+            "avatar_url": "https://avatars.githubusercontent.com/u/1", "url": api, "html_url": "https://github.com/octocat",
+            "followers_url": api, "following_url": api, "gists_url": api, "starred_url": api, "subscriptions_url": api,
+            "organizations_url": api, "repos_url": api, "events_url": api, "received_events_url": api
+        })
+    }
+
+    fn issue_json() -> serde_json::Value {
+        let api = "https://api.github.com/repos/acme/api/issues/12";
+        serde_json::json!({
+            "id": 1, "node_id": "I_kw1", "url": api, "repository_url": api, "labels_url": api, "comments_url": api, "events_url": api,
+            "html_url": "https://github.com/acme/api/issues/12",
+            "number": 12, "state": "open", "state_reason": null, "title": "Crash on login", "body": null,
+            "user": author_json(), "labels": [], "assignees": [], "author_association": "MEMBER", "locked": false, "comments": 2,
+            "created_at": "2025-01-01T00:00:00Z", "updated_at": "2025-01-02T00:00:00Z"
+        })
+    }
+
+    #[test]
+    fn issue_embed_links_html_url() {
+        let issue: octocrab::models::issues::Issue = serde_json::from_value(issue_json()).unwrap();
+        let embed = serde_json::to_value(build_issue_embed("api", &issue, &[])).unwrap();
+        assert_eq!(embed["url"], "https://github.com/acme/api/issues/12");
+    }
+
+    #[test]
+    fn issue_lacking_html_url() {
+        // octocrab requires html_url, so a REST issue without one fails to deserialize and never
+        // reaches build_issue_embed as "https://None"
+        let mut json = issue_json();
+        json.as_object_mut().unwrap().remove("html_url");
+        assert!(serde_json::from_value::<octocrab::models::issues::Issue>(json.clone()).is_err());
+        json["html_url"] = serde_json::Value::Null;
+        assert!(serde_json::from_value::<octocrab::models::issues::Issue>(json).is_err());
+
+        // The same issue as a GraphQL item node may lack its url; the embed then has no link
+        let item = serde_json::json!({ "content": { "title": "Crash on login", "number": 12, "state": "OPEN", "repository": { "name": "api" } } });
+        let embed = serde_json::to_value(build_item_embed(&item, 12, &Default::default(), &[], 3).unwrap()).unwrap();
+        assert!(embed.get("url").is_none_or(|u| u.is_null()), "{}", embed);
+    }
+
+    #[test]
+    fn links_only_use_http_urls() {
+        assert_eq!(md_link("#12", Some("https://github.com/acme/api/issues/12")), "[#12](https://github.com/acme/api/issues/12)");
+        for url in [None, Some(""), Some("None"), Some("javascript:alert(1)"), Some("github.com/acme")] {
+            assert_eq!(usable_url(url), None);
+            assert_eq!(md_link("#12", url), "#12");
+        }
+    }
+
+    fn target(input: &str) -> TargetArgs {
+        parse_target_args(input).unwrap_or_else(|e| panic!("{:?} failed: {}", input, e))
+    }
+
+    #[test]
+    fn target_durations() {
+        assert_eq!(target("+3d"), TargetArgs { date: Some(TargetDate::Days(3)), milestone: None });
+        assert_eq!(target("+1w"), TargetArgs { date: Some(TargetDate::Days(7)), milestone: None });
+        assert_eq!(target("+2m"), TargetArgs { date: Some(TargetDate::Months(2)), milestone: None });
+        assert_eq!(target("+999d").date, Some(TargetDate::Days(999)));
+    }
+
+    #[test]
+    fn target_dates() {
+        let june = chrono::NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        assert_eq!(target("2025-06-01"), TargetArgs { date: Some(TargetDate::On(june)), milestone: None });
+        assert_eq!(target("  2025-06-01  ").date, Some(TargetDate::On(june)));
+    }
+
+    #[test]
+    fn target_milestones() {
+        assert_eq!(target("v2.0"), TargetArgs { date: None, milestone: Some("v2.0".into()) });
+        assert_eq!(target("Q3  launch"), TargetArgs { date: None, milestone: Some("Q3 launch".into()) });
+        assert_eq!(target("v2.0 +1w"), TargetArgs { date: Some(TargetDate::Days(7)), milestone: Some("v2.0".into()) });
+        let sept = chrono::NaiveDate::from_ymd_opt(2025, 9, 30).unwrap();
+        assert_eq!(target("Q3 launch 2025-09-30"), TargetArgs { date: Some(TargetDate::On(sept)), milestone: Some("Q3 launch".into()) });
+        // Not a real date, so it's part of the name
+        assert_eq!(target("2025-13-45"), TargetArgs { date: None, milestone: Some("2025-13-45".into()) });
+    }
+
+    #[test]
+    fn target_rejects_garbage() {
+        for input in ["", "   ", "+", "+d", "+0d", "+1000d", "+3x", "+3é", "+-3d", "+3", "+1.5w", "+😀", "+1w +2d", "2025-06-01 +1w", "v1 +3y"] {
+            assert!(parse_target_args(input).is_err(), "{:?} should be rejected", input);
+        }
+    }
+
+    #[test]
+    fn truncate_keeps_emoji_whole() {
+        // "ab" + 4-byte emoji + "c": every cut inside the emoji falls back to before it
+        let text = "ab😀c";
+        assert_eq!(truncate_to(text, 2), "ab");
+        for max in 3..=5 {
+            assert_eq!(truncate_to(text, max), "ab", "max_bytes {}", max);
+        }
+        assert_eq!(truncate_to(text, 6), "ab😀");
+        // 2-byte and 3-byte chars straddling the boundary
+        assert_eq!(truncate_to("aé", 2), "a");
+        assert_eq!(truncate_to("a€b", 3), "a");
+        assert_eq!(truncate_to("👍🏽ok", 5), "👍");
+    }
+
+    #[test]
+    fn truncate_edge_lengths() {
+        assert_eq!(truncate_to("😀", 0), "");
+        assert_eq!(truncate_to("abc", 0), "");
+        assert_eq!(truncate_to("", 0), "");
+        assert_eq!(truncate_to("ab😀c", 7), "ab😀c");
+        assert_eq!(truncate_to("ab😀c", 100), "ab😀c");
+    }
+
+    // Nodes as fetch_project_items returns them for a board
+    fn sample_board() -> Vec<serde_json::Value> {
+        serde_json::from_value(serde_json::json!([
+            { "id": "PVTI_1", "content": { "title": "Crash on login", "number": 12, "url": "https://github.com/acme/api/issues/12", "state": "OPEN", "repository": { "name": "api" } } },
+            { "id": "PVTI_2", "content": { "title": "Fix login", "number": 13, "url": "https://github.com/acme/api/pull/13", "state": "MERGED", "repository": { "name": "api" } } },
+            { "id": "PVTI_3", "content": { "title": "Old bug", "number": 3, "url": "https://github.com/acme/web/issues/3", "state": "CLOSED", "repository": { "name": "web" } } },
+            { "id": "PVTI_4", "content": { "title": "Write the docs" } },
+            { "id": "PVTI_5", "content": null },
+            { "id": "PVTI_6" }
+        ])).unwrap()
+    }
+
+    #[test]
+    fn project_item_lines_for_issues_and_prs() {
+        let board = sample_board();
+        let (line, menu) = project_item_line(&board[0]).unwrap();
+        assert_eq!(line, "<:issue:1458877117176742065> **api/[#12](https://github.com/acme/api/issues/12)** Crash on login");
+        assert_eq!(menu, Some((12, "Crash on login".to_string(), "api".to_string())));
+
+        let (line, menu) = project_item_line(&board[1]).unwrap();
+        assert!(line.starts_with("<:pr_merged:1458877132414517360> **api/[#13]"), "{}", line);
+        assert_eq!(menu, Some((13, "Fix login".to_string(), "api".to_string())));
+
+        let (line, _) = project_item_line(&board[2]).unwrap();
+        assert!(line.starts_with("<:issue_neutral:1458877524015579209> **web/"), "{}", line);
+    }
+
+    #[test]
+    fn project_item_lines_for_drafts_and_missing_content() {
+        let board = sample_board();
+        assert_eq!(project_item_line(&board[3]), Some(("<:issue_draft:1458883679777460434> **Draft:** Write the docs".to_string(), None)));
+        assert_eq!(project_item_line(&board[4]), None);
+        assert_eq!(project_item_line(&board[5]), None);
+
+        // No usable url: the number isn't linked; unknown state: the neutral circle
+        let item = serde_json::json!({ "content": { "title": "Hidden", "number": 7, "state": "WEIRD", "repository": { "name": "api" } } });
+        assert_eq!(project_item_line(&item).unwrap().0, "⚪ **api/#7** Hidden");
+    }
+
+    #[test]
+    fn project_item_menu_entries_skip_drafts() {
+        let menu: Vec<MenuEntry> = sample_board().iter().filter_map(project_item_line).filter_map(|(_, m)| m).collect();
+        assert_eq!(menu.iter().map(|(n, _, r)| format!("{}#{}", r, n)).collect::<Vec<_>>(), ["api#12", "api#13", "web#3"]);
+    }
+}
@@ -0,0 +1,177 @@
+use poise::serenity_prelude as serenity;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use axum::{Router, routing::post, extract::State, http::{HeaderMap, StatusCode}, body::Bytes};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use crate::github::graphql;
+use crate::utils::usable_url;
+use crate::types::{BotState, CachedItem};
+use tracing::{info, warn};
+
+#[derive(Clone)]
+struct WebhookState {
+    bot: Arc<BotState>,
+    http: Arc<serenity::Http>,
+}
+
+// Serves the GitHub webhook endpoint (POST /github) until the process exits
+pub async fn serve(addr: SocketAddr, bot: Arc<BotState>, http: Arc<serenity::Http>) {
+    let app = Router::new()
+        .route("/github", post(github_webhook))
+        .with_state(WebhookState { bot, http });
+
+    match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => {
+            info!("🌐 Webhook endpoint listening on {}", addr);
+            if let Err(e) = axum::serve(listener, app).await {
+                warn!("⚠️ Webhook server stopped: {}", e);
+            }
+        }
+        Err(e) => warn!("⚠️ Failed to bind webhook endpoint on {}: {}", addr, e),
+    }
+}
+
+async fn github_webhook(State(app): State<WebhookState>, headers: HeaderMap, body: Bytes) -> StatusCode {
+    // Unsigned deliveries could post fake activity and patch the cache, so never accept them
+    let Some(secret) = &app.bot.webhook_secret else { return StatusCode::UNAUTHORIZED; };
+    let signature = headers.get("X-Hub-Signature-256").and_then(|v| v.to_str().ok()).unwrap_or("");
+    if !verify_signature(secret, &body, signature) {
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let event = headers.get("X-GitHub-Event").and_then(|v| v.to_str().ok()).unwrap_or("").to_string();
+    let payload: serde_json::Value = match serde_json::from_slice(&body) {
+        Ok(p) => p,
+        Err(_) => return StatusCode::BAD_REQUEST,
+    };
+
+    // Acknowledge right away, GitHub gives up on deliveries after 10s
+    if event == "projects_v2_item" {
+        tokio::spawn(async move { handle_project_item(&app, &payload).await });
+    }
+    StatusCode::OK
+}
+
+// Checks GitHub's "sha256=<hex hmac>" signature header
+fn verify_signature(secret: &str, body: &[u8], header: &str) -> bool {
+    let Some(hex_sig) = header.strip_prefix("sha256=") else { return false; };
+    let Ok(expected) = hex::decode(hex_sig) else { return false; };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else { return false; };
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+async fn handle_project_item(app: &WebhookState, payload: &serde_json::Value) {
+    let state = &app.bot;
+    let action = payload.get("action").and_then(|a| a.as_str()).unwrap_or("");
+    let item = payload.get("projects_v2_item");
+    let project_id = item.and_then(|i| i.get("project_node_id")).and_then(|s| s.as_str()).unwrap_or("");
+    let content_id = item.and_then(|i| i.get("content_node_id")).and_then(|s| s.as_str()).unwrap_or("");
+    let sender = payload.get("sender").and_then(|s| s.get("login")).and_then(|l| l.as_str()).unwrap_or("someone");
+
+    // Only report boards we know about (i.e. the configured org's projects)
+    let project_title = {
+        let projects = state.projects.read().await;
+        match projects.iter().find(|p| p.id == project_id) {
+            Some(p) => p.title.clone(),
+            None => return,
+        }
+    };
+
+    // The payload only carries node ids, resolve the issue/PR/draft behind the item
+    let query = serde_json::json!({
+        "query": r#"
+            query($id: ID!) {
+                node(id: $id) {
+                    ... on Issue { title number url state repository { name } assignees(first: 5) { nodes { login } } }
+                    ... on PullRequest { title number url state repository { name } assignees(first: 5) { nodes { login } } }
+                    ... on DraftIssue { title }
+                }
+            }
+        "#,
+        "variables": { "id": content_id }
+    });
+    let content = graphql(state, "webhook", &query).await.ok()
+        .and_then(|r| r.get("data").and_then(|d| d.get("node")).cloned())
+        .unwrap_or(serde_json::Value::Null);
+
+    let title = content.get("title").and_then(|t| t.as_str()).unwrap_or("an item");
+    let number = content.get("number").and_then(|n| n.as_i64());
+    let repo = content.get("repository").and_then(|r| r.get("name")).and_then(|n| n.as_str());
+    let url = usable_url(content.get("url").and_then(|u| u.as_str()));
+    let item_label = match (number, url) {
+        (Some(n), Some(u)) => format!("[#{}]({}) {}", n, u, title),
+        (Some(n), None) => format!("#{} {}", n, title),
+        _ => format!("draft **{}**", title),
+    };
+
+    // Mention the Discord user when the GitHub login is linked and they haven't snoozed the bot
+    let linked = {
+        let mapping = state.user_mapping.read().await;
+        mapping.map.iter().find(|(_, login)| login.eq_ignore_ascii_case(sender)).map(|(id, _)| *id)
+    };
+    let actor = match linked {
+        Some(discord_id) if !state.snoozes.read().await.is_snoozed(discord_id) => format!("<@{}>", discord_id),
+        _ => format!("**{}**", sender),
+    };
+
+    let description = match action {
+        "created" => format!("{} added {} to **{}**", actor, item_label, project_title),
+        "deleted" => format!("{} removed {} from **{}**", actor, item_label, project_title),
+        "edited" => {
+            let change = payload.get("changes").and_then(|c| c.get("field_value"));
+            let field = change.and_then(|c| c.get("field_name")).and_then(|f| f.as_str()).unwrap_or("a field");
+            match change.and_then(|c| c.get("to")).and_then(change_value_text) {
+                Some(to) if field.eq_ignore_ascii_case("Status") => format!("{} moved {} to **{}**", actor, item_label, to),
+                Some(to) => format!("{} set **{}** on {} to **{}**", actor, field, item_label, to),
+                None => format!("{} cleared **{}** on {}", actor, field, item_label),
+            }
+        }
+        other => format!("{} {} {} in **{}**", actor, other, item_label, project_title),
+    };
+
+    // Keep autocomplete in sync without a full refresh
+    if let (Some(num), Some(repo_name)) = (number, repo) {
+        let mut projects = state.projects.write().await;
+        if let Some(p) = projects.iter_mut().find(|p| p.id == project_id) {
+            match action {
+                "created" | "restored" if !p.items.iter().any(|i| i.number == num && i.repo_name == repo_name) => {
+                    p.items.push(CachedItem {
+                        id: item.and_then(|i| i.get("node_id")).and_then(|s| s.as_str()).unwrap_or("").to_string(),
+                        title: title.to_string(),
+                        number: num,
+                        repo_name: repo_name.to_string(),
+                        state: content.get("state").and_then(|s| s.as_str()).unwrap_or("OPEN").to_string(),
+                        assignees: content.get("assignees").and_then(|a| a.get("nodes")).and_then(|n| n.as_array())
+                            .map(|arr| arr.iter().filter_map(|a| a.get("login").and_then(|l| l.as_str()).map(|l| l.to_string())).collect())
+                            .unwrap_or_default(),
+                    });
+                }
+                "deleted" | "archived" => p.items.retain(|i| !(i.number == num && i.repo_name == repo_name)),
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(channel) = state.activity_channel {
+        let embed = serenity::CreateEmbed::new()
+            .description(description)
+            .footer(serenity::CreateEmbedFooter::new(project_title))
+            .color(0xEB459E)
+            .timestamp(serenity::Timestamp::now());
+        if let Err(e) = channel.send_message(&app.http, serenity::CreateMessage::new().embed(embed)).await {
+            warn!("⚠️ Failed to post project activity: {}", e);
+        }
+    }
+}
+
+// Field values in `changes.field_value.to` come as strings, numbers, or objects ({name}, {title})
+fn change_value_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Object(o) => o.get("name").or_else(|| o.get("title")).and_then(|v| v.as_str()).map(|s| s.to_string()),
+        _ => None,
+    }
+}